@@ -0,0 +1,52 @@
+//! Throughput of decoding long polylines, run with `cargo bench --bench decode`.
+//!
+//! `decode` takes the single pass decoder for well-formed input, `decode_with` always runs the
+//! general decoder, which checks and decodes group by group.
+
+#![allow(clippy::needless_return)]
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use polyline_rust::{Point, DecodeOptions, OddTrailingPolicy, encode, decode, decode_with};
+
+/// Points of the benchmarked polyline.
+const POINTS: usize = 100_000;
+
+/// Minimum time every decoder is measured.
+const MEASUREMENT_TIME: Duration = Duration::from_secs(2);
+
+fn main() {
+    // a winding track with steps from centimeters to kilometers
+    let track: Vec<Point> = (0..POINTS)
+        .map(|i| {
+            let i = i as f64;
+            return Point::new(48.2 + (i * 0.0013).sin() * 0.5 + i * 1e-7, 16.37 + (i * 0.0007).cos() * 0.8);
+        })
+        .collect();
+    let polyline = encode(&track, 6);
+    let options = DecodeOptions::new(6).odd_trailing(OddTrailingPolicy::DropSilently);
+
+    let general = measure("general decoder (decode_with)", &polyline, || decode_with(black_box(&polyline), &options).unwrap());
+    let fast = measure("single pass decoder (decode)", &polyline, || decode(black_box(&polyline), 6));
+    println!("speedup: {:.1}x", general.as_secs_f64() / fast.as_secs_f64());
+}
+
+/// Prints and returns the mean time of one run of `run`.
+fn measure<F>(name: &str, polyline: &str, mut run: F) -> Duration
+    where F: FnMut() -> Vec<Point> {
+    // warm up the caches and the allocator
+    black_box(run());
+
+    let start = Instant::now();
+    let mut runs: u32 = 0;
+    while start.elapsed() < MEASUREMENT_TIME {
+        black_box(run());
+        runs += 1;
+    }
+    let mean = start.elapsed() / runs;
+
+    let throughput = polyline.len() as f64 / mean.as_secs_f64() / 1e6;
+    println!("{:<32} {:>10.3?} per run, {:>8.1} MB/s", name, mean, throughput);
+    return mean;
+}
@@ -0,0 +1,51 @@
+//! Feeds arbitrary bytes to the decoding entry points, none of them may panic.
+//!
+//! Run it with `cargo +nightly fuzz run decode` in the crate directory.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use polyline_rust::{Decoder, DecodeOptions, Point, bytes, decode, decode3, decode_bytes, decode_iter, decode_multi, decode_timed, decode_with,
+                    decode_to_kml, decode_to_wkt, flexible, nearest_polyline, remove_range_encoded, reverse_encoded, concat_encoded,
+                    splice_encoded, try_decode, try_decode_bytes, validate};
+
+fuzz_target!(|data: &[u8]| {
+    // the first byte picks the precision, mostly a supported one, otherwise up to 16^5
+    let (precision, polyline) = match data.split_first() {
+        Some((precision, polyline)) if *precision < 0xf0 => (u32::from(*precision % 10), polyline),
+        Some((precision, polyline)) => ((u32::from(*precision) - 0xef).pow(5), polyline),
+        None => return
+    };
+
+    let _ = decode_bytes(polyline, precision);
+    let _ = try_decode_bytes(polyline, precision);
+    let _ = bytes::from_bytes(polyline);
+
+    let polyline = String::from_utf8_lossy(polyline);
+    let points = decode(&polyline, precision);
+    if let Ok(strict) = try_decode(&polyline, precision) {
+        assert_eq!(strict.len(), points.len());
+    }
+    assert_eq!(decode_iter(&polyline, precision).count(), points.len());
+
+    let mut decoder = Decoder::new(precision);
+    let _ = decoder.feed(&polyline);
+    let _ = decoder.finish();
+
+    let _ = decode_with(&polyline, &DecodeOptions::new(precision).reject_out_of_range());
+    let _ = decode3(&polyline, precision, precision);
+    let _ = decode_timed(&polyline, precision, precision);
+    let _ = decode_multi(&polyline, precision);
+    let _ = validate(&polyline);
+    let _ = flexible::decode(&polyline);
+    let _ = reverse_encoded(&polyline, precision);
+    let _ = concat_encoded(&polyline, &polyline, precision);
+    let _ = nearest_polyline(vec![&polyline, &polyline], precision, &Point::new(1., 2.));
+    let _ = decode_to_wkt(&polyline, precision);
+    let _ = decode_to_kml(&polyline, precision);
+
+    // ranges around the number of points, some of them reversed
+    let end = polyline.len() / 4;
+    let _ = splice_encoded(&polyline, end / 2..end, [Point::new(1., 2.)], precision);
+    let _ = remove_range_encoded(&polyline, precision, end..end / 3);
+});
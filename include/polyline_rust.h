@@ -0,0 +1,74 @@
+#ifndef POLYLINE_RUST_H
+#define POLYLINE_RUST_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Encodes interleaved coordinates to a NUL terminated polyline, see [`encode`].
+
+ `coordinates`: latitude and longitude of every point, one after the other
+
+ `length`: number of coordinates, twice the number of points
+
+ `precision`: usually 5 or 6, see [`encode`]
+
+ Returns `NULL` for an odd number of coordinates. Release the polyline with [`polyline_free`].
+
+ # Safety
+
+ `coordinates` has to point to `length` doubles, it may be `NULL` if `length` is 0.
+ */
+char *polyline_encode(const double *coordinates, size_t length, uint32_t precision);
+
+/*
+ Decodes a NUL terminated polyline into interleaved coordinates, see
+ [`try_decode`](crate::try_decode).
+
+ `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+
+ `precision`: usually 5 or 6, see [`decode`](crate::decode)
+
+ `length`: receives the number of coordinates, twice the number of points
+
+ Returns `NULL` for malformed polylines. Release the coordinates with
+ [`polyline_free_coordinates`].
+
+ # Safety
+
+ `polyline` has to be a NUL terminated string and `length` has to point to a `size_t`.
+ */
+double *polyline_decode(const char *polyline, uint32_t precision, size_t *length);
+
+/*
+ Releases a polyline returned by [`polyline_encode`], `NULL` is ignored.
+
+ # Safety
+
+ `polyline` has to be returned by [`polyline_encode`] and must not be used afterwards.
+ */
+void polyline_free(char *polyline);
+
+/*
+ Releases coordinates returned by [`polyline_decode`], `NULL` is ignored.
+
+ `length`: the number of coordinates returned by [`polyline_decode`]
+
+ # Safety
+
+ `coordinates` has to be returned by [`polyline_decode`] together with `length` and must not
+ be used afterwards.
+ */
+void polyline_free_coordinates(double *coordinates, size_t length);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* POLYLINE_RUST_H */
@@ -0,0 +1,56 @@
+use alloc::vec::Vec;
+
+use crate::{Point, DecodeError, try_decode};
+
+/// Decodes many polylines, malformed strings give an error in their place.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with, see [`decode`](crate::decode)
+///
+/// Returns one result per polyline in input order.
+pub fn decode_batch<S>(polylines: &[S], precision: u32) -> Vec<Result<Vec<Point>, DecodeError>>
+    where S: AsRef<str> {
+    return decode_batch_with_progress(polylines, precision, |_, _| {});
+}
+
+/// Like [`decode_batch`], and calls `progress` after every polyline, e.g. to render a progress
+/// bar for a multi-minute job.
+///
+/// `progress`: called with the number of decoded polylines and the total number of polylines
+///
+/// ```
+/// use polyline_rust::decode_batch_with_progress;
+///
+/// let mut reported = Vec::new();
+/// let decoded = decode_batch_with_progress(&["_p~iF~ps|U", "_p~iF"], 5, |done, total| reported.push((done, total)));
+/// assert!(decoded[0].is_ok() && decoded[1].is_err());
+/// assert_eq!(reported, vec![(1, 2), (2, 2)]);
+/// ```
+pub fn decode_batch_with_progress<S, F>(polylines: &[S], precision: u32, mut progress: F) -> Vec<Result<Vec<Point>, DecodeError>>
+    where S: AsRef<str>, F: FnMut(usize, usize) {
+    let mut decoded: Vec<Result<Vec<Point>, DecodeError>> = Vec::with_capacity(polylines.len());
+    for polyline in polylines.iter() {
+        decoded.push(try_decode(polyline.as_ref(), precision));
+        progress(decoded.len(), polylines.len());
+    }
+
+    return decoded;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, DecodeError, decode_batch};
+
+    #[test]
+    fn results_in_input_order() {
+        let polylines = vec![String::from("_p~iF~ps|U"), String::new(), String::from("_p~iF~ps|U_")];
+        assert_eq!(decode_batch(&polylines, 5), vec![
+            Ok(vec![Point::new(38.5, -120.2)]),
+            Ok(vec![]),
+            Err(DecodeError::DanglingGroup { offset: 10 })
+        ]);
+        assert_eq!(decode_batch::<&str>(&[], 5), vec![]);
+    }
+}
@@ -0,0 +1,340 @@
+//! `polyline convert`: converts files between GPX, CSV, GeoJSON and encoded polylines.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+
+use serde_json::{json, Value};
+
+use polyline_rust::{Point, FormatError, DecodeError, encode, from_geojson, to_csv, to_geojson, try_decode};
+use polyline_rust::gpx::{read_track, to_gpx};
+
+use crate::{Failure, Options, Output, finish};
+
+/// File formats, detected by the file extension.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum Format {
+    /// a single encoded polyline, `.polyline` or `.txt`
+    Polyline,
+    Gpx,
+    /// `latitude,longitude` lines with an optional header
+    Csv,
+    /// a `LineString` or a `Feature` with one, `.geojson` or `.json`
+    GeoJson
+}
+
+impl Format {
+    pub(crate) fn from_name(name: &str) -> Option<Format> {
+        return match name.to_ascii_lowercase().as_str() {
+            "polyline" | "txt" => Some(Format::Polyline),
+            "gpx" => Some(Format::Gpx),
+            "csv" => Some(Format::Csv),
+            "geojson" | "json" => Some(Format::GeoJson),
+            _ => None
+        };
+    }
+
+    fn extension(&self) -> &'static str {
+        return match self {
+            Format::Polyline => "polyline",
+            Format::Gpx => "gpx",
+            Format::Csv => "csv",
+            Format::GeoJson => "geojson"
+        };
+    }
+}
+
+/// Reason a single file couldn't be read or converted.
+#[derive(Debug)]
+pub(crate) enum ConvertError {
+    UnknownFormat,
+    SameFormat,
+    NoMatch,
+    Decode(DecodeError),
+    Format(FormatError),
+    /// line of an invalid CSV row
+    Csv(usize),
+    Io(io::Error)
+}
+
+impl ConvertError {
+    pub(crate) fn kind(&self) -> &'static str {
+        return match self {
+            ConvertError::UnknownFormat => "unknown_format",
+            ConvertError::SameFormat => "same_format",
+            ConvertError::NoMatch => "no_match",
+            ConvertError::Decode(_) => "decode",
+            ConvertError::Format(_) => "format",
+            ConvertError::Csv(_) => "csv",
+            ConvertError::Io(_) => "io"
+        };
+    }
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ConvertError::UnknownFormat => write!(f, "unknown file extension"),
+            ConvertError::SameFormat => write!(f, "the file is in the target format already"),
+            ConvertError::NoMatch => write!(f, "no files match the pattern"),
+            ConvertError::Decode(error) => write!(f, "{}", error),
+            ConvertError::Format(error) => write!(f, "{}", error),
+            ConvertError::Csv(line) => write!(f, "expected latitude,longitude in line {}", line),
+            ConvertError::Io(error) => write!(f, "{}", error)
+        };
+    }
+}
+
+impl From<io::Error> for ConvertError {
+    fn from(error: io::Error) -> ConvertError {
+        return ConvertError::Io(error);
+    }
+}
+
+/// Converts the input files, in parallel on all cores.
+///
+/// Every file is written next to the input (or into `--out-dir`) with the extension of the
+/// target format. Failures of single files are reported and don't stop the others.
+pub(crate) fn convert<W>(output: &mut W, options: &Options) -> Result<(), Failure>
+    where W: Write {
+    let start = Instant::now();
+    let target = options.to
+        .ok_or_else(|| Failure::Usage(String::from("convert needs --to")))?;
+    if options.inputs.is_empty() {
+        return Err(Failure::Usage(String::from("convert needs input files")));
+    }
+
+    let mut jobs: Vec<(PathBuf, Option<ConvertError>)> = Vec::new();
+    for pattern in options.inputs.iter() {
+        match expand(pattern) {
+            Ok(paths) if paths.is_empty() => jobs.push((PathBuf::from(pattern), Some(ConvertError::NoMatch))),
+            Ok(paths) => jobs.extend(paths.into_iter().map(|path| (path, None))),
+            Err(error) => jobs.push((PathBuf::from(pattern), Some(ConvertError::Io(error))))
+        }
+    }
+
+    let results = convert_all(jobs, target, options);
+
+    let mut files: Vec<Value> = Vec::new();
+    let mut errors: Vec<Value> = Vec::new();
+    let mut io_error: Option<io::Error> = None;
+    for (input, result) in results.into_iter() {
+        let input = input.display().to_string();
+        match result {
+            Ok(converted) => {
+                let converted = converted.display().to_string();
+                if options.output == Output::Text {
+                    writeln!(output, "{} -> {}", input, converted)?;
+                }
+                files.push(json!({ "input": input, "output": converted }));
+            },
+            Err(error) => {
+                if options.output == Output::Text {
+                    eprintln!("{}: {}", input, error);
+                }
+                errors.push(json!({ "input": input, "kind": error.kind(), "message": error.to_string() }));
+                if let ConvertError::Io(error) = error {
+                    io_error.get_or_insert(error);
+                }
+            }
+        }
+    }
+
+    let stats = json!({ "files": files.len() + errors.len(), "converted": files.len(), "failed": errors.len() });
+    let result = finish(output, options.output, json!({ "files": files }), stats, errors, start);
+    // I/O problems are the more fundamental failure class
+    return match io_error {
+        Some(error) => Err(Failure::Io(error)),
+        None => result
+    };
+}
+
+/// Converts the files on a thread per core, the results are in input order.
+fn convert_all(jobs: Vec<(PathBuf, Option<ConvertError>)>, target: Format, options: &Options) -> Vec<(PathBuf, Result<PathBuf, ConvertError>)> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get()).min(jobs.len());
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<(usize, Result<PathBuf, ConvertError>)>> = Mutex::new(Vec::with_capacity(jobs.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some((path, problem)) = jobs.get(index) else {
+                    break;
+                };
+                let result = match problem {
+                    Some(_) => Err(ConvertError::NoMatch),
+                    None => convert_file(path, target, options)
+                };
+                results.lock().unwrap().push((index, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    return jobs.into_iter()
+        .zip(results)
+        .map(|((path, problem), (_, result))| (path, match problem {
+            Some(problem) => Err(problem),
+            None => result
+        }))
+        .collect();
+}
+
+fn convert_file(input: &Path, target: Format, options: &Options) -> Result<PathBuf, ConvertError> {
+    let format = input.extension()
+        .and_then(|extension| Format::from_name(&extension.to_string_lossy()))
+        .ok_or(ConvertError::UnknownFormat)?;
+    if format == target {
+        return Err(ConvertError::SameFormat);
+    }
+
+    let content = fs::read_to_string(input)?;
+    let points = read_points(&content, format, options.precision)?;
+
+    let converted = match target {
+        Format::Polyline => encode(&points, options.precision) + "\n",
+        Format::Gpx => to_gpx(&points, options.precision),
+        Format::Csv => to_csv(&points, options.precision),
+        Format::GeoJson => to_geojson(&points, options.precision) + "\n"
+    };
+
+    let mut path = match &options.out_dir {
+        Some(directory) => Path::new(directory).join(input.file_name().unwrap_or_default()),
+        None => input.to_path_buf()
+    };
+    path.set_extension(target.extension());
+    fs::write(&path, converted)?;
+
+    return Ok(path);
+}
+
+/// Reads the points of a file in `format`, also used by `encode --from`.
+pub(crate) fn read_points(content: &str, format: Format, precision: u32) -> Result<Vec<Point>, ConvertError> {
+    return match format {
+        Format::Polyline => try_decode(content.trim(), precision).map_err(ConvertError::Decode),
+        Format::Gpx => read_track(content).map_err(ConvertError::Format),
+        Format::Csv => read_csv(content),
+        Format::GeoJson => from_geojson(content).map_err(ConvertError::Format)
+    };
+}
+
+/// Reads `latitude,longitude` lines, a first line that isn't a point is taken as header.
+fn read_csv(csv: &str) -> Result<Vec<Point>, ConvertError> {
+    let mut points: Vec<Point> = Vec::new();
+    for (index, line) in csv.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match crate::parse_point(line) {
+            Some(point) => points.push(point),
+            None if index == 0 => {},
+            None => return Err(ConvertError::Csv(index + 1))
+        }
+    }
+
+    return Ok(points);
+}
+
+/// Paths matching a pattern with `*` and `?` in the file name, sorted; other paths as they are.
+fn expand(pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let path = Path::new(pattern);
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    if !name.contains(['*', '?']) {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new(".")
+    };
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && matches_wildcard(&name, &entry.file_name().to_string_lossy()) {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+
+    return Ok(paths);
+}
+
+/// Whether `name` matches `pattern`, `*` stands for any characters and `?` for one.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // position after the last `*` and the character of the name it matched up to
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, n));
+            p += 1;
+        } else if let Some((after_star, matched)) = star {
+            // let the `*` match one more character
+            p = after_star;
+            n = matched + 1;
+            star = Some((after_star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    return pattern[p..].iter().all(|letter| *letter == '*');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::Value;
+
+    use crate::Failure;
+    use crate::convert::matches_wildcard;
+    use crate::tests::run_with;
+
+    #[test]
+    fn wildcards() {
+        assert!(matches_wildcard("*.gpx", "run.gpx"));
+        assert!(matches_wildcard("r?n*.g*x", "run 2.gpx"));
+        assert!(matches_wildcard("*", ""));
+        assert!(!matches_wildcard("*.gpx", "run.csv"));
+        assert!(!matches_wildcard("run?.gpx", "run.gpx"));
+    }
+
+    #[test]
+    fn convert_files() {
+        let directory = std::env::temp_dir().join(format!("polyline-convert-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("a.csv"), "latitude,longitude\n38.5,-120.2\n40.7,-120.95\n").unwrap();
+        fs::write(directory.join("b.geojson"), r#"{"type":"LineString","coordinates":[[-120.2,38.5]]}"#).unwrap();
+        fs::write(directory.join("c.csv"), "38.5,-120.2\nnot a point\n").unwrap();
+
+        let pattern = directory.join("*.*").display().to_string();
+        let (result, output) = run_with(&["convert", "--to", "polyline", "--output", "json", &pattern], "");
+        assert!(matches!(result, Err(Failure::InvalidData)));
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(report["stats"]["converted"], 2);
+        assert_eq!(report["errors"][0]["kind"], "csv");
+        assert_eq!(fs::read_to_string(directory.join("a.polyline")).unwrap(), "_p~iF~ps|U_ulLnnqC\n");
+        assert_eq!(fs::read_to_string(directory.join("b.polyline")).unwrap(), "_p~iF~ps|U\n");
+
+        let input = directory.join("a.polyline").display().to_string();
+        let (result, _) = run_with(&["convert", "--to", "gpx", &input], "");
+        assert!(result.is_ok());
+        assert!(fs::read_to_string(directory.join("a.gpx")).unwrap().contains("<trkpt lat=\"38.50000\" lon=\"-120.20000\"/>"));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,523 @@
+//! `polyline` command line tool, build it with `--features cli`.
+//!
+//! Reads from stdin and writes to stdout, so it fits into shell pipelines:
+//!
+//! ```text
+//! polyline decode   [--precision 5] [--output text|json] < polylines.txt
+//! polyline encode   [--precision 5] [--output text|json] [--from csv|geojson|gpx] < points.csv
+//! polyline simplify --tolerance <meters> [--precision 5] [--output text|json] < polylines.txt
+//! polyline validate [--precision 5] [--output text|json] < polylines.txt
+//! polyline map      --expr decode|simplify:10m|encode:6 [--field geometry] < records.ndjson
+//! polyline convert  --to polyline|gpx|csv|geojson [--precision 5] [--out-dir <dir>] <files>...
+//! ```
+//!
+//! Polylines are read one per line. Points are read as `latitude,longitude` lines, an empty
+//! line ends a polyline. `encode --from` instead reads a single line from a CSV file with
+//! header, a GeoJSON `LineString` or `Feature`, or a GPX track. `simplify` removes points that
+//! are less than `--tolerance` meters away from the simplified line. With `--output json` the
+//! result is a single JSON document with the results, statistics, errors with their byte
+//! offsets and the elapsed time.
+//!
+//! `map` reads one JSON record per line and transforms its field `--field` (a polyline
+//! string or an array of `[latitude, longitude]` pairs, `a.b` for nested fields) with the
+//! steps of `--expr`: `decode[:precision]`, `simplify:<meters>m` and `encode[:precision]`.
+//! Records that can't be transformed are written unchanged.
+//!
+//! `convert` converts files between GPX, CSV, GeoJSON and encoded polylines (`.polyline` or
+//! `.txt`), the input format is taken from the extension. The converted files are written
+//! next to the inputs or into `--out-dir`; `*` and `?` in file names match like in a shell,
+//! for shells that don't expand them. The files are converted in parallel.
+//!
+//! The exit code tells the class of the failure:
+//!
+//! * 0: success
+//! * 1: invalid input data, e.g. a malformed polyline; the valid data is processed anyway
+//! * 2: invalid command line
+//! * 3: reading or writing failed
+
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+use std::time::Instant;
+
+use serde_json::{json, Value};
+
+use polyline_rust::{Point, DecodeError, EARTH_RADIUS_M, encode, simplify, try_decode};
+
+mod convert;
+mod map;
+
+const USAGE: &str = "usage: polyline <decode|encode|simplify|validate|map|convert> [--precision <0-9>] [--output <text|json>] [--from <format>] [--tolerance <meters>] [--expr <steps>] [--field <name>] [--to <format>] [--out-dir <dir>] [<files>...]";
+
+/// Exit code if some of the input data is invalid.
+const EXIT_INVALID_DATA: i32 = 1;
+/// Exit code of an invalid command line.
+const EXIT_USAGE: i32 = 2;
+/// Exit code if reading the input or writing the output failed.
+const EXIT_IO: i32 = 3;
+
+/// Largest precision the codec supports.
+const MAX_PRECISION: u32 = 9;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Output {
+    Text,
+    Json
+}
+
+/// Options shared by all commands.
+#[derive(PartialEq, Debug)]
+struct Options {
+    precision: u32,
+    output: Output,
+    /// input format of `encode` instead of `latitude,longitude` lines
+    from: Option<convert::Format>,
+    /// tolerance of `simplify` in meters
+    tolerance: Option<f64>,
+    /// steps of `map`
+    expr: Option<String>,
+    /// field of the records transformed by `map`
+    field: Option<String>,
+    /// target format of `convert`
+    to: Option<convert::Format>,
+    /// directory of the files written by `convert`
+    out_dir: Option<String>,
+    /// files or patterns of `convert`
+    inputs: Vec<String>
+}
+
+/// Reason the tool failed, each one has its own exit code.
+#[derive(Debug)]
+enum Failure {
+    /// some of the input data is invalid, the details are in the output already
+    InvalidData,
+    Usage(String),
+    Io(io::Error)
+}
+
+impl Failure {
+    fn exit_code(&self) -> i32 {
+        return match self {
+            Failure::InvalidData => EXIT_INVALID_DATA,
+            Failure::Usage(_) => EXIT_USAGE,
+            Failure::Io(_) => EXIT_IO
+        };
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Failure {
+        return Failure::Io(error);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    if let Err(failure) = run(&args, stdin.lock(), &mut stdout.lock()) {
+        match &failure {
+            Failure::InvalidData => {},
+            Failure::Usage(message) => eprintln!("polyline: {}\n{}", message, USAGE),
+            Failure::Io(error) => eprintln!("polyline: {}", error)
+        }
+        process::exit(failure.exit_code());
+    }
+}
+
+fn run<R, W>(args: &[String], input: R, output: &mut W) -> Result<(), Failure>
+    where R: BufRead, W: Write {
+    let (command, options) = match args.split_first() {
+        Some((command, options)) => (command, parse_options(options)?),
+        None => return Err(Failure::Usage(String::from("missing command")))
+    };
+    if !options.inputs.is_empty() && command != "convert" {
+        return Err(Failure::Usage(format!("{} reads from stdin, not from {:?}", command, options.inputs[0])));
+    }
+
+    return match command.as_str() {
+        "decode" => decode(input, output, &options),
+        "encode" => encode_points(input, output, &options),
+        "simplify" => simplify_polylines(input, output, &options),
+        "validate" => validate(input, output, &options),
+        "map" => map::map(input, output, &options),
+        "convert" => convert::convert(output, &options),
+        _ => Err(Failure::Usage(format!("unknown command {:?}", command)))
+    };
+}
+
+fn parse_options(args: &[String]) -> Result<Options, Failure> {
+    let mut options = Options { precision: 5, output: Output::Text, from: None, tolerance: None, expr: None, field: None, to: None, out_dir: None, inputs: Vec::new() };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            options.inputs.push(arg.clone());
+            continue;
+        }
+        let value = args.next()
+            .ok_or_else(|| Failure::Usage(format!("missing value of {}", arg)))?;
+        match arg.as_str() {
+            "--precision" => {
+                options.precision = value.parse().ok()
+                    .filter(|precision| *precision <= MAX_PRECISION)
+                    .ok_or_else(|| Failure::Usage(format!("invalid precision {:?}", value)))?;
+            },
+            "--output" => {
+                options.output = match value.as_str() {
+                    "text" => Output::Text,
+                    "json" => Output::Json,
+                    _ => return Err(Failure::Usage(format!("invalid output {:?}", value)))
+                };
+            },
+            "--from" => {
+                options.from = Some(convert::Format::from_name(value)
+                    .filter(|format| *format != convert::Format::Polyline)
+                    .ok_or_else(|| Failure::Usage(format!("invalid input format {:?}", value)))?);
+            },
+            "--tolerance" => {
+                options.tolerance = Some(value.strip_suffix('m').unwrap_or(value).parse().ok()
+                    .filter(|meters: &f64| *meters >= 0.)
+                    .ok_or_else(|| Failure::Usage(format!("invalid tolerance {:?}", value)))?);
+            },
+            "--expr" => options.expr = Some(value.clone()),
+            "--field" => options.field = Some(value.clone()),
+            "--to" => {
+                options.to = Some(convert::Format::from_name(value)
+                    .ok_or_else(|| Failure::Usage(format!("invalid format {:?}", value)))?);
+            },
+            "--out-dir" => options.out_dir = Some(value.clone()),
+            _ => return Err(Failure::Usage(format!("unknown option {:?}", arg)))
+        }
+    }
+
+    return Ok(options);
+}
+
+/// Decodes one polyline per line into `latitude,longitude` lines, with an empty line after
+/// every polyline.
+fn decode<R, W>(input: R, output: &mut W, options: &Options) -> Result<(), Failure>
+    where R: BufRead, W: Write {
+    let start = Instant::now();
+    let mut results: Vec<Value> = Vec::new();
+    let mut errors: Vec<Value> = Vec::new();
+    let mut points_total = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        match try_decode(line.trim_end(), options.precision) {
+            Ok(points) => {
+                points_total += points.len();
+                match options.output {
+                    Output::Text => {
+                        for point in points.iter() {
+                            writeln!(output, "{}", point.display(options.precision))?;
+                        }
+                        writeln!(output)?;
+                    },
+                    Output::Json => {
+                        let points: Vec<[f64; 2]> = points.iter().map(|point| [point.latitude, point.longitude]).collect();
+                        results.push(json!(points));
+                    }
+                }
+            },
+            Err(error) => {
+                report_decode_error(&mut errors, index + 1, &error, options.output);
+                results.push(Value::Null);
+            }
+        }
+    }
+
+    let stats = json!({ "polylines": results.len(), "points": points_total, "errors": errors.len() });
+    return finish(output, options.output, json!({ "polylines": results }), stats, errors, start);
+}
+
+/// Encodes `latitude,longitude` lines, an empty line ends a polyline.
+///
+/// With `--from` the whole input is read as a single line in that format.
+fn encode_points<R, W>(mut input: R, output: &mut W, options: &Options) -> Result<(), Failure>
+    where R: BufRead, W: Write {
+    if let Some(format) = options.from {
+        return encode_file(&mut input, output, format, options);
+    }
+
+    let start = Instant::now();
+    let mut polylines: Vec<String> = Vec::new();
+    let mut errors: Vec<Value> = Vec::new();
+    let mut points: Vec<Point> = Vec::new();
+    let mut points_total = 0;
+
+    let mut lines = input.lines().enumerate().peekable();
+    while let Some((index, line)) = lines.next() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() {
+            match parse_point(line) {
+                Some(point) => points.push(point),
+                None => {
+                    let message = format!("expected latitude,longitude but got {:?}", line);
+                    report_error(&mut errors, json!({ "line": index + 1, "offset": null, "kind": "invalid_point", "message": message }), options.output);
+                }
+            }
+        }
+
+        if (line.is_empty() || lines.peek().is_none()) && !points.is_empty() {
+            points_total += points.len();
+            let polyline = encode(&points, options.precision);
+            if options.output == Output::Text {
+                writeln!(output, "{}", polyline)?;
+            }
+            polylines.push(polyline);
+            points.clear();
+        }
+    }
+
+    let stats = json!({ "polylines": polylines.len(), "points": points_total, "errors": errors.len() });
+    return finish(output, options.output, json!({ "polylines": polylines }), stats, errors, start);
+}
+
+/// Encodes the single line of a CSV, GeoJSON or GPX document.
+fn encode_file<R, W>(input: &mut R, output: &mut W, format: convert::Format, options: &Options) -> Result<(), Failure>
+    where R: BufRead, W: Write {
+    let start = Instant::now();
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let mut polylines: Vec<String> = Vec::new();
+    let mut errors: Vec<Value> = Vec::new();
+    let mut points_total = 0;
+    match convert::read_points(&content, format, options.precision) {
+        Ok(points) => {
+            points_total = points.len();
+            let polyline = encode(&points, options.precision);
+            if options.output == Output::Text {
+                writeln!(output, "{}", polyline)?;
+            }
+            polylines.push(polyline);
+        },
+        Err(error) => {
+            report_error(&mut errors, json!({ "line": null, "offset": null, "kind": error.kind(), "message": error.to_string() }), options.output);
+        }
+    }
+
+    let stats = json!({ "polylines": polylines.len(), "points": points_total, "errors": errors.len() });
+    return finish(output, options.output, json!({ "polylines": polylines }), stats, errors, start);
+}
+
+/// Simplifies one polyline per line with `--tolerance`, the result keeps the precision.
+fn simplify_polylines<R, W>(input: R, output: &mut W, options: &Options) -> Result<(), Failure>
+    where R: BufRead, W: Write {
+    let start = Instant::now();
+    let tolerance = options.tolerance
+        .ok_or_else(|| Failure::Usage(String::from("simplify needs --tolerance")))?;
+    let tolerance_deg = (tolerance / EARTH_RADIUS_M).to_degrees();
+
+    let mut results: Vec<Value> = Vec::new();
+    let mut errors: Vec<Value> = Vec::new();
+    let mut points_total = 0;
+    let mut points_kept = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        match try_decode(line.trim_end(), options.precision) {
+            Ok(points) => {
+                let simplified = simplify(&points, tolerance_deg);
+                points_total += points.len();
+                points_kept += simplified.len();
+
+                let polyline = encode(&simplified, options.precision);
+                if options.output == Output::Text {
+                    writeln!(output, "{}", polyline)?;
+                }
+                results.push(json!(polyline));
+            },
+            Err(error) => {
+                report_decode_error(&mut errors, index + 1, &error, options.output);
+                results.push(Value::Null);
+            }
+        }
+    }
+
+    let stats = json!({ "polylines": results.len(), "points": points_total, "points_kept": points_kept, "errors": errors.len() });
+    return finish(output, options.output, json!({ "polylines": results }), stats, errors, start);
+}
+
+/// Checks one polyline per line without printing the points.
+fn validate<R, W>(input: R, output: &mut W, options: &Options) -> Result<(), Failure>
+    where R: BufRead, W: Write {
+    let start = Instant::now();
+    let mut errors: Vec<Value> = Vec::new();
+    let mut polylines = 0;
+    let mut points_total = 0;
+    let mut bytes = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        polylines += 1;
+        bytes += line.len();
+        match try_decode(line, options.precision) {
+            Ok(points) => points_total += points.len(),
+            Err(error) => report_decode_error(&mut errors, index + 1, &error, options.output)
+        }
+    }
+
+    if options.output == Output::Text && errors.is_empty() {
+        writeln!(output, "{} polylines with {} points are valid", polylines, points_total)?;
+    }
+    let stats = json!({
+        "polylines": polylines,
+        "valid": polylines - errors.len(),
+        "invalid": errors.len(),
+        "points": points_total,
+        "bytes": bytes
+    });
+    return finish(output, options.output, json!({}), stats, errors, start);
+}
+
+/// Writes the JSON document with `results`, `stats`, `errors` and the elapsed time.
+///
+/// Returns `Failure::InvalidData` if there are errors.
+fn finish<W>(output: &mut W, format: Output, results: Value, stats: Value, errors: Vec<Value>, start: Instant) -> Result<(), Failure>
+    where W: Write {
+    let has_errors = !errors.is_empty();
+    if format == Output::Json {
+        let mut document = results;
+        document["stats"] = stats;
+        document["errors"] = Value::Array(errors);
+        document["elapsed_ms"] = json!(start.elapsed().as_secs_f64() * 1_000.);
+        writeln!(output, "{}", document)?;
+    }
+    output.flush()?;
+
+    if has_errors {
+        return Err(Failure::InvalidData);
+    }
+    return Ok(());
+}
+
+fn report_decode_error(errors: &mut Vec<Value>, line: usize, error: &DecodeError, format: Output) {
+    let error = json!({ "line": line, "offset": error.offset(), "kind": kind(error), "message": error.to_string() });
+    report_error(errors, error, format);
+}
+
+/// Collects an error for the JSON output, prints it to stderr for the text output.
+fn report_error(errors: &mut Vec<Value>, error: Value, format: Output) {
+    if format == Output::Text {
+        let message = error["message"].as_str().unwrap_or_default();
+        match error["line"].as_u64() {
+            Some(line) => eprintln!("line {}: {}", line, message),
+            None => eprintln!("{}", message)
+        }
+    }
+    errors.push(error);
+}
+
+/// Stable name of the class of a decode error for the JSON output.
+fn kind(error: &DecodeError) -> &'static str {
+    return match error {
+        DecodeError::InvalidCharacter { .. } => "invalid_character",
+        DecodeError::ChunkOutOfRange { .. } => "chunk_out_of_range",
+        DecodeError::DanglingGroup { .. } => "dangling_group",
+        DecodeError::DanglingCoordinate { .. } => "dangling_coordinate",
+        _ => "other"
+    };
+}
+
+/// Parses a `latitude,longitude` line.
+fn parse_point(line: &str) -> Option<Point> {
+    let (latitude, longitude) = line.split_once(',')?;
+    let latitude: f64 = latitude.trim().parse().ok()?;
+    let longitude: f64 = longitude.trim().parse().ok()?;
+    if !latitude.is_finite() || !longitude.is_finite() {
+        return None;
+    }
+
+    return Some(Point::new(latitude, longitude));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{Failure, run, EXIT_INVALID_DATA, EXIT_USAGE};
+
+    pub(crate) fn run_with(args: &[&str], input: &str) -> (Result<(), Failure>, String) {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut output: Vec<u8> = Vec::new();
+        let result = run(&args, input.as_bytes(), &mut output);
+        return (result, String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn decode_and_encode() {
+        let (result, output) = run_with(&["decode"], "_p~iF~ps|U_ulLnnqC\n");
+        assert!(result.is_ok());
+        assert_eq!(output, "38.50000,-120.20000\n40.70000,-120.95000\n\n");
+
+        let (result, output) = run_with(&["encode", "--precision", "5"], &output);
+        assert!(result.is_ok());
+        assert_eq!(output, "_p~iF~ps|U_ulLnnqC\n");
+    }
+
+    #[test]
+    fn json_output() {
+        let (result, output) = run_with(&["validate", "--output", "json"], "_p~iF~ps|U\n_p~iF~ps|U_\n");
+        assert_eq!(result.unwrap_err().exit_code(), EXIT_INVALID_DATA);
+
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(report["stats"]["valid"], 1);
+        assert_eq!(report["stats"]["points"], 1);
+        assert_eq!(report["errors"][0]["line"], 2);
+        assert_eq!(report["errors"][0]["offset"], 10);
+        assert_eq!(report["errors"][0]["kind"], "dangling_group");
+        assert!(report["elapsed_ms"].as_f64().unwrap() >= 0.);
+
+        let (result, output) = run_with(&["decode", "--output", "json"], "_p~iF~ps|U\n");
+        assert!(result.is_ok());
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(report["polylines"][0][0][0], 38.5);
+    }
+
+    #[test]
+    fn encode_from() {
+        let (result, output) = run_with(&["encode", "--from", "csv"], "latitude,longitude\n38.5,-120.2\n40.7,-120.95\n");
+        assert!(result.is_ok());
+        assert_eq!(output, "_p~iF~ps|U_ulLnnqC\n");
+
+        let geojson = r#"{"type": "LineString", "coordinates": [[-120.2, 38.5], [-120.95, 40.7]]}"#;
+        let (result, output) = run_with(&["encode", "--from", "geojson"], geojson);
+        assert!(result.is_ok());
+        assert_eq!(output, "_p~iF~ps|U_ulLnnqC\n");
+
+        let (result, output) = run_with(&["encode", "--from", "csv", "--output", "json"], "latitude,longitude\n38.5,-120.2\nnorth,west\n");
+        assert_eq!(result.unwrap_err().exit_code(), EXIT_INVALID_DATA);
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(report["errors"][0]["kind"], "csv");
+    }
+
+    #[test]
+    fn simplify() {
+        let (result, output) = run_with(&["simplify", "--tolerance", "1000000m", "--output", "json"], "_p~iF~ps|U_ulLnnqC_mqNvxq`@\n_p~iF~ps|U_\n");
+        assert_eq!(result.unwrap_err().exit_code(), EXIT_INVALID_DATA);
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(report["polylines"][0], "_p~iF~ps|U_c_\\fhde@");
+        assert_eq!(report["polylines"][1], Value::Null);
+        assert_eq!(report["stats"]["points_kept"], 2);
+
+        let (result, output) = run_with(&["simplify", "--tolerance", "0"], "_p~iF~ps|U_ulLnnqC_mqNvxq`@\n");
+        assert!(result.is_ok());
+        assert_eq!(output, "_p~iF~ps|U_ulLnnqC_mqNvxq`@\n");
+    }
+
+    #[test]
+    fn usage_errors() {
+        for args in [&[][..], &["unknown"], &["decode", "--precision", "42"], &["decode", "--output"], &["decode", "a.txt"], &["convert", "a.txt"],
+                     &["simplify"], &["simplify", "--tolerance", "-1"], &["encode", "--from", "polyline"]] {
+            assert_eq!(run_with(args, "").0.unwrap_err().exit_code(), EXIT_USAGE);
+        }
+    }
+}
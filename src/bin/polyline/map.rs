@@ -0,0 +1,167 @@
+//! `polyline map`: transforms a field of every NDJSON record read from stdin.
+
+use std::io::{BufRead, Write};
+
+use serde_json::{json, Value};
+
+use polyline_rust::{Point, EARTH_RADIUS_M, encode, simplify, try_decode};
+
+use crate::{Failure, Options, Output, MAX_PRECISION, report_error};
+
+/// Step of a `--expr` pipeline.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) enum Step {
+    /// polyline string to points, with the precision
+    Decode(u32),
+    /// points to fewer points, with the tolerance in meters
+    Simplify(f64),
+    /// points to polyline string, with the precision
+    Encode(u32)
+}
+
+/// Value of the transformed field between the steps.
+enum Geometry {
+    Encoded(String),
+    Points(Vec<Point>)
+}
+
+/// Parses a pipeline like `decode|simplify:10m|encode:6`.
+///
+/// `precision`: precision of `decode` and `encode` without one
+pub(crate) fn parse_expr(expr: &str, precision: u32) -> Result<Vec<Step>, Failure> {
+    let invalid = |step: &str| Failure::Usage(format!("invalid step {:?} in --expr", step));
+
+    let mut steps: Vec<Step> = Vec::new();
+    for step in expr.split('|') {
+        let (name, argument) = match step.split_once(':') {
+            Some((name, argument)) => (name.trim(), Some(argument.trim())),
+            None => (step.trim(), None)
+        };
+        let precision = match (name, argument) {
+            ("decode", Some(argument)) | ("encode", Some(argument)) => argument.parse().ok()
+                .filter(|precision| *precision <= MAX_PRECISION)
+                .ok_or_else(|| invalid(step))?,
+            _ => precision
+        };
+
+        steps.push(match (name, argument) {
+            ("decode", _) => Step::Decode(precision),
+            ("encode", _) => Step::Encode(precision),
+            ("simplify", Some(argument)) => {
+                let meters: f64 = argument.strip_suffix('m')
+                    .and_then(|meters| meters.parse().ok())
+                    .filter(|meters: &f64| *meters >= 0.)
+                    .ok_or_else(|| invalid(step))?;
+                Step::Simplify(meters)
+            },
+            _ => return Err(invalid(step))
+        });
+    }
+
+    return Ok(steps);
+}
+
+/// Reads NDJSON records, transforms `field` of each with the steps of `expr` and writes them.
+///
+/// Records that can't be transformed are written unchanged and reported on stderr.
+pub(crate) fn map<R, W>(input: R, output: &mut W, options: &Options) -> Result<(), Failure>
+    where R: BufRead, W: Write {
+    let expr = options.expr.as_deref()
+        .ok_or_else(|| Failure::Usage(String::from("map needs --expr")))?;
+    let steps = parse_expr(expr, options.precision)?;
+    // `properties.geometry` is the member `geometry` of the object `properties`
+    let pointer = format!("/{}", options.field.as_deref().unwrap_or("geometry").replace('.', "/"));
+
+    let mut errors: Vec<Value> = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match transform(&line, &pointer, &steps) {
+            Ok(record) => writeln!(output, "{}", record)?,
+            Err(message) => {
+                report_error(&mut errors, json!({ "line": index + 1, "message": message }), Output::Text);
+                writeln!(output, "{}", line)?;
+            }
+        }
+    }
+    output.flush()?;
+
+    if !errors.is_empty() {
+        return Err(Failure::InvalidData);
+    }
+    return Ok(());
+}
+
+/// Applies the steps to the field at `pointer` of a record.
+fn transform(line: &str, pointer: &str, steps: &[Step]) -> Result<Value, String> {
+    let mut record: Value = serde_json::from_str(line)
+        .map_err(|error| format!("invalid JSON: {}", error))?;
+    let field = record.pointer_mut(pointer)
+        .ok_or_else(|| format!("no field {}", pointer))?;
+
+    let mut geometry = match field {
+        Value::String(encoded) => Geometry::Encoded(encoded.clone()),
+        Value::Array(points) => Geometry::Points(points.iter().map(parse_point).collect::<Option<Vec<Point>>>()
+            .ok_or_else(|| String::from("expected [latitude, longitude] pairs"))?),
+        _ => return Err(String::from("expected a polyline string or an array of points"))
+    };
+
+    for step in steps.iter() {
+        geometry = match (step, geometry) {
+            (Step::Decode(precision), Geometry::Encoded(encoded)) => {
+                Geometry::Points(try_decode(&encoded, *precision).map_err(|error| error.to_string())?)
+            },
+            (Step::Simplify(meters), Geometry::Points(points)) => {
+                // degrees of latitude, so the tolerance is a bit larger across longitudes
+                Geometry::Points(simplify(&points, (meters / EARTH_RADIUS_M).to_degrees()))
+            },
+            (Step::Encode(precision), Geometry::Points(points)) => Geometry::Encoded(encode(&points, *precision)),
+            (Step::Decode(_), Geometry::Points(_)) => return Err(String::from("can't decode points")),
+            (_, Geometry::Encoded(_)) => return Err(String::from("decode the polyline first"))
+        };
+    }
+
+    *field = match geometry {
+        Geometry::Encoded(encoded) => Value::String(encoded),
+        Geometry::Points(points) => json!(points.iter().map(|point| [point.latitude, point.longitude]).collect::<Vec<[f64; 2]>>())
+    };
+    return Ok(record);
+}
+
+fn parse_point(point: &Value) -> Option<Point> {
+    return match point.as_array()?.as_slice() {
+        [latitude, longitude] => Some(Point::new(latitude.as_f64()?, longitude.as_f64()?)),
+        _ => None
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Failure;
+    use crate::map::{Step, parse_expr};
+    use crate::tests::run_with;
+
+    #[test]
+    fn expressions() {
+        assert_eq!(parse_expr("decode|simplify:10m|encode:6", 5).ok(), Some(vec![Step::Decode(5), Step::Simplify(10.), Step::Encode(6)]));
+        for expr in ["decode:42", "simplify", "simplify:10", "reverse"] {
+            assert!(matches!(parse_expr(expr, 5), Err(Failure::Usage(_))));
+        }
+    }
+
+    #[test]
+    fn ndjson() {
+        let input = "{\"id\":1,\"geometry\":\"_p~iF~ps|U_ulLnnqC\"}\n\n{\"id\":2,\"geometry\":\"_p~iF~ps|U_\"}\n";
+        let (result, output) = run_with(&["map", "--expr", "decode|encode:6"], input);
+        assert!(matches!(result, Err(Failure::InvalidData)));
+        assert_eq!(output, "{\"geometry\":\"_izlhA~rlgdF_{geC~ywl@\",\"id\":1}\n{\"id\":2,\"geometry\":\"_p~iF~ps|U_\"}\n");
+
+        let input = "{\"properties\":{\"route\":\"_p~iF~ps|U_ulLnnqC_mqNvxq`@\"}}\n";
+        let (result, output) = run_with(&["map", "--expr", "decode|simplify:1000000m", "--field", "properties.route"], input);
+        assert!(result.is_ok());
+        assert_eq!(output, "{\"properties\":{\"route\":[[38.5,-120.2],[43.252,-126.453]]}}\n");
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{Point, DecodeError, decode, round, scan_group_offsets, signed_value};
+use crate::precision::scale;
+use crate::geodesy::EARTH_RADIUS_M;
+
+/// Axis-aligned bounding box in degrees.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Bounds {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64
+}
+
+impl Bounds {
+    /// Creates new `Bounds`.
+    pub fn new(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> Bounds {
+        return Bounds {
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon
+        };
+    }
+
+    /// Returns `true` if the point is inside or on the border of the bounding box.
+    pub fn contains(&self, point: &Point) -> bool {
+        return point.latitude >= self.min_lat && point.latitude <= self.max_lat
+            && point.longitude >= self.min_lon && point.longitude <= self.max_lon;
+    }
+
+    /// Lower bound of the distance in meters from `point` to the points inside the bounding
+    /// box, `0` if the point is inside.
+    ///
+    /// Boxes wider than 180° of longitude may belong to polylines crossing the antimeridian,
+    /// whose segments run outside of the box, the bound is `0` for them. Otherwise the haversine
+    /// formula is evaluated with the smallest latitude and longitude gaps and the latitude of
+    /// the box closest to a pole, where the meridians converge.
+    pub(crate) fn distance_to(&self, point: &Point) -> f64 {
+        if self.max_lon - self.min_lon > 180. {
+            return 0.;
+        }
+
+        let d_lat = if point.latitude < self.min_lat {
+            self.min_lat - point.latitude
+        } else if point.latitude > self.max_lat {
+            point.latitude - self.max_lat
+        } else {
+            0.
+        };
+        let d_lon = if point.longitude >= self.min_lon && point.longitude <= self.max_lon {
+            0.
+        } else {
+            // the short way around the earth
+            (self.min_lon - point.longitude).rem_euclid(360.).min((point.longitude - self.max_lon).rem_euclid(360.))
+        };
+        let cos_lat = point.latitude.to_radians().cos()
+            * self.min_lat.to_radians().cos().min(self.max_lat.to_radians().cos());
+
+        let h = (d_lat.to_radians() / 2.).sin().powi(2) + cos_lat * (d_lon.to_radians() / 2.).sin().powi(2);
+        return 2. * EARTH_RADIUS_M * h.sqrt().min(1.).asin();
+    }
+
+    /// Clips the segment `a`-`b` to the bounding box (Liang–Barsky) and returns the segment
+    /// parameters of the part inside, if any.
+    fn clip(&self, a: &Point, b: &Point) -> Option<(f64, f64)> {
+        let d_lat = b.latitude - a.latitude;
+        let d_lon = b.longitude - a.longitude;
+
+        let mut t0: f64 = 0.;
+        let mut t1: f64 = 1.;
+        let edges = [
+            (-d_lon, a.longitude - self.min_lon),
+            (d_lon, self.max_lon - a.longitude),
+            (-d_lat, a.latitude - self.min_lat),
+            (d_lat, self.max_lat - a.latitude)
+        ];
+        for (p, q) in edges.iter() {
+            if *p == 0. {
+                if *q < 0. {
+                    return None;
+                }
+                continue;
+            }
+
+            let t = q / p;
+            if *p < 0. {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+
+        if t0 > t1 {
+            return None;
+        }
+        return Some((t0, t1));
+    }
+}
+
+/// Bounding box of the points, `None` if there are none.
+///
+/// `points`: points of the polyline
+pub fn bounds(points: &[Point]) -> Option<Bounds> {
+    let first = points.first()?;
+    let mut bounds = Bounds::new(first.latitude, first.longitude, first.latitude, first.longitude);
+
+    for point in points.iter().skip(1) {
+        bounds.min_lat = bounds.min_lat.min(point.latitude);
+        bounds.min_lon = bounds.min_lon.min(point.longitude);
+        bounds.max_lat = bounds.max_lat.max(point.latitude);
+        bounds.max_lon = bounds.max_lon.max(point.longitude);
+    }
+
+    return Some(bounds);
+}
+
+/// Bounding box of a polyline without decoding it into points.
+///
+/// Walks the string once and only keeps the current position and the bounding box, so it
+/// doesn't allocate; the result is the same as `bounds(&try_decode(polyline, precision)?)`.
+/// Useful e.g. to select the map tiles of a polyline.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+///
+/// Returns `None` for an empty polyline and an error for malformed ones, see [`try_decode`](crate::try_decode).
+///
+/// ```
+/// use polyline_rust::{Bounds, decode_bounds};
+///
+/// let bounds = decode_bounds("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5);
+/// assert_eq!(bounds, Ok(Some(Bounds::new(38.5, -126.453, 43.252, -120.2))));
+/// ```
+pub fn decode_bounds(polyline: &str, precision: u32) -> Result<Option<Bounds>, DecodeError> {
+    let scale = scale(precision);
+    let mut bounds: Option<Bounds> = None;
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    // the latitude of the current point and the byte offset of its group, until the longitude follows
+    let mut pending: Option<(f64, usize)> = None;
+
+    scan_group_offsets(polyline, |value, offset| {
+        // same rounding as `decode`
+        let delta = round(signed_value(value) as f64 / scale, precision);
+
+        match pending.take() {
+            None => pending = Some((round(latitude + delta, precision), offset)),
+            Some((next_latitude, _)) => {
+                latitude = next_latitude;
+                longitude = round(longitude + delta, precision);
+                bounds = Some(match bounds {
+                    None => Bounds::new(latitude, longitude, latitude, longitude),
+                    Some(b) => Bounds::new(b.min_lat.min(latitude), b.min_lon.min(longitude), b.max_lat.max(latitude), b.max_lon.max(longitude))
+                });
+            }
+        }
+    })?;
+
+    if let Some((_, offset)) = pending {
+        return Err(DecodeError::DanglingCoordinate { offset });
+    }
+
+    return Ok(bounds);
+}
+
+/// Decodes a polyline but only returns the parts inside the bounding box.
+///
+/// Segments crossing the border of the bounding box are cut at the border, so every part
+/// leaving and re-entering the box becomes a separate `Vec`. Clipping happens in plain
+/// latitude/longitude space, bounding boxes crossing the antimeridian are not supported.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+///
+/// `bbox`: the visible area
+pub fn decode_within_bbox(polyline: &str, precision: u32, bbox: &Bounds) -> Vec<Vec<Point>> {
+    let points = decode(polyline, precision);
+
+    let mut parts: Vec<Vec<Point>> = Vec::new();
+    if points.len() == 1 && bbox.contains(&points[0]) {
+        parts.push(points);
+        return parts;
+    }
+
+    let mut part: Vec<Point> = Vec::new();
+    for segment in points.windows(2) {
+        let (a, b) = (&segment[0], &segment[1]);
+        let (t0, t1) = match bbox.clip(a, b) {
+            Some(range) => range,
+            None => continue
+        };
+
+        if part.is_empty() {
+            part.push(lerp(a, b, t0));
+        }
+        part.push(lerp(a, b, t1));
+
+        if t1 < 1. {
+            parts.push(part);
+            part = Vec::new();
+        }
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+
+    return parts;
+}
+
+fn lerp(a: &Point, b: &Point, t: f64) -> Point {
+    if t == 0. {
+        return Point::new(a.latitude, a.longitude);
+    }
+    if t == 1. {
+        return Point::new(b.latitude, b.longitude);
+    }
+
+    return Point::new(
+        a.latitude + (b.latitude - a.latitude) * t,
+        a.longitude + (b.longitude - a.longitude) * t
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Bounds, DecodeError, encode, decode, try_decode, bounds, decode_bounds, decode_within_bbox};
+
+    fn polyline() -> String {
+        return encode(vec![
+            Point::new(0., 0.),
+            Point::new(0., 2.),
+            Point::new(2., 2.),
+            Point::new(2., 0.),
+            Point::new(1., 0.),
+        ], 5);
+    }
+
+    #[test]
+    fn fully_inside() {
+        let parts = decode_within_bbox(&polyline(), 5, &Bounds::new(-1., -1., 3., 3.));
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].len(), 5);
+    }
+
+    #[test]
+    fn fully_outside() {
+        assert_eq!(decode_within_bbox(&polyline(), 5, &Bounds::new(10., 10., 11., 11.)), Vec::<Vec<Point>>::new());
+    }
+
+    #[test]
+    fn leaving_and_reentering() {
+        let parts = decode_within_bbox(&polyline(), 5, &Bounds::new(-1., -1., 1.5, 1.));
+        assert_eq!(parts, vec![
+            vec![Point::new(0., 0.), Point::new(0., 1.)],
+            vec![Point::new(1.5, 0.), Point::new(1., 0.)]
+        ]);
+    }
+
+    #[test]
+    fn single_point() {
+        let parts = decode_within_bbox("_ibE_ibE", 5, &Bounds::new(0., 0., 2., 2.));
+        assert_eq!(parts, vec![vec![Point::new(1., 1.)]]);
+    }
+
+    #[test]
+    fn bounds_without_decoding() {
+        assert_eq!(decode_bounds(&polyline(), 5), Ok(Some(Bounds::new(0., 0., 2., 2.))));
+        assert_eq!(decode_bounds("", 5), Ok(None));
+        assert_eq!(bounds(&[]), None);
+
+        for polyline in ["_p~iF~ps|U_ulLnnqC_mqNvxq`@", "mgjjAcfh~OgSySep@gS", "~d|cN`~oia@", "|bdpvCruhhvI"].iter() {
+            for precision in 5..=7 {
+                assert_eq!(decode_bounds(polyline, precision), Ok(bounds(&decode(polyline, precision))));
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_bounds() {
+        for polyline in ["_p~iF", "_p~iF~ps|U_", "_p~iF ~ps|U", "_p~iFä", "~~~~~~~~~~~~~?", "~~~~~~~~~~~~O?", "~~~~~~~~~~~~N?"].iter() {
+            assert_eq!(decode_bounds(polyline, 5).map(|_| ()), try_decode(polyline, 5).map(|_| ()), "{}", polyline);
+        }
+        assert_eq!(decode_bounds("_p~iF~ps|U_ulL", 5), Err(DecodeError::DanglingCoordinate { offset: 10 }));
+    }
+}
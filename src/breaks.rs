@@ -0,0 +1,151 @@
+//! Gaps in a trace (e.g. signal loss) encoded as break markers.
+//!
+//! A break is written as [`BREAK_MARKER`], a point whose latitude and longitude delta are both
+//! zero but use a superfluous second chunk (`_?` instead of `?`). Decoders that don't know about
+//! breaks simply see the last point before the gap repeated, so the string stays a valid
+//! polyline. [`EncodedPolyline::canonical`](crate::EncodedPolyline::canonical) re-encodes a
+//! break as that repeated point (`??`), so the gap is lost.
+//!
+//! ```
+//! use polyline_rust::{Point, decode};
+//! use polyline_rust::breaks::{encode_with_breaks, decode_with_breaks};
+//!
+//! let polyline = encode_with_breaks(&[
+//!     Point::new(38.5, -120.2),
+//!     Point::new(f64::NAN, f64::NAN),
+//!     Point::new(40.7, -120.95),
+//! ], 5);
+//! assert_eq!(polyline, "_p~iF~ps|U_?_?_ulLnnqC");
+//!
+//! assert_eq!(decode_with_breaks(&polyline, 5), vec![
+//!     vec![Point::new(38.5, -120.2)],
+//!     vec![Point::new(40.7, -120.95)],
+//! ]);
+//! assert_eq!(decode(&polyline, 5).len(), 3);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, push_element, round, scan_group_offsets, signed_value};
+use crate::precision::scale;
+
+/// Marker for a gap between two parts of a trace.
+pub const BREAK_MARKER: &str = "_?_?";
+
+/// Encodes coordinates to the "Encoded Polyline Algorithm Format" with gaps as break markers.
+///
+/// `points`: points of the trace; a point with a NaN latitude or longitude marks a gap.
+/// Consecutive gaps are merged and gaps at the start or end of the trace are dropped.
+///
+/// `precision`: usually 5 or 6, see [`encode`](crate::encode)
+pub fn encode_with_breaks(points: &[Point], precision: u32) -> String {
+    let mut encoded = String::new();
+
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    let mut pending_break = false;
+
+    for point in points.iter() {
+        if point.latitude.is_nan() || point.longitude.is_nan() {
+            pending_break = !encoded.is_empty();
+            continue;
+        }
+
+        if pending_break {
+            encoded += BREAK_MARKER;
+            pending_break = false;
+        }
+
+        push_element(&mut encoded, point.latitude-latitude, precision);
+        push_element(&mut encoded, point.longitude-longitude, precision);
+
+        latitude = point.latitude;
+        longitude = point.longitude;
+    }
+
+    return encoded;
+}
+
+/// Decodes a polyline with break markers into the separate parts of the trace.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`](crate::decode)
+///
+/// Like [`decode`](crate::decode), malformed polylines are decoded up to the first invalid
+/// character.
+pub fn decode_with_breaks(polyline: &str, precision: u32) -> Vec<Vec<Point>> {
+    // values and start offsets of the groups
+    let mut groups: Vec<(u64, usize)> = Vec::new();
+    let _ = scan_group_offsets(polyline, |value, offset| groups.push((value, offset)));
+    // a zero with a superfluous second chunk, the group ends with the `?`
+    let is_marker = |(value, offset): (u64, usize)| value == 0 && polyline[offset..].starts_with("_?");
+
+    let scale = scale(precision);
+    let mut parts: Vec<Vec<Point>> = Vec::new();
+    let mut part: Vec<Point> = Vec::new();
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+
+    for pair in groups.chunks_exact(2) {
+        if is_marker(pair[0]) && is_marker(pair[1]) {
+            if !part.is_empty() {
+                parts.push(part);
+                part = Vec::new();
+            }
+            continue;
+        }
+
+        latitude = round(latitude + signed_value(pair[0].0) as f64 / scale, precision);
+        longitude = round(longitude + signed_value(pair[1].0) as f64 / scale, precision);
+        part.push(Point::new(latitude, longitude));
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+
+    return parts;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+    use crate::breaks::{encode_with_breaks, decode_with_breaks};
+
+    #[test]
+    fn without_breaks() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+        assert_eq!(encode_with_breaks(&points, 5), "_p~iF~ps|U_ulLnnqC");
+        assert_eq!(decode_with_breaks("_p~iF~ps|U_ulLnnqC", 5), vec![points]);
+    }
+
+    #[test]
+    fn leading_trailing_and_repeated_gaps() {
+        let gap = Point::new(f64::NAN, 0.);
+        let polyline = encode_with_breaks(&[
+            Point::new(f64::NAN, f64::NAN),
+            Point::new(38.5, -120.2),
+            gap,
+            Point::new(0., f64::NAN),
+            Point::new(40.7, -120.95),
+            Point::new(f64::NAN, f64::NAN)
+        ], 5);
+        assert_eq!(polyline, "_p~iF~ps|U_?_?_ulLnnqC");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(encode_with_breaks(&[], 5), "");
+        assert_eq!(decode_with_breaks("", 5), Vec::<Vec<Point>>::new());
+        assert_eq!(decode_with_breaks("_?_?", 5), Vec::<Vec<Point>>::new());
+    }
+
+    #[test]
+    fn malformed() {
+        let points = vec![vec![Point::new(38.5, -120.2)]];
+        assert_eq!(decode_with_breaks("_p~iF~ps|U_ulL nnqC", 5), points);
+        assert_eq!(decode_with_breaks("_p~iF~ps|U_?_?\u{e4}", 5), points);
+        assert_eq!(decode_with_breaks("\u{1}\u{2}\u{3}\u{4}", 5), Vec::<Vec<Point>>::new());
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::string::String;
+
+use crate::{Coordinate, push_element};
+
+/// Encodes a polyline point by point, e.g. a live track that grows with every GPS fix.
+///
+/// Only the new point is encoded on every [`push`](PolylineBuilder::push), relative to the last
+/// point, instead of encoding the whole track again. The string is the same as [`encode`](crate::encode)
+/// gives for all points.
+///
+/// ```
+/// use polyline_rust::{Point, PolylineBuilder, encode};
+///
+/// let mut builder = PolylineBuilder::new(5);
+/// builder.push(Point::new(38.5, -120.2));
+/// assert_eq!(builder.as_str(), "_p~iF~ps|U");
+///
+/// builder.extend(vec![Point::new(40.7, -120.95), Point::new(43.252, -126.453)]);
+/// assert_eq!(builder.finish(), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+/// ```
+#[derive(PartialEq, Debug, Clone)]
+pub struct PolylineBuilder {
+    encoded: String,
+    precision: u32,
+    latitude: f64,
+    longitude: f64
+}
+
+impl PolylineBuilder {
+    /// Creates a new `PolylineBuilder` without points.
+    ///
+    /// `precision`: usually 5 or 6, see [`encode`](crate::encode)
+    pub fn new(precision: u32) -> PolylineBuilder {
+        return PolylineBuilder {
+            encoded: String::new(),
+            precision,
+            latitude: 0.,
+            longitude: 0.
+        };
+    }
+
+    /// Appends a point.
+    pub fn push<C>(&mut self, point: C)
+        where C: Coordinate {
+        push_element(&mut self.encoded, point.lat() - self.latitude, self.precision);
+        push_element(&mut self.encoded, point.lng() - self.longitude, self.precision);
+
+        self.latitude = point.lat();
+        self.longitude = point.lng();
+    }
+
+    /// The polyline string of the points so far.
+    pub fn as_str(&self) -> &str {
+        return &self.encoded;
+    }
+
+    /// The precision of the polyline.
+    pub fn precision(&self) -> u32 {
+        return self.precision;
+    }
+
+    /// Consumes the `PolylineBuilder` and returns the polyline string.
+    pub fn finish(self) -> String {
+        return self.encoded;
+    }
+}
+
+impl<C> Extend<C> for PolylineBuilder where C: Coordinate {
+    fn extend<I>(&mut self, points: I)
+        where I: IntoIterator<Item = C> {
+        for point in points {
+            self.push(point);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, PolylineBuilder, encode};
+
+    #[test]
+    fn same_as_encode() {
+        let track = vec![
+            Point::new(52.520008, 13.404954),
+            Point::new(52.520108, 13.404854),
+            Point::new(52.519999, 13.405001),
+            Point::new(-0.000001, 0.000004)
+        ];
+        for precision in [5, 6] {
+            let mut builder = PolylineBuilder::new(precision);
+            for (i, point) in track.iter().enumerate() {
+                builder.push(point);
+                assert_eq!(builder.as_str(), encode(&track[..=i], precision));
+            }
+            assert_eq!(builder.finish(), encode(&track, precision));
+        }
+        assert_eq!(PolylineBuilder::new(5).finish(), "");
+    }
+}
@@ -0,0 +1,164 @@
+//! Compact binary serialization of points.
+//!
+//! The format uses the same fixed-point deltas as the "Encoded Polyline Algorithm Format", but
+//! writes them as binary varints instead of printable characters:
+//!
+//! * one byte with the precision
+//! * for every point the latitude and longitude delta to the previous point (multiplied by
+//!   10^precision and rounded), zigzag encoded and written as LEB128 varint: 7 bits per byte,
+//!   least significant group first, the high bit is set on all but the last byte
+//!
+//! Using 7 instead of 5 bits per byte makes the output about 17% smaller than an encoded
+//! polyline string. The format is stable and won't change in future versions.
+
+use alloc::vec::Vec;
+
+use crate::{Point, Precision, DecodeError, round_to_integer};
+use crate::precision::scale;
+
+/// Serializes points into the binary format described in the [module documentation](self).
+///
+/// `points`: points of the polyline
+///
+/// `precision`: usually 5 or 6, see [`encode`](crate::encode); at most [`Precision::MAX`]
+///
+/// # Panics
+///
+/// If `precision` is larger than [`Precision::MAX`], `from_bytes` would reject its header.
+pub fn to_bytes(points: &[Point], precision: u32) -> Vec<u8> {
+    assert!(precision <= Precision::MAX, "precision of the binary format is at most {}", Precision::MAX);
+    let factor = scale(precision);
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(1 + points.len() * 6);
+    bytes.push(precision as u8);
+
+    let mut latitude: i64 = 0;
+    let mut longitude: i64 = 0;
+    for point in points.iter() {
+        let point_latitude = round_to_integer(point.latitude * factor) as i64;
+        let point_longitude = round_to_integer(point.longitude * factor) as i64;
+
+        write_varint(&mut bytes, zigzag(point_latitude - latitude));
+        write_varint(&mut bytes, zigzag(point_longitude - longitude));
+
+        latitude = point_latitude;
+        longitude = point_longitude;
+    }
+
+    return bytes;
+}
+
+/// Deserializes points from the binary format described in the [module documentation](self).
+///
+/// Offsets in errors are byte offsets into `bytes`.
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<Point>, DecodeError> {
+    let precision = match bytes.first() {
+        Some(precision) if *precision <= 9 => *precision as u32,
+        Some(_) => return Err(DecodeError::ChunkOutOfRange { offset: 0 }),
+        None => return Err(DecodeError::MissingHeader)
+    };
+    let factor = scale(precision);
+
+    let mut points: Vec<Point> = Vec::new();
+    let mut latitude: i64 = 0;
+    let mut longitude: i64 = 0;
+
+    let mut offset = 1;
+    while offset < bytes.len() {
+        let latitude_offset = offset;
+        let (d_latitude, length) = read_varint(bytes, offset)?;
+        offset += length;
+        if offset >= bytes.len() {
+            return Err(DecodeError::DanglingCoordinate { offset: latitude_offset });
+        }
+        let (d_longitude, length) = read_varint(bytes, offset)?;
+        offset += length;
+
+        latitude = latitude.wrapping_add(unzigzag(d_latitude));
+        longitude = longitude.wrapping_add(unzigzag(d_longitude));
+        points.push(Point::new(latitude as f64 / factor, longitude as f64 / factor));
+    }
+
+    return Ok(points);
+}
+
+fn zigzag(value: i64) -> u64 {
+    return ((value << 1) ^ (value >> 63)) as u64;
+}
+
+fn unzigzag(value: u64) -> i64 {
+    return (value >> 1) as i64 ^ -((value & 1) as i64);
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads a varint starting at `offset` and returns its value and length in bytes.
+fn read_varint(bytes: &[u8], offset: usize) -> Result<(u64, usize), DecodeError> {
+    let mut value: u64 = 0;
+
+    for (i, byte) in bytes[offset..].iter().enumerate() {
+        // 10 groups of 7 bits hold 64 bits, the last group may only use a single bit
+        if i > 9 || (i == 9 && *byte > 1) {
+            return Err(DecodeError::ChunkOutOfRange { offset });
+        }
+
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    return Err(DecodeError::DanglingGroup { offset });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, DecodeError, encode};
+    use crate::bytes::{to_bytes, from_bytes};
+
+    fn points() -> Vec<Point> {
+        return vec![
+            Point::new(48.208771, 16.372572),
+            Point::new(48.210133, 16.374164),
+            Point::new(-48.210495, -163.373436)
+        ];
+    }
+
+    #[test]
+    fn roundtrip() {
+        assert_eq!(from_bytes(&to_bytes(&points(), 6)), Ok(points()));
+        assert_eq!(from_bytes(&to_bytes(&[], 5)), Ok(vec![]));
+    }
+
+    #[test]
+    fn highest_precision() {
+        assert_eq!(to_bytes(&[], 9), vec![9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "precision of the binary format is at most 9")]
+    fn precision_too_high() {
+        to_bytes(&points(), 10);
+    }
+
+    #[test]
+    fn smaller_than_polyline() {
+        assert!(to_bytes(&points(), 6).len() < encode(points(), 6).len());
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(from_bytes(&[]), Err(DecodeError::MissingHeader));
+        assert_eq!(from_bytes(&[10]), Err(DecodeError::ChunkOutOfRange { offset: 0 }));
+        assert_eq!(from_bytes(&[5, 0x80]), Err(DecodeError::DanglingGroup { offset: 1 }));
+        assert_eq!(from_bytes(&[5, 0x01]), Err(DecodeError::DanglingCoordinate { offset: 1 }));
+        assert_eq!(from_bytes(&[5, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]), Err(DecodeError::ChunkOutOfRange { offset: 1 }));
+    }
+}
@@ -0,0 +1,14 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Cancelled;
+
+/// Fails if the optional cancellation flag is set.
+///
+/// Only a relaxed load, so it's cheap enough to call once per polyline or loop iteration.
+pub(crate) fn check(cancel: Option<&AtomicBool>) -> Result<(), Cancelled> {
+    if cancel.is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+        return Err(Cancelled);
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,198 @@
+use std::sync::atomic::AtomicBool;
+
+use crate::{Point, Cancelled, decode};
+use crate::cancel::check;
+use crate::interpolate::resample_count;
+use crate::metrics::frechet;
+
+/// Groups similar polylines with DBSCAN, e.g. to find the canonical routes of commuter traces.
+///
+/// Two polylines are neighbours if their discrete Fréchet distance is at most `eps_m` meters,
+/// so traces of the same route driven in opposite directions end up in different clusters.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with
+///
+/// `eps_m`: maximum distance in meters between neighbouring polylines
+///
+/// `min_pts`: minimum number of polylines (including itself) in the neighbourhood of a
+/// polyline to start a cluster
+///
+/// Returns the cluster label of every polyline in input order, `None` for noise.
+/// Clusters are numbered from 0 in the order they are discovered, so the result is
+/// deterministic for the same input.
+pub fn cluster<I, S>(polylines: I, precision: u32, eps_m: f64, min_pts: usize) -> Vec<Option<usize>>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    // can't be cancelled without flag
+    return dbscan(polylines, precision, eps_m, min_pts, None).unwrap_or_default();
+}
+
+/// Like [`cluster`], but stops as soon as `cancel` is set.
+///
+/// Clustering compares every polyline with all others, so large batches can take minutes;
+/// the flag is checked before decoding each polyline and before each neighbourhood query.
+///
+/// `cancel`: cancellation flag, set by another thread
+pub fn cluster_cancellable<I, S>(polylines: I, precision: u32, eps_m: f64, min_pts: usize, cancel: &AtomicBool) -> Result<Vec<Option<usize>>, Cancelled>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    return dbscan(polylines, precision, eps_m, min_pts, Some(cancel));
+}
+
+fn dbscan<I, S>(polylines: I, precision: u32, eps_m: f64, min_pts: usize, cancel: Option<&AtomicBool>) -> Result<Vec<Option<usize>>, Cancelled>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    let lines: Vec<Vec<Point>> = polylines.into_iter()
+        .map(|polyline| {
+            check(cancel)?;
+            return Ok(decode(polyline.as_ref(), precision));
+        })
+        .collect::<Result<Vec<Vec<Point>>, Cancelled>>()?;
+
+    let neighbours = |i: usize| -> Result<Vec<usize>, Cancelled> {
+        check(cancel)?;
+        return Ok((0..lines.len()).filter(|j| frechet(&lines[i], &lines[*j]) <= eps_m).collect());
+    };
+
+    let mut labels: Vec<Option<usize>> = vec![None; lines.len()];
+    let mut visited = vec![false; lines.len()];
+    let mut cluster_count = 0;
+
+    for i in 0..lines.len() {
+        if visited[i] {
+            continue;
+        }
+        visited[i] = true;
+
+        let mut queue = neighbours(i)?;
+        if queue.len() < min_pts {
+            continue;
+        }
+
+        let label = cluster_count;
+        cluster_count += 1;
+        labels[i] = Some(label);
+
+        while let Some(j) = queue.pop() {
+            if labels[j].is_none() {
+                labels[j] = Some(label);
+            }
+            if visited[j] {
+                continue;
+            }
+            visited[j] = true;
+
+            let expansion = neighbours(j)?;
+            if expansion.len() >= min_pts {
+                queue.extend(expansion);
+            }
+        }
+    }
+
+    return Ok(labels);
+}
+
+/// Computes a representative line for a group of similar lines, e.g. a cluster found by
+/// [`cluster`].
+///
+/// All lines are resampled to the same number of evenly spaced points (the highest point count
+/// of any line) and the median latitude and longitude of each sample position is used. Unlike
+/// the mean, the median isn't dragged off by a single outlier trace.
+///
+/// Returns an empty `Vec` if there are no lines or all lines are empty.
+pub fn consensus_line(lines: &[Vec<Point>]) -> Vec<Point> {
+    let lines: Vec<&Vec<Point>> = lines.iter().filter(|line| !line.is_empty()).collect();
+    let count = match lines.iter().map(|line| line.len()).max() {
+        Some(count) => count.max(2),
+        None => return vec![]
+    };
+
+    let resampled: Vec<Vec<Point>> = lines.iter().map(|line| resample_count(line, count)).collect();
+
+    return (0..count).map(|k| {
+        let mut latitudes: Vec<f64> = resampled.iter().map(|line| line[k].latitude).collect();
+        let mut longitudes: Vec<f64> = resampled.iter().map(|line| line[k].longitude).collect();
+        return Point::new(median(&mut latitudes), median(&mut longitudes));
+    }).collect();
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        return values[middle];
+    }
+    return (values[middle - 1] + values[middle]) / 2.;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use crate::{Point, Cancelled, encode, cluster, cluster_cancellable, consensus_line};
+
+    fn line(offset: f64, reversed: bool) -> String {
+        let mut points = vec![Point::new(offset, 0.), Point::new(offset, 0.01), Point::new(offset, 0.02)];
+        if reversed {
+            points.reverse();
+        }
+        return encode(points, 5);
+    }
+
+    #[test]
+    fn two_clusters_and_noise() {
+        let polylines = vec![
+            line(0., false),
+            line(0.00001, false),
+            line(0., true),
+            line(0.00002, false),
+            line(0.00001, true),
+            line(1., false),
+        ];
+
+        assert_eq!(cluster(&polylines, 5, 5., 2), vec![
+            Some(0),
+            Some(0),
+            Some(1),
+            Some(0),
+            Some(1),
+            None
+        ]);
+    }
+
+    #[test]
+    fn cancelled() {
+        let polylines = vec![line(0., false), line(0.00001, false)];
+        let cancel = AtomicBool::new(false);
+        assert_eq!(cluster_cancellable(&polylines, 5, 5., 2, &cancel), Ok(vec![Some(0), Some(0)]));
+        cancel.store(true, Ordering::Relaxed);
+        assert_eq!(cluster_cancellable(&polylines, 5, 5., 2, &cancel), Err(Cancelled));
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(cluster(Vec::<String>::new(), 5, 5., 2), vec![]);
+    }
+
+    #[test]
+    fn consensus_of_parallel_lines() {
+        let lines = vec![
+            vec![Point::new(0., 0.), Point::new(0., 1.)],
+            vec![Point::new(0.2, 0.), Point::new(0.2, 0.5), Point::new(0.2, 1.)],
+            vec![Point::new(0.1, 0.), Point::new(0.1, 1.)],
+            vec![]
+        ];
+
+        let consensus = consensus_line(&lines);
+        assert_eq!(consensus.len(), 3);
+        for (point, longitude) in consensus.iter().zip([0., 0.5, 1.].iter()) {
+            assert!((point.latitude - 0.1).abs() < 1e-9);
+            assert!((point.longitude - longitude).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn consensus_of_nothing() {
+        assert_eq!(consensus_line(&[]), vec![]);
+        assert_eq!(consensus_line(&[vec![]]), vec![]);
+    }
+}
@@ -0,0 +1,147 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, Coordinate, DecodeError, Precision, encode, decode, try_decode};
+
+/// Precision of [`DEFAULT_CODEC`] if no codec was set.
+const UNSET: u32 = u32::MAX;
+
+/// Process-wide default codec, see [`set_default_codec`].
+static DEFAULT_CODEC: AtomicU32 = AtomicU32::new(UNSET);
+
+/// Precision of a polyline format, e.g. of a routing provider.
+///
+/// Google and OSRM use [`Codec::POLYLINE5`], Valhalla and OSRM with `geometries=polyline6` use
+/// [`Codec::POLYLINE6`].
+///
+/// ```
+/// use polyline_rust::{Point, Codec};
+///
+/// let codec = Codec::POLYLINE6;
+/// assert_eq!(codec.decode(&codec.encode(vec![Point::new(38.5, -120.2)])), vec![Point::new(38.5, -120.2)]);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct Codec {
+    precision: u32
+}
+
+impl Codec {
+    /// Google's original format, accurate to about one meter.
+    pub const POLYLINE5: Codec = Codec { precision: 5 };
+
+    /// Accurate to about ten centimeters.
+    pub const POLYLINE6: Codec = Codec { precision: 6 };
+
+    /// Creates a new `Codec`.
+    ///
+    /// `precision`: usually 5 or 6, see [`encode`]
+    pub const fn new(precision: u32) -> Codec {
+        return Codec { precision };
+    }
+
+    /// The precision of the polylines.
+    pub const fn precision(&self) -> u32 {
+        return self.precision;
+    }
+
+    /// Encodes coordinates, see [`encode`].
+    pub fn encode<I>(&self, points: I) -> String
+        where I: IntoIterator, I::Item: Coordinate {
+        return encode(points, self.precision);
+    }
+
+    /// Decodes coordinates, see [`decode`].
+    pub fn decode(&self, polyline: &str) -> Vec<Point> {
+        return decode(polyline, self.precision);
+    }
+
+    /// Decodes coordinates and fails on malformed input, see [`try_decode`].
+    pub fn try_decode(&self, polyline: &str) -> Result<Vec<Point>, DecodeError> {
+        return try_decode(polyline, self.precision);
+    }
+}
+
+impl From<Precision> for Codec {
+    fn from(precision: Precision) -> Codec {
+        return Codec::new(precision.digits());
+    }
+}
+
+impl Default for Codec {
+    fn default() -> Codec {
+        return Codec::POLYLINE5;
+    }
+}
+
+/// Sets the codec used by [`encode_default`], [`decode_default`] and [`try_decode_default`].
+///
+/// The default can only be set once, usually at startup, so all threads see the same codec.
+/// Without a call [`Codec::POLYLINE5`] is used.
+///
+/// Returns the codec that is the default already if it was set before.
+///
+/// ```
+/// use polyline_rust::{Point, Codec, set_default_codec, default_codec, encode_default};
+///
+/// assert_eq!(set_default_codec(Codec::POLYLINE6), Ok(()));
+/// assert_eq!(set_default_codec(Codec::POLYLINE5), Err(Codec::POLYLINE6));
+/// assert_eq!(default_codec(), Codec::POLYLINE6);
+/// assert_eq!(encode_default(vec![Point::new(38.5, -120.2)]), "_izlhA~rlgdF");
+/// ```
+pub fn set_default_codec(codec: Codec) -> Result<(), Codec> {
+    // precisions above 9 aren't supported anyway, so `UNSET` can't collide with a real codec
+    return match DEFAULT_CODEC.compare_exchange(UNSET, codec.precision, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => Ok(()),
+        Err(precision) => Err(Codec::new(precision))
+    };
+}
+
+/// The codec set with [`set_default_codec`], [`Codec::POLYLINE5`] if none was set.
+pub fn default_codec() -> Codec {
+    return match DEFAULT_CODEC.load(Ordering::Acquire) {
+        UNSET => Codec::default(),
+        precision => Codec::new(precision)
+    };
+}
+
+/// Shorthand call for encode with the [`default_codec`].
+pub fn encode_default<I>(points: I) -> String
+    where I: IntoIterator, I::Item: Coordinate {
+    return default_codec().encode(points);
+}
+
+/// Shorthand call for decode with the [`default_codec`].
+pub fn decode_default(polyline: &str) -> Vec<Point> {
+    return default_codec().decode(polyline);
+}
+
+/// Shorthand call for try_decode with the [`default_codec`].
+pub fn try_decode_default(polyline: &str) -> Result<Vec<Point>, DecodeError> {
+    return default_codec().try_decode(polyline);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, Codec, set_default_codec, default_codec, encode_default, decode_default};
+
+    // the only test that sets the default, the codec is shared by all tests of the process
+    #[test]
+    fn set_once() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+        assert_eq!(default_codec(), Codec::POLYLINE5);
+        assert_eq!(encode_default(&points), "_p~iF~ps|U_ulLnnqC");
+
+        let threads: Vec<_> = (0..4)
+            .map(|precision| std::thread::spawn(move || set_default_codec(Codec::new(6 + precision % 2))))
+            .collect();
+        let results: Vec<Result<(), Codec>> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+
+        let codec = default_codec();
+        assert!(results.iter().all(|result| result.is_ok() || *result == Err(codec)));
+        assert_eq!(decode_default(&codec.encode(&points)), points);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{Point, decode};
+use crate::geodesy::{distance, distance_to_polyline, interpolate};
+use crate::metrics::{length, hausdorff, frechet};
+
+/// Maximum distance in meters a segment of one polyline may be away from the other polyline
+/// to still count as overlapping in [`ComparisonReport::overlap_pct`].
+pub const OVERLAP_TOLERANCE_M: f64 = 10.;
+
+/// Summary of the differences between two polylines, see [`compare`].
+#[derive(PartialEq, Debug)]
+pub struct ComparisonReport {
+    /// length of the new polyline minus the length of the old one, in meters
+    pub length_diff: f64,
+    /// Hausdorff distance in meters
+    pub hausdorff: f64,
+    /// discrete Fréchet distance in meters
+    pub frechet: f64,
+    /// share of the old polyline's length (0 to 100) running within
+    /// [`OVERLAP_TOLERANCE_M`] of the new polyline, sampled every [`OVERLAP_TOLERANCE_M`]
+    pub overlap_pct: f64,
+    /// distances in meters the start and end point moved
+    pub endpoints_moved_m: (f64, f64)
+}
+
+/// Compares two encoded polylines and reports how much the geometry changed from `a` to `b`.
+///
+/// `a`: old polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `b`: new polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision both polylines were encoded with
+///
+/// If only one polyline is empty, the Hausdorff and Fréchet distances and the moved endpoints
+/// are `f64::INFINITY`, if both are empty they are `0`. An empty polyline is 0 meters long for
+/// `length_diff` and overlaps nothing.
+pub fn compare(a: &str, b: &str, precision: u32) -> ComparisonReport {
+    let old = decode(a, precision);
+    let new = decode(b, precision);
+
+    return ComparisonReport {
+        length_diff: length(&new) - length(&old),
+        hausdorff: hausdorff(&old, &new),
+        frechet: frechet(&old, &new),
+        overlap_pct: overlap_pct(&old, &new),
+        endpoints_moved_m: (
+            endpoint_distance(old.first(), new.first()),
+            endpoint_distance(old.last(), new.last())
+        )
+    };
+}
+
+fn overlap_pct(old: &[Point], new: &[Point]) -> f64 {
+    let total = length(old);
+    if total == 0. {
+        return 0.;
+    }
+
+    let mut overlapping = 0.;
+    for segment in old.windows(2) {
+        let segment_length = distance(&segment[0], &segment[1]);
+        if !segment_length.is_finite() {
+            continue;
+        }
+
+        // pieces of at most the tolerance, each one counts if its middle is close to `new`
+        let pieces = (segment_length / OVERLAP_TOLERANCE_M).ceil().max(1.);
+        let covered = (0..pieces as usize)
+            .filter(|piece| {
+                let middle = interpolate(&segment[0], &segment[1], (*piece as f64 + 0.5) / pieces);
+                return distance_to_polyline(&middle, new) <= OVERLAP_TOLERANCE_M;
+            })
+            .count();
+        overlapping += segment_length * (covered as f64 / pieces);
+    }
+
+    return overlapping / total * 100.;
+}
+
+fn endpoint_distance(a: Option<&Point>, b: Option<&Point>) -> f64 {
+    return match (a, b) {
+        (Some(a), Some(b)) => distance(a, b),
+        (None, None) => 0.,
+        _ => f64::INFINITY
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, compare, encode};
+
+    #[test]
+    fn identical_polylines() {
+        let report = compare("_p~iF~ps|U_ulLnnqC_mqNvxq`@", "_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5);
+        assert_eq!(report.length_diff, 0.);
+        assert_eq!(report.hausdorff, 0.);
+        assert_eq!(report.frechet, 0.);
+        assert_eq!(report.overlap_pct, 100.);
+        assert_eq!(report.endpoints_moved_m, (0., 0.));
+    }
+
+    #[test]
+    fn extended_polyline() {
+        // second polyline has an additional point at the end
+        let report = compare("_p~iF~ps|U_ulLnnqC", "_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5);
+        assert!(report.length_diff > 0.);
+        assert_eq!(report.overlap_pct, 100.);
+        assert_eq!(report.endpoints_moved_m.0, 0.);
+        assert!(report.endpoints_moved_m.1 > 0.);
+    }
+
+    #[test]
+    fn shared_part_of_a_segment() {
+        // the new route only covers the middle half of the long old segment
+        let old = encode(vec![Point::new(0., 0.), Point::new(0., 0.04)], 5);
+        let new = encode(vec![Point::new(0.01, 0.01), Point::new(0., 0.01), Point::new(0., 0.03), Point::new(0.01, 0.03)], 5);
+        let report = compare(&old, &new, 5);
+        assert!((report.overlap_pct - 50.).abs() < 2., "{}", report.overlap_pct);
+    }
+
+    #[test]
+    fn empty_polylines() {
+        let report = compare("", "", 5);
+        assert_eq!(report.hausdorff, 0.);
+        assert_eq!(report.overlap_pct, 0.);
+        assert_eq!(report.endpoints_moved_m, (0., 0.));
+
+        let old = "_p~iF~ps|U_ulLnnqC";
+        let report = compare(old, "", 5);
+        assert_eq!(report.length_diff, -compare("", old, 5).length_diff);
+        assert_eq!((report.hausdorff, report.frechet), (f64::INFINITY, f64::INFINITY));
+        assert_eq!(report.overlap_pct, 0.);
+        assert_eq!(report.endpoints_moved_m, (f64::INFINITY, f64::INFINITY));
+    }
+}
@@ -0,0 +1,114 @@
+//! Context for decode errors deep inside services.
+//!
+//! A bare [`DecodeError`] only tells the offset of the problem. In the logs of a service that
+//! decodes thousands of polylines, the offending part of the string and the precision used
+//! are what's needed to reproduce the failure. [`PolylineContext::with_polyline_context`]
+//! attaches both; the resulting [`ContextError`] is `Send + Sync + 'static` with the
+//! `DecodeError` as source, so it converts into `anyhow::Error` or `Box<dyn Error>` with `?`.
+//!
+//! ```
+//! use polyline_rust::try_decode;
+//! use polyline_rust::context::PolylineContext;
+//!
+//! let polyline = "_p~iF~ps|U_ulLnn qC";
+//! let error = try_decode(polyline, 5).with_polyline_context(polyline, 5).unwrap_err();
+//! assert_eq!(error.to_string(), "can't decode polyline with precision 5 at offset 16 near \"ps|U_ulLnn qC\"");
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::DecodeError;
+
+/// Number of bytes shown on each side of the offset.
+const SNIPPET_RADIUS: usize = 10;
+
+/// A [`DecodeError`] with the part of the polyline around it and the precision.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ContextError {
+    /// the original error
+    pub error: DecodeError,
+    /// up to 10 bytes before and after the offset of the error, the whole polyline if the
+    /// error has no offset and the polyline is short
+    pub snippet: String,
+    /// precision the polyline was decoded with
+    pub precision: u32
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the error itself is the source
+        write!(f, "can't decode polyline with precision {}", self.precision)?;
+        if let Some(offset) = self.error.offset() {
+            write!(f, " at offset {}", offset)?;
+        }
+        return write!(f, " near {:?}", self.snippet);
+    }
+}
+
+impl Error for ContextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return Some(&self.error);
+    }
+}
+
+/// Attaches context to decode results, see the [module documentation](self).
+pub trait PolylineContext<T> {
+    /// Turns a [`DecodeError`] into a [`ContextError`].
+    ///
+    /// `polyline`: the polyline that was decoded
+    ///
+    /// `precision`: the precision it was decoded with
+    fn with_polyline_context(self, polyline: &str, precision: u32) -> Result<T, ContextError>;
+}
+
+impl<T> PolylineContext<T> for Result<T, DecodeError> {
+    fn with_polyline_context(self, polyline: &str, precision: u32) -> Result<T, ContextError> {
+        return self.map_err(|error| {
+            let snippet = snippet(polyline, error.offset().unwrap_or(0)).to_string();
+            return ContextError { error, snippet, precision };
+        });
+    }
+}
+
+/// Part of the polyline around `offset`, on character boundaries.
+fn snippet(polyline: &str, offset: usize) -> &str {
+    let mut start = offset.saturating_sub(SNIPPET_RADIUS).min(polyline.len());
+    while !polyline.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = offset.saturating_add(SNIPPET_RADIUS).min(polyline.len());
+    while !polyline.is_char_boundary(end) {
+        end += 1;
+    }
+
+    return &polyline[start..end];
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{DecodeError, DecodeOptions, try_decode, decode_with};
+    use crate::context::{PolylineContext, ContextError};
+
+    #[test]
+    fn snippets() {
+        let error = try_decode("_p~iF", 6).with_polyline_context("_p~iF", 6).unwrap_err();
+        assert_eq!(error, ContextError { error: DecodeError::DanglingCoordinate { offset: 0 }, snippet: String::from("_p~iF"), precision: 6 });
+        assert_eq!(error.source().map(|source| source.to_string()), Some(String::from("latitude without longitude at offset 0")));
+
+        let polyline = "_p~iF~ps|U_ulLnnqC_mqNvxq`@ä";
+        let error = try_decode(polyline, 5).with_polyline_context(polyline, 5).unwrap_err();
+        assert_eq!(error.snippet, "C_mqNvxq`@ä");
+        assert_eq!(try_decode("??", 5).with_polyline_context("??", 5).map(|points| points.len()), Ok(1));
+    }
+
+    #[test]
+    fn without_offset() {
+        let options = DecodeOptions::new(5).max_jump_m(1.);
+        let error = decode_with("_p~iF~ps|U_ulLnnqC", &options).with_polyline_context("_p~iF~ps|U_ulLnnqC", 5).unwrap_err();
+        assert_eq!(error.snippet, "_p~iF~ps|U");
+        assert_eq!(error.to_string(), "can't decode polyline with precision 5 near \"_p~iF~ps|U\"");
+    }
+}
@@ -0,0 +1,263 @@
+use alloc::boxed::Box;
+
+use crate::Point;
+
+/// Anything with a latitude and a longitude, e.g. a domain type of the application; also
+/// available as [`Coord`](crate::Coord).
+///
+/// [`encode`](crate::encode) and its variants take iterators over `Coordinate`s, so domain
+/// types can be encoded without mapping them into [`Point`]s first. References to
+/// coordinates are coordinates as well. Types with an [`elevation`](Coordinate::elevation) can
+/// be encoded with [`encode3`](crate::encode3) as well.
+///
+/// Implement [`lat`](Coordinate::lat) and [`lng`](Coordinate::lng), [`lon`](Coordinate::lon)
+/// is another name of `lng`:
+///
+/// ```
+/// use polyline_rust::{Coord, encode};
+///
+/// struct GpsFix {
+///     lat: f64,
+///     lon: f64
+/// }
+///
+/// impl Coord for GpsFix {
+///     fn lat(&self) -> f64 {
+///         return self.lat;
+///     }
+///
+///     fn lng(&self) -> f64 {
+///         return self.lon;
+///     }
+/// }
+///
+/// let fix = GpsFix { lat: 38.5, lon: -120.2 };
+/// assert_eq!(fix.lon(), -120.2);
+/// assert_eq!(encode(&[fix], 5), "_p~iF~ps|U");
+/// ```
+///
+/// A type without longitude doesn't compile:
+///
+/// ```compile_fail
+/// struct Latitude(f64);
+///
+/// impl polyline_rust::Coord for Latitude {
+///     fn lat(&self) -> f64 {
+///         return self.0;
+///     }
+/// }
+/// ```
+///
+/// ```
+/// use polyline_rust::{Coordinate, encode};
+///
+/// struct VehicleFix {
+///     vehicle: u32,
+///     lat: f64,
+///     lon: f64
+/// }
+///
+/// impl Coordinate for VehicleFix {
+///     fn lat(&self) -> f64 {
+///         return self.lat;
+///     }
+///
+///     fn lng(&self) -> f64 {
+///         return self.lon;
+///     }
+/// }
+///
+/// let fixes = vec![VehicleFix { vehicle: 7, lat: 38.5, lon: -120.2 }, VehicleFix { vehicle: 7, lat: 40.7, lon: -120.95 }];
+/// assert_eq!(encode(&fixes, 5), "_p~iF~ps|U_ulLnnqC");
+/// ```
+pub trait Coordinate {
+    /// latitude in degrees
+    fn lat(&self) -> f64;
+
+    /// longitude in degrees
+    fn lng(&self) -> f64;
+
+    /// longitude in degrees, same as [`lng`](Coordinate::lng)
+    fn lon(&self) -> f64 {
+        return self.lng();
+    }
+
+    /// elevation, usually in meters above sea level; `None` without elevation, for which
+    /// [`encode3`](crate::encode3) keeps the elevation of the previous point
+    fn elevation(&self) -> Option<f64> {
+        return None;
+    }
+}
+
+/// Types that can be created from a latitude and a longitude, see [`decode_as`](crate::decode_as).
+///
+/// The counterpart of [`Coordinate`] for decoding: domain types are constructed during
+/// decoding, with defaults for all other fields, instead of mapping decoded [`Point`]s in a
+/// second pass.
+pub trait FromCoordinate {
+    /// Creates a value at the given position.
+    ///
+    /// `lat`: latitude in degrees
+    ///
+    /// `lng`: longitude in degrees
+    fn from_coordinate(lat: f64, lng: f64) -> Self;
+}
+
+impl FromCoordinate for Point {
+    fn from_coordinate(lat: f64, lng: f64) -> Point {
+        return Point::new(lat, lng);
+    }
+}
+
+impl Coordinate for Point {
+    fn lat(&self) -> f64 {
+        return self.latitude;
+    }
+
+    fn lng(&self) -> f64 {
+        return self.longitude;
+    }
+}
+
+/// `(latitude, longitude)`, the order of [`Point::new`].
+impl Coordinate for (f64, f64) {
+    fn lat(&self) -> f64 {
+        return self.0;
+    }
+
+    fn lng(&self) -> f64 {
+        return self.1;
+    }
+}
+
+/// `[latitude, longitude]`, the order of [`Point::new`].
+impl Coordinate for [f64; 2] {
+    fn lat(&self) -> f64 {
+        return self[0];
+    }
+
+    fn lng(&self) -> f64 {
+        return self[1];
+    }
+}
+
+impl FromCoordinate for (f64, f64) {
+    fn from_coordinate(lat: f64, lng: f64) -> (f64, f64) {
+        return (lat, lng);
+    }
+}
+
+impl FromCoordinate for [f64; 2] {
+    fn from_coordinate(lat: f64, lng: f64) -> [f64; 2] {
+        return [lat, lng];
+    }
+}
+
+impl From<(f64, f64)> for Point {
+    /// Takes `(latitude, longitude)`.
+    fn from((latitude, longitude): (f64, f64)) -> Point {
+        return Point::new(latitude, longitude);
+    }
+}
+
+impl From<[f64; 2]> for Point {
+    /// Takes `[latitude, longitude]`.
+    fn from([latitude, longitude]: [f64; 2]) -> Point {
+        return Point::new(latitude, longitude);
+    }
+}
+
+impl From<Point> for (f64, f64) {
+    /// Gives `(latitude, longitude)`.
+    fn from(point: Point) -> (f64, f64) {
+        return (point.latitude, point.longitude);
+    }
+}
+
+impl From<Point> for [f64; 2] {
+    /// Gives `[latitude, longitude]`.
+    fn from(point: Point) -> [f64; 2] {
+        return [point.latitude, point.longitude];
+    }
+}
+
+impl<T: Coordinate + ?Sized> Coordinate for &T {
+    fn lat(&self) -> f64 {
+        return (**self).lat();
+    }
+
+    fn lng(&self) -> f64 {
+        return (**self).lng();
+    }
+
+    fn elevation(&self) -> Option<f64> {
+        return (**self).elevation();
+    }
+}
+
+impl<T: Coordinate + ?Sized> Coordinate for &mut T {
+    fn lat(&self) -> f64 {
+        return (**self).lat();
+    }
+
+    fn lng(&self) -> f64 {
+        return (**self).lng();
+    }
+
+    fn elevation(&self) -> Option<f64> {
+        return (**self).elevation();
+    }
+}
+
+impl<T: Coordinate + ?Sized> Coordinate for Box<T> {
+    fn lat(&self) -> f64 {
+        return (**self).lat();
+    }
+
+    fn lng(&self) -> f64 {
+        return (**self).lng();
+    }
+
+    fn elevation(&self) -> Option<f64> {
+        return (**self).elevation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, Coordinate, encode, decode_as};
+
+    struct Fix(f64, f64);
+
+    impl Coordinate for Fix {
+        fn lat(&self) -> f64 {
+            return self.0;
+        }
+
+        fn lng(&self) -> f64 {
+            return self.1;
+        }
+    }
+
+    #[test]
+    fn mixed_trait_objects() {
+        let fix = Fix(40.7, -120.95);
+        let point = Point::new(38.5, -120.2);
+        let coordinates: Vec<&dyn Coordinate> = vec![&point, &fix, &Fix(43.252, -126.453)];
+        assert_eq!(encode(coordinates.iter(), 5), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+        assert_eq!(encode(vec![Box::new(fix)], 5), "_flwFn`faV");
+    }
+
+    #[test]
+    fn pairs() {
+        let point = Point::new(38.5, -120.2);
+        assert_eq!(Point::from((38.5, -120.2)), point);
+        assert_eq!(Point::from([38.5, -120.2]), point);
+        assert_eq!(<(f64, f64)>::from(point), (38.5, -120.2));
+        assert_eq!(<[f64; 2]>::from(point), [38.5, -120.2]);
+
+        assert_eq!(encode([[38.5, -120.2], [40.7, -120.95]], 5), "_p~iF~ps|U_ulLnnqC");
+        assert_eq!(decode_as::<(f64, f64)>("_p~iF~ps|U_ulLnnqC", 5), vec![(38.5, -120.2), (40.7, -120.95)]);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::Point;
+use crate::geodesy::{bearing, destination};
+
+/// Mitred corners are cut off at this multiple of the half width, so sharp turns don't produce
+/// long spikes.
+const MITRE_LIMIT: f64 = 4.;
+
+/// Creates a closed ring around a route, e.g. for geofencing.
+///
+/// The ring runs along the left side of the route from start to end and back along the right
+/// side; the last point equals the first one. Corners are mitred, so the corridor is
+/// approximate: it can be wider than `width_m` at turns. At turns sharper than about 151° the
+/// mitre is cut off at four times the half width, which makes the corridor narrower than
+/// `width_m` around the corner; at a U-turn both sides meet on the route.
+///
+/// `points`: points of the route
+///
+/// `width_m`: total width of the corridor in meters
+///
+/// Returns an empty `Vec` if the route has less than two distinct points.
+pub fn corridor_polygon(points: &[Point], width_m: f64) -> Vec<Point> {
+    let mut route: Vec<&Point> = Vec::with_capacity(points.len());
+    for point in points.iter() {
+        if route.last() != Some(&point) {
+            route.push(point);
+        }
+    }
+    if route.len() < 2 {
+        return vec![];
+    }
+
+    let half_width = width_m / 2.;
+    let mut left: Vec<Point> = Vec::with_capacity(route.len());
+    let mut right: Vec<Point> = Vec::with_capacity(route.len());
+
+    for i in 0..route.len() {
+        let (direction, offset) = if i == 0 {
+            (bearing(route[0], route[1]), half_width)
+        } else if i == route.len() - 1 {
+            (bearing(route[i-1], route[i]), half_width)
+        } else {
+            let incoming = bearing(route[i-1], route[i]);
+            let outgoing = bearing(route[i], route[i+1]);
+            let turn = (outgoing - incoming + 180.).rem_euclid(360.) - 180.;
+            let offset = half_width / (turn / 2.).to_radians().cos();
+
+            (incoming + turn / 2., offset.min(half_width * MITRE_LIMIT))
+        };
+
+        left.push(destination(route[i], direction - 90., offset));
+        right.push(destination(route[i], direction + 90., offset));
+    }
+
+    let first = Point::new(left[0].latitude, left[0].longitude);
+    left.extend(right.into_iter().rev());
+    left.push(first);
+
+    return left;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, corridor_polygon, distance};
+
+    #[test]
+    fn too_short() {
+        assert_eq!(corridor_polygon(&[], 10.), vec![]);
+        assert_eq!(corridor_polygon(&[Point::new(1., 1.), Point::new(1., 1.)], 10.), vec![]);
+    }
+
+    #[test]
+    fn straight_line() {
+        let ring = corridor_polygon(&[Point::new(0., 0.), Point::new(0., 0.5), Point::new(0., 1.)], 2000.);
+        assert_eq!(ring.len(), 7);
+        assert_eq!(ring.first(), ring.last());
+
+        // left side is north of the route, right side south
+        for point in ring[..3].iter() {
+            assert!((distance(point, &Point::new(0., point.longitude)) - 1000.).abs() < 1e-6);
+            assert!(point.latitude > 0.);
+        }
+        for point in ring[3..6].iter() {
+            assert!(point.latitude < 0.);
+        }
+    }
+
+    #[test]
+    fn right_angle_is_mitred() {
+        let ring = corridor_polygon(&[Point::new(0., 0.), Point::new(0., 0.1), Point::new(0.1, 0.1)], 200.);
+        let corner = &ring[1];
+        let expected = 100. * 2_f64.sqrt();
+        assert!((distance(corner, &Point::new(0., 0.1)) - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn u_turn_is_cut_off() {
+        let turn = Point::new(0., 0.1);
+        let ring = corridor_polygon(&[Point::new(0., 0.), turn, Point::new(0., 0.)], 200.);
+        assert_eq!(ring.len(), 7);
+
+        // both corners lie on the route at four times the half width from the turning point
+        for corner in [&ring[1], &ring[4]].iter() {
+            assert!((distance(corner, &turn) - 400.).abs() < 0.01);
+            assert!(corner.latitude.abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use alloc::string::String;
+
+use crate::Point;
+use crate::format::write_fixed;
+
+/// Exports points as CSV with a `latitude,longitude` header.
+///
+/// `points`: points of the polyline
+///
+/// `precision`: number of decimals, usually the precision the polyline was encoded with;
+/// every coordinate is written with exactly this many decimals, so exports of the same
+/// polyline are byte-wise identical on all platforms
+///
+/// ```
+/// use polyline_rust::{Point, to_csv};
+///
+/// let csv = to_csv(&[Point::new(38.5, -120.2), Point::new(40.7, -120.95)], 5);
+/// assert_eq!(csv, "latitude,longitude\n38.50000,-120.20000\n40.70000,-120.95000\n");
+/// ```
+pub fn to_csv(points: &[Point], precision: u32) -> String {
+    let mut csv = String::from("latitude,longitude\n");
+    for point in points.iter() {
+        // writing into a `String` never fails
+        let _ = write_fixed(&mut csv, point.latitude, precision);
+        csv.push(',');
+        let _ = write_fixed(&mut csv, point.longitude, precision);
+        csv.push('\n');
+    }
+
+    return csv;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, to_csv, decode};
+
+    #[test]
+    fn no_float_noise() {
+        let points = vec![Point::new(12.340000000000001, -0.1 - 0.2)];
+        assert_eq!(to_csv(&points, 5), "latitude,longitude\n12.34000,-0.30000\n");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(to_csv(&[], 5), "latitude,longitude\n");
+    }
+
+    #[test]
+    fn decoded_polyline() {
+        let csv = to_csv(&decode("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5), 5);
+        assert_eq!(csv.lines().last(), Some("43.25200,-126.45300"));
+    }
+}
@@ -0,0 +1,264 @@
+//! Deterministic workloads for benchmarks.
+//!
+//! Every generator returns the same points for the same size and seed in every version of the
+//! crate, so performance numbers of different changes (and of downstream code) are measured
+//! against the same corpora. The generated points are rounded to 6 decimals, like real data
+//! that went through a polyline of precision 6 before; this hides most differences in the last
+//! bits of the platform's trigonometric functions, but not all of them.
+//!
+//! ## Reproducibility
+//!
+//! All randomness of the crate lives in this module and comes from an explicit `seed`; there
+//! is no global or time-based random state, and the other algorithms of the crate (e.g.
+//! [`cluster`](crate::cluster)) are deterministic. The output of every generator and of
+//! [`perturb`] for a given seed is bit-stable across versions on the same platform and
+//! toolchain, regression suites can store it or compare against hard-coded values. Changing it
+//! is a breaking change. `sin`, `cos` and `atan2` of the standard library aren't correctly
+//! rounded, on another platform or with another Rust version a point may differ in the 6th
+//! decimal when its exact value is close to the rounding boundary.
+//!
+//! ```
+//! use polyline_rust::datasets::Dataset;
+//! use polyline_rust::encode;
+//!
+//! for dataset in Dataset::ALL.iter() {
+//!     let points = dataset.generate(1_000, 42);
+//!     let polyline = encode(&points, 5);
+//!     println!("{:?}: {} bytes", dataset, polyline.len());
+//! }
+//! ```
+
+use crate::{Point, destination, round};
+
+/// Start of the urban workload, Berlin Alexanderplatz.
+const URBAN_START: Point = Point { latitude: 52.521918, longitude: 13.413215 };
+/// Start of the highway workload, on the A9 north of Munich.
+const HIGHWAY_START: Point = Point { latitude: 48.215, longitude: 11.6251 };
+/// Route of the flight workload, Frankfurt to New York JFK.
+const FLIGHT_FROM: Point = Point { latitude: 50.0379, longitude: 8.5622 };
+const FLIGHT_TO: Point = Point { latitude: 40.6413, longitude: -73.7781 };
+
+/// Kinds of representative polylines.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Dataset {
+    /// GPS trace in a city: 5–30 m between points, frequent turns, see [`urban_dense`]
+    UrbanDense,
+    /// Routed highway geometry: 200 m–2 km between points, gentle curves, see [`highway_sparse`]
+    HighwaySparse,
+    /// Great-circle flight path with evenly spaced points, see [`flight_great_circle`]
+    FlightGreatCircle
+}
+
+impl Dataset {
+    /// All datasets, e.g. to run a benchmark for each of them.
+    pub const ALL: [Dataset; 3] = [Dataset::UrbanDense, Dataset::HighwaySparse, Dataset::FlightGreatCircle];
+
+    /// Generates `size` points of this dataset.
+    ///
+    /// `seed`: different seeds give different polylines of the same kind; ignored by
+    /// [`Dataset::FlightGreatCircle`]
+    pub fn generate(&self, size: usize, seed: u64) -> Vec<Point> {
+        return match self {
+            Dataset::UrbanDense => urban_dense(size, seed),
+            Dataset::HighwaySparse => highway_sparse(size, seed),
+            Dataset::FlightGreatCircle => flight_great_circle(size)
+        };
+    }
+}
+
+/// GPS trace of a walk or drive through a city.
+///
+/// Consecutive points are 5 to 30 m apart; the heading changes by up to ±20° at every point
+/// and turns by 90° every 5 to 20 points, like a trace through a street grid.
+///
+/// `size`: number of points
+///
+/// `seed`: seed of the random generator
+pub fn urban_dense(size: usize, seed: u64) -> Vec<Point> {
+    let mut random = SplitMix64::new(seed);
+    let mut heading = random.range(0., 360.);
+    let mut until_turn = 0;
+
+    return walk(&URBAN_START, size, || {
+        if until_turn == 0 {
+            heading += if random.next() & 1 == 0 { 90. } else { -90. };
+            until_turn = 5 + random.next() % 16;
+        }
+        until_turn -= 1;
+
+        return (heading + random.range(-20., 20.), random.range(5., 30.));
+    });
+}
+
+/// Routed highway geometry with few, far apart points.
+///
+/// Consecutive points are 200 m to 2 km apart and the heading drifts by at most ±5° per point.
+///
+/// `size`: number of points
+///
+/// `seed`: seed of the random generator
+pub fn highway_sparse(size: usize, seed: u64) -> Vec<Point> {
+    let mut random = SplitMix64::new(seed);
+    let mut heading = random.range(0., 360.);
+
+    return walk(&HIGHWAY_START, size, || {
+        heading += random.range(-5., 5.);
+        return (heading, random.range(200., 2_000.));
+    });
+}
+
+/// Great-circle path of a flight from Frankfurt to New York with evenly spaced points.
+///
+/// Coordinates change in every point, neighbouring deltas are similar and large, which is the
+/// worst case for the size of the encoded polyline.
+///
+/// `size`: number of points, including departure and destination
+pub fn flight_great_circle(size: usize) -> Vec<Point> {
+    let (lat1, lon1) = (FLIGHT_FROM.latitude.to_radians(), FLIGHT_FROM.longitude.to_radians());
+    let (lat2, lon2) = (FLIGHT_TO.latitude.to_radians(), FLIGHT_TO.longitude.to_radians());
+    let from = [lat1.cos() * lon1.cos(), lat1.cos() * lon1.sin(), lat1.sin()];
+    let to = [lat2.cos() * lon2.cos(), lat2.cos() * lon2.sin(), lat2.sin()];
+    let angle = (from[0] * to[0] + from[1] * to[1] + from[2] * to[2]).acos();
+
+    let mut points: Vec<Point> = Vec::with_capacity(size);
+    for i in 0..size {
+        let fraction = if size > 1 { i as f64 / (size - 1) as f64 } else { 0. };
+        // spherical linear interpolation between the unit vectors
+        let a = ((1. - fraction) * angle).sin() / angle.sin();
+        let b = (fraction * angle).sin() / angle.sin();
+        let x = a * from[0] + b * to[0];
+        let y = a * from[1] + b * to[1];
+        let z = a * from[2] + b * to[2];
+
+        points.push(rounded(z.atan2(x.hypot(y)).to_degrees(), y.atan2(x).to_degrees()));
+    }
+
+    return points;
+}
+
+/// Moves every point in a random direction by up to `max_offset_m` meters, e.g. to turn a
+/// route into a synthetic GPS trace with noise.
+///
+/// `points`: points of the polyline
+///
+/// `max_offset_m`: largest distance in meters between a point and its perturbed position
+///
+/// `seed`: seed of the random generator
+///
+/// ```
+/// use polyline_rust::{Point, distance};
+/// use polyline_rust::datasets::perturb;
+///
+/// let route = vec![Point::new(52.5, 13.4), Point::new(52.51, 13.41)];
+/// let trace = perturb(&route, 10., 7);
+/// assert_eq!(trace, perturb(&route, 10., 7));
+/// assert!(distance(&route[0], &trace[0]) < 10.01);
+/// ```
+pub fn perturb(points: &[Point], max_offset_m: f64, seed: u64) -> Vec<Point> {
+    let mut random = SplitMix64::new(seed);
+    return points.iter()
+        .map(|point| {
+            let bearing = random.range(0., 360.);
+            let moved = destination(point, bearing, random.range(0., max_offset_m));
+            return rounded(moved.latitude, moved.longitude);
+        })
+        .collect();
+}
+
+/// Walks from `start`, `step` returns the bearing and distance of the next point.
+fn walk<F>(start: &Point, size: usize, mut step: F) -> Vec<Point>
+    where F: FnMut() -> (f64, f64) {
+    let mut points: Vec<Point> = Vec::with_capacity(size);
+    let mut current = Point::new(start.latitude, start.longitude);
+    for _ in 0..size {
+        points.push(rounded(current.latitude, current.longitude));
+        let (bearing, distance) = step();
+        current = destination(&current, bearing, distance);
+    }
+
+    return points;
+}
+
+fn rounded(latitude: f64, longitude: f64) -> Point {
+    return Point::new(round(latitude, 6), round(longitude, 6));
+}
+
+/// SplitMix64 random generator, small and with a fixed output for every seed.
+struct SplitMix64 {
+    state: u64
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> SplitMix64 {
+        return SplitMix64 { state: seed };
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return z ^ (z >> 31);
+    }
+
+    /// Uniformly distributed number in `[min, max)`.
+    fn range(&mut self, min: f64, max: f64) -> f64 {
+        // 53 random bits are all an f64 mantissa can take
+        let unit = (self.next() >> 11) as f64 / (1_u64 << 53) as f64;
+        return min + unit * (max - min);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, distance};
+    use crate::datasets::{Dataset, urban_dense, highway_sparse, flight_great_circle, perturb};
+
+    fn step_distances(points: &[Point]) -> (f64, f64) {
+        let distances: Vec<f64> = points.windows(2).map(|pair| distance(&pair[0], &pair[1])).collect();
+        return (distances.iter().cloned().fold(f64::MAX, f64::min), distances.iter().cloned().fold(0., f64::max));
+    }
+
+    #[test]
+    fn deterministic() {
+        for dataset in Dataset::ALL.iter() {
+            assert_eq!(dataset.generate(500, 7), dataset.generate(500, 7));
+            assert_eq!(dataset.generate(500, 7).len(), 500);
+        }
+        assert_ne!(urban_dense(10, 1), urban_dense(10, 2));
+        // pins the generator, changing it invalidates all recorded benchmark results
+        assert_eq!(urban_dense(2, 42)[1], Point::new(52.521865, 13.413229));
+    }
+
+    #[test]
+    fn step_lengths() {
+        let (min, max) = step_distances(&urban_dense(1_000, 3));
+        assert!(min > 4.9 && max < 30.1, "{} {}", min, max);
+        let (min, max) = step_distances(&highway_sparse(1_000, 3));
+        assert!(min > 199. && max < 2_001., "{} {}", min, max);
+    }
+
+    #[test]
+    fn flight() {
+        let points = flight_great_circle(101);
+        assert_eq!(points[0], Point::new(50.0379, 8.5622));
+        assert_eq!(points[100], Point::new(40.6413, -73.7781));
+        let (min, max) = step_distances(&points);
+        assert!(max - min < 1., "{} {}", min, max);
+        assert_eq!(flight_great_circle(1), vec![Point::new(50.0379, 8.5622)]);
+        assert_eq!(flight_great_circle(0), vec![]);
+    }
+
+    #[test]
+    fn perturbed() {
+        let route = highway_sparse(100, 1);
+        let trace = perturb(&route, 15., 9);
+        assert_eq!(trace.len(), route.len());
+        for (point, moved) in route.iter().zip(trace.iter()) {
+            assert!(distance(point, moved) < 15.1);
+        }
+        assert_ne!(perturb(&route, 15., 10), trace);
+        // pins the generator like in `deterministic`
+        assert_eq!(perturb(&[Point::new(52.5, 13.4)], 10., 42), vec![Point::new(52.499999, 13.399976)]);
+    }
+}
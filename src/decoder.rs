@@ -0,0 +1,155 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, DecodeError, decode_element, round, MAX_GROUP_LENGTH, MAX_LAST_CHUNK};
+
+/// Decodes a polyline that arrives in pieces, e.g. over a socket.
+///
+/// Every call to [`feed`](Decoder::feed) returns the points completed by the new piece; an
+/// incomplete group or a latitude without longitude at the end of a piece is kept until the
+/// next one. Pieces may be split anywhere, the points are the same as [`decode`](crate::decode)
+/// gives for the whole polyline.
+///
+/// Like `decode` the decoder ignores everything after the first malformed group;
+/// [`finish`](Decoder::finish) reports it like [`try_decode`](crate::try_decode), with offsets
+/// counted from the start of the first piece.
+///
+/// ```
+/// use polyline_rust::{Point, Decoder};
+///
+/// let mut decoder = Decoder::new(5);
+/// assert_eq!(decoder.feed("_p~iF~ps"), vec![]);
+/// assert_eq!(decoder.feed("|U_ulLnnq"), vec![Point::new(38.5, -120.2)]);
+/// assert_eq!(decoder.feed("C"), vec![Point::new(40.7, -120.95)]);
+/// assert_eq!(decoder.finish(), Ok(()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    precision: u32,
+    /// number of bytes fed so far
+    offset: usize,
+    /// chunks of the incomplete group at the end of the last piece
+    group: String,
+    /// latitude delta waiting for its longitude and the offset of its group
+    pending_latitude: Option<(f64, usize)>,
+    latitude: f64,
+    longitude: f64,
+    error: Option<DecodeError>
+}
+
+impl Decoder {
+    /// Creates a new `Decoder`.
+    ///
+    /// `precision`: usually 5 or 6, see [`decode`](crate::decode)
+    pub fn new(precision: u32) -> Decoder {
+        return Decoder {
+            precision,
+            offset: 0,
+            group: String::new(),
+            pending_latitude: None,
+            latitude: 0.,
+            longitude: 0.,
+            error: None
+        };
+    }
+
+    /// Decodes the next piece of the polyline.
+    ///
+    /// `piece`: continuation of the pieces fed so far
+    ///
+    /// Returns the points completed by `piece`.
+    pub fn feed(&mut self, piece: &str) -> Vec<Point> {
+        let mut points: Vec<Point> = Vec::new();
+        let start = self.offset;
+        self.offset += piece.len();
+        if self.error.is_some() {
+            return points;
+        }
+
+        for (i, letter) in piece.char_indices() {
+            if !('?'..='~').contains(&letter) {
+                self.error = Some(DecodeError::InvalidCharacter { offset: start + i, character: letter });
+                return points;
+            }
+
+            let chunk = letter as u32 - 63;
+            let group_length = self.group.len() + 1;
+            if group_length > MAX_GROUP_LENGTH || (group_length == MAX_GROUP_LENGTH && chunk & 0x1f > MAX_LAST_CHUNK) {
+                self.error = Some(DecodeError::ChunkOutOfRange { offset: start + i + 1 - group_length });
+                return points;
+            }
+
+            self.group.push(letter);
+            if chunk & 0x20 != 0 {
+                continue;
+            }
+
+            let value = decode_element(&self.group, self.precision);
+            let group_offset = start + i + 1 - self.group.len();
+            self.group.clear();
+            match self.pending_latitude.take() {
+                None => self.pending_latitude = Some((value, group_offset)),
+                Some((latitude, _)) => {
+                    // the same rounding as `pair_coordinates`
+                    self.latitude = round(self.latitude + round(latitude, self.precision), self.precision);
+                    self.longitude = round(self.longitude + round(value, self.precision), self.precision);
+                    points.push(Point::new(self.latitude, self.longitude));
+                }
+            }
+        }
+
+        return points;
+    }
+
+    /// Ends the polyline and reports malformed or incomplete input, see [`DecodeError`].
+    pub fn finish(self) -> Result<(), DecodeError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if !self.group.is_empty() {
+            return Err(DecodeError::DanglingGroup { offset: self.offset - self.group.len() });
+        }
+        if let Some((_, offset)) = self.pending_latitude {
+            return Err(DecodeError::DanglingCoordinate { offset });
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, Decoder, decode, try_decode};
+
+    const POLYLINE: &str = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+
+    fn feed_in_pieces(polyline: &str, size: usize) -> (Vec<Point>, Decoder) {
+        let mut decoder = Decoder::new(5);
+        let mut points: Vec<Point> = Vec::new();
+        for piece in polyline.as_bytes().chunks(size) {
+            points.extend(decoder.feed(std::str::from_utf8(piece).unwrap()));
+        }
+        return (points, decoder);
+    }
+
+    #[test]
+    fn any_split() {
+        for size in 1..=POLYLINE.len() {
+            let (points, decoder) = feed_in_pieces(POLYLINE, size);
+            assert_eq!(points, decode(POLYLINE, 5));
+            assert_eq!(decoder.finish(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn errors_like_try_decode() {
+        for polyline in ["_p~iF~ps|U_ulLnnqC_mqN", "_p~iF~ps|U_ulLnnq", "_p~iF~ps!U_ulLnnqC", "_p~iF~ps|U__________________?"] {
+            for size in 1..=polyline.len() {
+                let (points, decoder) = feed_in_pieces(polyline, size);
+                assert_eq!(points, decode(polyline, 5));
+                assert_eq!(decoder.finish(), try_decode(polyline, 5).map(|_| ()));
+            }
+        }
+    }
+}
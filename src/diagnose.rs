@@ -0,0 +1,176 @@
+use std::fmt;
+
+use crate::{Point, decode};
+use crate::validate::{is_well_formed, find_jumps};
+
+/// Consecutive points farther apart than this are reported by [`diagnose`].
+const SUSPICIOUS_JUMP_M: f64 = 1_000_000.;
+
+/// A problem found by [`diagnose`].
+///
+/// The `Display` implementation describes the problem and its likely cause.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Symptom {
+    /// the polyline is an empty string
+    Empty,
+    /// the string contains invalid characters, is truncated or has a dangling latitude
+    Malformed,
+    /// coordinates are outside of the valid range with the assumed precision
+    OutOfRange {
+        /// the lowest higher precision with which all coordinates are in range, if any
+        likely_precision: Option<u32>
+    },
+    /// all coordinates are suspiciously close to 0,0, which happens if the polyline
+    /// was decoded with a precision that is too high
+    NearNullIsland {
+        /// the precision that would spread the coordinates out by a factor of 10
+        likely_precision: u32,
+        /// `true` if all points are within 1° of 0,0, in the open sea of the Gulf of Guinea;
+        /// `false` if they may as well be a real route in West or Central Africa
+        confident: bool
+    },
+    /// some consecutive points are more than 1000km apart
+    LargeJumps {
+        /// number of large jumps
+        count: usize
+    }
+}
+
+impl fmt::Display for Symptom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Symptom::Empty => write!(f, "polyline is empty"),
+            Symptom::Malformed => write!(f, "polyline is malformed → check for truncation or escaping issues (e.g. a doubled backslash)"),
+            Symptom::OutOfRange { likely_precision: Some(precision) } => {
+                write!(f, "coordinates are out of range → likely precision {} decoded with a lower precision", precision)
+            },
+            Symptom::OutOfRange { likely_precision: None } => write!(f, "coordinates are out of range with any precision → data is likely corrupted"),
+            Symptom::NearNullIsland { likely_precision, confident: true } => {
+                write!(f, "all coordinates are 10× or more too close to 0,0 → likely precision {} decoded with a higher precision", likely_precision)
+            },
+            Symptom::NearNullIsland { likely_precision, confident: false } => {
+                write!(f, "all coordinates are close to 0,0 → unless the route is in West or Central Africa, possibly precision {} decoded with a higher precision", likely_precision)
+            },
+            Symptom::LargeJumps { count } => write!(f, "{} jumps of more than 1000km between consecutive points → data may be corrupted", count)
+        };
+    }
+}
+
+/// Result of [`diagnose`].
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Diagnosis {
+    /// all problems found, empty if the polyline looks fine
+    pub symptoms: Vec<Symptom>
+}
+
+impl Diagnosis {
+    /// Returns `true` if no problems were found.
+    pub fn is_healthy(&self) -> bool {
+        return self.symptoms.is_empty();
+    }
+}
+
+/// Looks for common problems with a polyline and their likely causes,
+/// for example a polyline with precision 6 that is decoded with precision 5.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `assumed_precision`: precision the polyline is believed to be encoded with
+///
+/// ```
+/// use polyline_rust::{diagnose, Symptom};
+///
+/// // precision 6 polyline of Vienna
+/// let diagnosis = diagnose("ewl}zAwthf^ctAobBsUnl@", 5);
+/// assert_eq!(diagnosis.symptoms, vec![Symptom::OutOfRange { likely_precision: Some(6) }]);
+/// ```
+pub fn diagnose(polyline: &str, assumed_precision: u32) -> Diagnosis {
+    let mut diagnosis = Diagnosis::default();
+
+    if polyline.is_empty() {
+        diagnosis.symptoms.push(Symptom::Empty);
+        return diagnosis;
+    }
+    if !is_well_formed(polyline) {
+        diagnosis.symptoms.push(Symptom::Malformed);
+        return diagnosis;
+    }
+
+    let points = decode(polyline, assumed_precision);
+    if !in_range(&points, 1.) {
+        let likely_precision = (assumed_precision+1..=9)
+            .find(|precision| in_range(&decode(polyline, *precision), 1.));
+        diagnosis.symptoms.push(Symptom::OutOfRange { likely_precision });
+        return diagnosis;
+    }
+
+    if assumed_precision > 0 && in_range(&points, 0.1) {
+        // there is no land within 1° of 0,0, further away the points can be real
+        let confident = points.iter().all(|point| point.latitude.abs() <= 1. && point.longitude.abs() <= 1.);
+        diagnosis.symptoms.push(Symptom::NearNullIsland { likely_precision: assumed_precision - 1, confident });
+    }
+
+    let jumps = find_jumps(&points, SUSPICIOUS_JUMP_M).len();
+    if jumps > 0 {
+        diagnosis.symptoms.push(Symptom::LargeJumps { count: jumps });
+    }
+
+    return diagnosis;
+}
+
+/// Checks that all points are within the valid range scaled by `factor`.
+fn in_range(points: &[Point], factor: f64) -> bool {
+    return points.iter().all(|point| {
+        point.latitude.abs() <= 90. * factor && point.longitude.abs() <= 180. * factor
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, encode, diagnose, Symptom};
+
+    #[test]
+    fn healthy() {
+        assert!(diagnose("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5).is_healthy());
+    }
+
+    #[test]
+    fn empty_and_malformed() {
+        assert_eq!(diagnose("", 5).symptoms, vec![Symptom::Empty]);
+        assert_eq!(diagnose("_p~iF~ps|", 5).symptoms, vec![Symptom::Malformed]);
+    }
+
+    #[test]
+    fn precision_too_high() {
+        assert_eq!(diagnose("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 6).symptoms, vec![
+            Symptom::NearNullIsland { likely_precision: 5, confident: false }
+        ]);
+
+        // Berlin with precision 5, decoded with precision 7
+        let berlin = encode(vec![Point::new(52.52, 13.405), Point::new(52.521, 13.41)], 5);
+        assert_eq!(diagnose(&berlin, 7).symptoms, vec![
+            Symptom::NearNullIsland { likely_precision: 6, confident: true }
+        ]);
+    }
+
+    #[test]
+    fn west_africa() {
+        // a route in Lagos looks like a wrong precision, but only with low confidence
+        let lagos = encode(vec![Point::new(6.4541, 3.3947), Point::new(6.4654, 3.4064)], 5);
+        let diagnosis = diagnose(&lagos, 5);
+        assert_eq!(diagnosis.symptoms, vec![Symptom::NearNullIsland { likely_precision: 4, confident: false }]);
+        assert!(diagnosis.symptoms[0].to_string().contains("unless the route is in West or Central Africa"));
+    }
+
+    #[test]
+    fn large_jumps() {
+        let polyline = encode(vec![
+            Point::new(48.208771, 16.372572),
+            Point::new(-33.868820, 151.209290),
+            Point::new(48.208771, 16.372572)
+        ], 6);
+        assert_eq!(diagnose(&polyline, 6).symptoms, vec![
+            Symptom::LargeJumps { count: 2 }
+        ]);
+    }
+}
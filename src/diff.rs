@@ -0,0 +1,285 @@
+use std::ops::Range;
+
+use crate::Point;
+use crate::geodesy::distance;
+
+/// A section of a route that differs between two versions, see [`changed_sections`].
+///
+/// Either range may be empty: an empty `old_range` means points were inserted,
+/// an empty `new_range` means points were removed.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ChangedSection {
+    /// indices of the affected points in the old polyline
+    pub old_range: Range<usize>,
+    /// indices of the affected points in the new polyline
+    pub new_range: Range<usize>
+}
+
+/// Largest number of inserted and removed points that is aligned point by point, the
+/// alignment keeps about `MAX_EDITS²` offsets in memory.
+const MAX_EDITS: usize = 2048;
+
+/// Marks an offset of a diagonal that can't be reached within the polylines.
+const UNREACHABLE: usize = usize::MAX;
+
+/// Finds the portions of an updated route that differ from the old route.
+///
+/// Points of both polylines are aligned in order (like a line based diff); two points are
+/// considered the same if they are at most `tolerance_m` meters apart. Every run of points that
+/// could not be aligned is reported as a [`ChangedSection`], ordered by position.
+///
+/// The alignment (Myers' algorithm) takes time proportional to the length of the polylines
+/// times the number of inserted and removed points. If more than 2048 points were inserted
+/// or removed, everything between the unchanged start and end of the route is reported as a
+/// single section.
+pub fn changed_sections(old: &[Point], new: &[Point], tolerance_m: f64) -> Vec<ChangedSection> {
+    let same = |i: usize, j: usize| distance(&old[i], &new[j]) <= tolerance_m;
+
+    // unchanged start and end of the route don't need to take part in the alignment
+    let mut prefix = 0;
+    while prefix < old.len() && prefix < new.len() && same(prefix, prefix) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < old.len() - prefix && suffix < new.len() - prefix
+        && same(old.len() - suffix - 1, new.len() - suffix - 1) {
+        suffix += 1;
+    }
+
+    let old_end = old.len() - suffix;
+    let new_end = new.len() - suffix;
+    if prefix == old_end && prefix == new_end {
+        return vec![];
+    }
+
+    let moves = match align(old_end - prefix, new_end - prefix, |i, j| same(prefix + i, prefix + j)) {
+        Some(moves) => moves,
+        None => return vec![ChangedSection { old_range: prefix..old_end, new_range: prefix..new_end }]
+    };
+
+    let mut sections: Vec<ChangedSection> = Vec::new();
+    let mut current: Option<ChangedSection> = None;
+    let (mut i, mut j) = (prefix, prefix);
+    for step in moves {
+        if step == Move::Both {
+            if let Some(section) = current.take() {
+                sections.push(section);
+            }
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        let section = current.get_or_insert(ChangedSection {
+            old_range: i..i,
+            new_range: j..j
+        });
+        if step == Move::Old {
+            i += 1;
+            section.old_range.end = i;
+        } else {
+            j += 1;
+            section.new_range.end = j;
+        }
+    }
+    if let Some(section) = current {
+        sections.push(section);
+    }
+
+    return sections;
+}
+
+/// A step of the alignment.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Move {
+    /// the points are the same
+    Both,
+    /// a point of the old polyline was removed
+    Old,
+    /// a point of the new polyline was inserted
+    New
+}
+
+/// Shortest alignment of `rows` old and `columns` new points (Myers' algorithm), `None` if it
+/// takes more than [`MAX_EDITS`] insertions and removals.
+///
+/// `offsets[d][k + d]` is the furthest old index reached with `d` insertions and removals on
+/// the diagonal `k` (old index minus new index).
+fn align<F>(rows: usize, columns: usize, same: F) -> Option<Vec<Move>>
+    where F: Fn(usize, usize) -> bool {
+    let snake = |mut x: usize, k: isize| {
+        while x < rows && ((x as isize - k) as usize) < columns && same(x, (x as isize - k) as usize) {
+            x += 1;
+        }
+        return x;
+    };
+
+    // the ends of both polylines are on this diagonal
+    let last = rows as isize - columns as isize;
+    let mut offsets: Vec<Vec<usize>> = vec![vec![snake(0, 0)]];
+    let mut edits = 0;
+    while last.unsigned_abs() > edits || offsets[edits][(last + edits as isize) as usize] != rows {
+        if edits == MAX_EDITS {
+            return None;
+        }
+
+        edits += 1;
+        let d = edits as isize;
+        let mut current = vec![UNREACHABLE; 2 * edits + 1];
+        for k in (-d..=d).step_by(2) {
+            if let Some((x, _)) = step(&offsets[edits - 1], d, k, rows, columns) {
+                current[(k + d) as usize] = snake(x, k);
+            }
+        }
+        offsets.push(current);
+    }
+
+    // walk back from the end of both polylines
+    let mut moves: Vec<Move> = Vec::with_capacity(rows + columns);
+    let mut x = rows;
+    let mut k = last;
+    for d in (1..=edits).rev() {
+        let (start, is_insertion) = step(&offsets[d - 1], d as isize, k, rows, columns)?;
+        for _ in start..x {
+            moves.push(Move::Both);
+        }
+        if is_insertion {
+            moves.push(Move::New);
+            k += 1;
+            x = start;
+        } else {
+            moves.push(Move::Old);
+            k -= 1;
+            x = start - 1;
+        }
+    }
+    for _ in 0..x {
+        moves.push(Move::Both);
+    }
+    moves.reverse();
+
+    return Some(moves);
+}
+
+/// The step onto diagonal `k` with `d` edits: the old index after it and whether it's an
+/// insertion (from diagonal `k + 1`) or a removal (from diagonal `k - 1`).
+///
+/// `previous`: the offsets with `d - 1` edits
+fn step(previous: &[usize], d: isize, k: isize, rows: usize, columns: usize) -> Option<(usize, bool)> {
+    let offset = |k: isize| {
+        if k < -(d - 1) || k > d - 1 {
+            return UNREACHABLE;
+        }
+        return previous[(k + d - 1) as usize];
+    };
+
+    // an insertion keeps the old index, a removal advances it
+    let insertion = match offset(k + 1) {
+        UNREACHABLE => None,
+        x if x as isize - k <= columns as isize => Some(x),
+        _ => None
+    };
+    let removal = match offset(k - 1) {
+        UNREACHABLE => None,
+        x if x < rows => Some(x + 1),
+        _ => None
+    };
+
+    return match (insertion, removal) {
+        (Some(inserted), Some(removed)) if inserted >= removed => Some((inserted, true)),
+        (_, Some(removed)) => Some((removed, false)),
+        (Some(inserted), None) => Some((inserted, true)),
+        (None, None) => None
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, changed_sections, ChangedSection};
+
+    fn line() -> Vec<Point> {
+        return vec![
+            Point::new(48.2, 16.30),
+            Point::new(48.2, 16.31),
+            Point::new(48.2, 16.32),
+            Point::new(48.2, 16.33),
+            Point::new(48.2, 16.34),
+        ];
+    }
+
+    #[test]
+    fn unchanged() {
+        assert_eq!(changed_sections(&line(), &line(), 1.), vec![]);
+    }
+
+    #[test]
+    fn moved_point() {
+        let mut new = line();
+        new[2] = Point::new(48.21, 16.32);
+        assert_eq!(changed_sections(&line(), &new, 1.), vec![
+            ChangedSection { old_range: 2..3, new_range: 2..3 }
+        ]);
+    }
+
+    #[test]
+    fn inserted_and_removed_points() {
+        let mut new = line();
+        new.remove(4);
+        new.insert(1, Point::new(48.21, 16.305));
+        assert_eq!(changed_sections(&line(), &new, 1.), vec![
+            ChangedSection { old_range: 1..1, new_range: 1..2 },
+            ChangedSection { old_range: 4..5, new_range: 5..5 },
+        ]);
+    }
+
+    #[test]
+    fn shortest_alignment() {
+        // pseudo random points out of three, aligned like the longest common subsequence
+        let mut seed: u32 = 7;
+        let mut points = |count: usize| -> Vec<Point> {
+            return (0..count).map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                return Point::new(0., ((seed >> 16) % 3) as f64);
+            }).collect();
+        };
+        for (rows, columns) in [(12, 9), (20, 20), (1, 15), (30, 7)] {
+            let (old, new) = (points(rows), points(columns));
+            let mut lcs = vec![vec![0; columns + 1]; rows + 1];
+            for i in (0..rows).rev() {
+                for j in (0..columns).rev() {
+                    lcs[i][j] = if old[i] == new[j] { lcs[i+1][j+1] + 1 } else { lcs[i+1][j].max(lcs[i][j+1]) };
+                }
+            }
+
+            let changed: usize = changed_sections(&old, &new, 1.).iter()
+                .map(|section| section.old_range.len() + section.new_range.len())
+                .sum();
+            assert_eq!(changed, rows + columns - 2 * lcs[0][0]);
+        }
+    }
+
+    #[test]
+    fn long_traces() {
+        let old: Vec<Point> = (0..50_000).map(|i| Point::new(48.2, 16. + i as f64 * 1e-5)).collect();
+        let mut new = old.clone();
+        new[10_000] = Point::new(48.3, 16.1);
+        new.drain(30_000..30_005);
+        assert_eq!(changed_sections(&old, &new, 0.1), vec![
+            ChangedSection { old_range: 10_000..10_001, new_range: 10_000..10_001 },
+            ChangedSection { old_range: 30_000..30_005, new_range: 30_000..30_000 },
+        ]);
+
+        // too many changes to align them point by point
+        let moved: Vec<Point> = old.iter().map(|point| Point::new(point.latitude + 1., point.longitude)).collect();
+        assert_eq!(changed_sections(&old, &moved, 0.1), vec![
+            ChangedSection { old_range: 0..50_000, new_range: 0..50_000 }
+        ]);
+    }
+
+    #[test]
+    fn within_tolerance() {
+        let mut new = line();
+        new[2] = Point::new(48.20001, 16.32);
+        assert_eq!(changed_sections(&line(), &new, 5.), vec![]);
+    }
+}
@@ -0,0 +1,567 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{Coordinate, RangeError, MAX_GROUP_LENGTH, push_integer, group_value, round_to_integer, signed_value};
+use crate::precision::scale;
+use crate::Point;
+#[cfg(feature = "std")]
+use crate::geodesy::{distance, distance_to_segment};
+
+/// Replaces the points `range` of an encoded polyline with `replacement`.
+///
+/// Only the replaced points and the delta of the first point after them are encoded, the
+/// groups before and after are copied as they are. Small edits of long routes cost a scan of
+/// the groups up to the end of the range instead of decoding and encoding all points. The
+/// replacement points are rounded to `precision` before their deltas are taken, so the result
+/// is the same as decoding, splicing the rounded points and encoding them again. Unrounded
+/// points may end up one unit away from what [`encode`](crate::encode) writes for them, which
+/// rounds the deltas instead.
+///
+/// `encoded`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `range`: indices of the replaced points, e.g. `3..3` to insert before the fourth point
+///
+/// `replacement`: new points, see [`encode`](crate::encode)
+///
+/// `precision`: precision of the polyline
+///
+/// Returns a [`RangeError`] if the start of the range is after its end or the end is after the
+/// last point; like [`decode`](crate::decode) an invalid character or an incomplete point ends
+/// the polyline.
+///
+/// ```
+/// use polyline_rust::{Point, encode, splice_encoded};
+///
+/// let route = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95), Point::new(43.252, -126.453)];
+/// let edited = splice_encoded(&encode(&route, 5), 1..2, &[Point::new(41., -121.5)], 5).unwrap();
+/// assert_eq!(edited, encode(vec![Point::new(38.5, -120.2), Point::new(41., -121.5), Point::new(43.252, -126.453)], 5));
+/// ```
+pub fn splice_encoded<I>(encoded: &str, range: Range<usize>, replacement: I, precision: u32) -> Result<String, RangeError>
+    where I: IntoIterator, I::Item: Coordinate {
+    if range.start > range.end {
+        return Err(RangeError { range, points: PointOffsets::new(encoded).count() });
+    }
+
+    let mut points = PointOffsets::new(encoded);
+    let mut previous: (i64, i64) = (0, 0);
+    for index in 0..range.end {
+        let point = match points.next() {
+            Some(point) => point,
+            None => return Err(RangeError { range, points: index })
+        };
+        if index < range.start {
+            previous = point;
+        }
+        if index + 1 == range.start {
+            // the groups of the replaced points start here
+            points.mark();
+        }
+    }
+    let start = if range.start == 0 { 0 } else { points.marked };
+
+    let mut spliced = String::with_capacity(encoded.len());
+    spliced.push_str(&encoded[..start]);
+    for point in replacement {
+        let point = (to_units(point.lat(), precision), to_units(point.lng(), precision));
+        push_delta(&mut spliced, previous, point);
+        previous = point;
+    }
+
+    let tail = points.offset;
+    if let Some(next) = points.next() {
+        push_delta(&mut spliced, previous, next);
+        spliced.push_str(&encoded[points.offset..]);
+    } else {
+        // nothing (or only a malformed rest) follows, keep it as it is
+        spliced.push_str(&encoded[tail..]);
+    }
+
+    return Ok(spliced);
+}
+
+/// Removes the points `range` of an encoded polyline.
+///
+/// Like [`splice_encoded`] without replacement: only the delta of the first point after the
+/// range is encoded again, all other groups are copied.
+///
+/// `encoded`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision of the polyline
+///
+/// `range`: indices of the removed points
+///
+/// Returns a [`RangeError`] for ranges that aren't part of the polyline, see [`splice_encoded`].
+///
+/// ```
+/// use polyline_rust::{Point, encode, remove_range_encoded};
+///
+/// let route = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95), Point::new(43.252, -126.453)];
+/// let edited = remove_range_encoded(&encode(&route, 5), 5, 1..2).unwrap();
+/// assert_eq!(edited, encode(vec![Point::new(38.5, -120.2), Point::new(43.252, -126.453)], 5));
+/// assert!(remove_range_encoded("_p~iF~ps|U_ulL", 5, 1..2).is_err());
+/// ```
+pub fn remove_range_encoded(encoded: &str, precision: u32, range: Range<usize>) -> Result<String, RangeError> {
+    return splice_encoded(encoded, range, core::iter::empty::<&Point>(), precision);
+}
+
+/// Appends the points of `b` to the points of `a`, e.g. to join the legs of a route.
+///
+/// The groups of `a` are only read to find its last point, nothing is converted to `f64`;
+/// `b` is copied except for its first delta, which is written again as integer relative to
+/// the last point of `a`. The result is the same as encoding the points of both polylines together.
+/// Like [`decode`](crate::decode) an invalid character or an incomplete point ends `a`.
+///
+/// `a`, `b`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `_precision`: precision of both polylines; the deltas are integers, so the result is the
+/// same for every precision
+///
+/// ```
+/// use polyline_rust::{Point, encode, concat_encoded};
+///
+/// let first_leg = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+/// let second_leg = vec![Point::new(40.7, -120.95), Point::new(43.252, -126.453)];
+/// let route = concat_encoded(&encode(&first_leg, 5), &encode(&second_leg, 5), 5);
+/// assert_eq!(route, encode(first_leg.iter().chain(second_leg.iter()), 5));
+/// ```
+pub fn concat_encoded(a: &str, b: &str, _precision: u32) -> String {
+    return join(a, b, false);
+}
+
+/// Appends `b` to `a`; only the first delta of `b` is encoded again.
+///
+/// `merge_seam`: drop the first point of `b` if it's the last point of `a`
+fn join(a: &str, b: &str, merge_seam: bool) -> String {
+    let mut a_points = PointOffsets::new(a);
+    let a_last = a_points.by_ref().last();
+    let mut b_points = PointOffsets::new(b);
+
+    let mut joined = String::with_capacity(a.len() + b.len());
+    joined.push_str(&a[..a_points.offset]);
+    match (a_last, b_points.next()) {
+        (Some(last), Some(first)) if merge_seam && last == first => {},
+        (last, Some(first)) => push_delta(&mut joined, last.unwrap_or((0, 0)), first),
+        (_, None) => {}
+    }
+    joined.push_str(&b[b_points.offset..]);
+
+    return joined;
+}
+
+/// Reverses the order of the points of an encoded polyline.
+///
+/// The groups are read once into integer coordinates and the deltas are written from the
+/// other end, without converting anything to `f64` and back. Like [`decode`](crate::decode)
+/// it stops at the first invalid character and drops an incomplete point at the end.
+///
+/// `encoded`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `_precision`: precision of the polyline; the deltas are reversed as integers, so the
+/// result is the same for every precision
+///
+/// ```
+/// use polyline_rust::{Point, encode, reverse_encoded};
+///
+/// let mut route = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95), Point::new(43.252, -126.453)];
+/// let reversed = reverse_encoded(&encode(&route, 5), 5);
+/// route.reverse();
+/// assert_eq!(reversed, encode(&route, 5));
+/// ```
+pub fn reverse_encoded(encoded: &str, _precision: u32) -> String {
+    let points: Vec<(i64, i64)> = PointOffsets::new(encoded).collect();
+
+    let mut reversed = String::with_capacity(encoded.len());
+    let mut previous: (i64, i64) = (0, 0);
+    for point in points.into_iter().rev() {
+        push_delta(&mut reversed, previous, point);
+        previous = point;
+    }
+
+    return reversed;
+}
+
+/// Joins two fragments of a line, e.g. pieces of a road clipped at tile borders, if two of
+/// their ends are at most `max_gap_m` apart.
+///
+/// The closest pair of ends is joined; `b` is reversed if needed, `a` keeps its direction.
+/// Ends at the same position (at the precision of the polylines) are merged into one point,
+/// otherwise the gap is bridged by a segment between them. The groups of both polylines are
+/// copied except for the delta at the seam, reversing `b` re-encodes it though.
+///
+/// `a`, `b`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `max_gap_m`: largest distance in meters between the joined ends
+///
+/// `precision`: precision of both polylines
+///
+/// Returns `None` if the fragments are too far apart. An empty fragment doesn't need to be
+/// joined, the other one is returned as it is.
+///
+/// ```
+/// use polyline_rust::{Point, encode, stitch};
+///
+/// let a = encode(vec![Point::new(52.5, 13.4), Point::new(52.5, 13.41)], 5);
+/// let b = encode(vec![Point::new(52.51, 13.41), Point::new(52.50001, 13.41)], 5);
+/// assert_eq!(stitch(&a, &b, 5., 5), Some(encode(vec![
+///     Point::new(52.5, 13.4), Point::new(52.5, 13.41), Point::new(52.50001, 13.41), Point::new(52.51, 13.41)
+/// ], 5)));
+/// assert_eq!(stitch(&a, &b, 1., 5), None);
+/// ```
+#[cfg(feature = "std")]
+pub fn stitch(a: &str, b: &str, max_gap_m: f64, precision: u32) -> Option<String> {
+    let ((a_first, a_last), (b_first, b_last)) = match (ends(a), ends(b)) {
+        (Some(a_ends), Some(b_ends)) => (a_ends, b_ends),
+        (None, _) => return Some(String::from(b)),
+        (_, None) => return Some(String::from(a))
+    };
+
+    let scale = scale(precision);
+    let gap = |from: (i64, i64), to: (i64, i64)| {
+        let from = Point::new(from.0 as f64 / scale, from.1 as f64 / scale);
+        let to = Point::new(to.0 as f64 / scale, to.1 as f64 / scale);
+        return distance(&from, &to);
+    };
+
+    // the seams in order of preference, the first one keeps the direction of both
+    let seams = [
+        (gap(a_last, b_first), Seam::AB),
+        (gap(a_last, b_last), Seam::AReversedB),
+        (gap(b_last, a_first), Seam::BA),
+        (gap(b_first, a_first), Seam::ReversedBA)
+    ];
+    let mut best = &seams[0];
+    for seam in seams[1..].iter() {
+        if seam.0 < best.0 {
+            best = seam;
+        }
+    }
+    if max_gap_m.is_nan() || best.0 > max_gap_m {
+        return None;
+    }
+
+    return Some(match best.1 {
+        Seam::AB => join(a, b, true),
+        Seam::AReversedB => join(a, &reverse_encoded(b, precision), true),
+        Seam::BA => join(b, a, true),
+        Seam::ReversedBA => join(&reverse_encoded(b, precision), a, true)
+    });
+}
+
+/// Ends of two polylines joined by [`stitch`].
+#[cfg(feature = "std")]
+enum Seam {
+    AB,
+    AReversedB,
+    BA,
+    ReversedBA
+}
+
+/// First and last point of a polyline, `None` if it has no points.
+#[cfg(feature = "std")]
+fn ends(encoded: &str) -> Option<((i64, i64), (i64, i64))> {
+    let mut points = PointOffsets::new(encoded);
+    let first = points.next()?;
+    return Some((first, points.last().unwrap_or(first)));
+}
+
+/// Inserts `via` into the segment of an encoded route closest to it, e.g. when a user drags
+/// the route through a point.
+///
+/// The points are decoded one at a time while looking for the segment, without collecting
+/// them; the via point is then spliced in with [`splice_encoded`]. A route of a single point
+/// is extended by the via point.
+///
+/// `encoded`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision of the polyline
+///
+/// `via`: the point the route has to pass
+///
+/// ```
+/// use polyline_rust::{Point, encode, insert_via};
+///
+/// let route = vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.)];
+/// let edited = insert_via(&encode(&route, 5), 5, Point::new(0.1, 0.5));
+/// assert_eq!(edited, encode(vec![Point::new(0., 0.), Point::new(0.1, 0.5), Point::new(0., 1.), Point::new(1., 1.)], 5));
+/// ```
+#[cfg(feature = "std")]
+pub fn insert_via(encoded: &str, precision: u32, via: Point) -> String {
+    let scale = scale(precision);
+    let mut previous: Option<Point> = None;
+    // index of the point after the closest segment so far and the distance to it
+    let mut best: Option<(usize, f64)> = None;
+    for (i, (latitude, longitude)) in PointOffsets::new(encoded).enumerate() {
+        let point = Point::new(latitude as f64 / scale, longitude as f64 / scale);
+        if let Some(previous) = previous {
+            let d = distance_to_segment(&via, &previous, &point);
+            if best.is_none_or(|(_, best_distance)| d < best_distance) {
+                best = Some((i, d));
+            }
+        }
+        previous = Some(point);
+    }
+
+    let index = match (best, previous) {
+        (Some((index, _)), _) => index,
+        (None, Some(_)) => 1,
+        (None, None) => 0
+    };
+    // the index is at most the number of points, so the range is always valid
+    return splice_encoded(encoded, index..index, [via], precision).unwrap_or_else(|_| String::from(encoded));
+}
+
+/// Points of an encoded polyline as integer coordinates, i.e. scaled by `10^precision`,
+/// together with the byte offset after the last point read.
+///
+/// Works on the integers in the string, so points are never rounded again; the iteration
+/// ends at the first malformed group or incomplete point.
+struct PointOffsets<'a> {
+    polyline: &'a str,
+    /// byte offset of the next point
+    offset: usize,
+    /// offset saved by [`PointOffsets::mark`]
+    marked: usize,
+    latitude: i64,
+    longitude: i64
+}
+
+impl<'a> PointOffsets<'a> {
+    fn new(polyline: &'a str) -> PointOffsets<'a> {
+        return PointOffsets {
+            polyline,
+            offset: 0,
+            marked: 0,
+            latitude: 0,
+            longitude: 0
+        };
+    }
+
+    /// Remembers the current offset.
+    fn mark(&mut self) {
+        self.marked = self.offset;
+    }
+
+    /// Value of the group starting at `offset` and the offset after it.
+    fn group(&self, offset: usize) -> Option<(i64, usize)> {
+        let bytes = self.polyline.as_bytes();
+        let mut end = offset;
+        loop {
+            let letter = *bytes.get(end)?;
+            if !(b'?'..=b'~').contains(&letter) || end - offset == MAX_GROUP_LENGTH {
+                return None;
+            }
+            end += 1;
+            if (letter - 63) & 0x20 == 0 {
+                break;
+            }
+        }
+
+        return Some((signed_value(group_value(&self.polyline[offset..end])), end));
+    }
+}
+
+impl<'a> Iterator for PointOffsets<'a> {
+    type Item = (i64, i64);
+
+    fn next(&mut self) -> Option<(i64, i64)> {
+        let (latitude, end) = self.group(self.offset)?;
+        let (longitude, end) = self.group(end)?;
+
+        self.offset = end;
+        // adversarial deltas may overflow, the result is garbage then but nothing panics
+        self.latitude = self.latitude.wrapping_add(latitude);
+        self.longitude = self.longitude.wrapping_add(longitude);
+        return Some((self.latitude, self.longitude));
+    }
+}
+
+/// Coordinate scaled to an integer like `encode` does.
+fn to_units(coordinate: f64, precision: u32) -> i64 {
+    return round_to_integer(coordinate * scale(precision)) as i64;
+}
+
+/// Appends the groups of the step from `from` to `to`, both integer coordinates.
+fn push_delta(encoded: &mut String, from: (i64, i64), to: (i64, i64)) {
+    push_integer(encoded, to.0.wrapping_sub(from.0));
+    push_integer(encoded, to.1.wrapping_sub(from.1));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, RangeError, encode, concat_encoded, remove_range_encoded, reverse_encoded, splice_encoded, push_integer};
+    #[cfg(feature = "std")]
+    use crate::{insert_via, stitch};
+
+    fn route() -> Vec<Point> {
+        return vec![
+            Point::new(38.5, -120.2),
+            Point::new(40.7, -120.95),
+            Point::new(43.252, -126.453),
+            Point::new(43.1, -126.),
+            Point::new(-12.00001, 80.5)
+        ];
+    }
+
+    fn spliced(range: std::ops::Range<usize>, replacement: &[Point]) -> String {
+        let mut points = route();
+        points.splice(range, replacement.iter().cloned());
+        return encode(points, 5);
+    }
+
+    #[test]
+    fn same_as_reencoding() {
+        let encoded = encode(route(), 5);
+        let replacement = vec![Point::new(1., 2.), Point::new(-3.5, 4.25)];
+        for start in 0..=5 {
+            for end in start..=5 {
+                assert_eq!(splice_encoded(&encoded, start..end, &replacement, 5), Ok(spliced(start..end, &replacement)));
+                assert_eq!(splice_encoded(&encoded, start..end, &[] as &[Point], 5), Ok(spliced(start..end, &[])));
+            }
+        }
+        assert_eq!(splice_encoded("", 0..0, &replacement, 5), Ok(encode(&replacement, 5)));
+    }
+
+    #[test]
+    fn rounds_replacement() {
+        let encoded = encode(route(), 5);
+        // 0.4 and 0.6 units north of the first point, `encode` would round both deltas to 0
+        let replacement = vec![Point::new(38.500004, -120.2), Point::new(38.500006, -120.2)];
+        let rounded = vec![Point::new(38.5, -120.2), Point::new(38.50001, -120.2)];
+        assert_eq!(splice_encoded(&encoded, 1..1, &replacement, 5), Ok(spliced(1..1, &rounded)));
+        assert_ne!(splice_encoded(&encoded, 1..1, &replacement, 5), Ok(spliced(1..1, &replacement)));
+    }
+
+    #[test]
+    fn keeps_untouched_groups() {
+        // a superfluous zero chunk in the last point and an incomplete point at the end
+        let encoded = "_p~iF~ps|U_ulLnnqC_mqNvxq`@__??_p~iF";
+        let edited = splice_encoded(encoded, 1..2, [Point::new(40., -121.)], 5).unwrap();
+        assert!(edited.starts_with("_p~iF~ps|U") && edited.ends_with("__??_p~iF"));
+        assert_eq!(crate::decode(&edited, 5), vec![
+            Point::new(38.5, -120.2),
+            Point::new(40., -121.),
+            Point::new(43.252, -126.453),
+            Point::new(43.252, -126.453)
+        ]);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn range_out_of_bounds() {
+        let error = splice_encoded(&encode(route(), 5), 4..6, &[] as &[Point], 5).unwrap_err();
+        assert_eq!(error, RangeError { range: 4..6, points: 5 });
+        assert_eq!(error.to_string(), "range end 6 is out of range for a polyline of 5 points");
+
+        let error = remove_range_encoded(&encode(route(), 5), 5, 3..2).unwrap_err();
+        assert_eq!(error.to_string(), "range starts at 3 but ends at 2");
+        // malformed input ends the polyline
+        assert_eq!(remove_range_encoded("_p~iF~ps|U_ulL\u{0}nnqC", 5, 1..2), Err(RangeError { range: 1..2, points: 1 }));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn via_closest_segment() {
+        let encoded = encode(route(), 5);
+        let via = Point::new(43.2, -126.3);
+        assert_eq!(insert_via(&encoded, 5, via), spliced(3..3, std::slice::from_ref(&via)));
+        let via = Point::new(39., -120.);
+        assert_eq!(insert_via(&encoded, 5, via), spliced(1..1, std::slice::from_ref(&via)));
+
+        let single = encode(vec![Point::new(1., 1.)], 5);
+        assert_eq!(insert_via(&single, 5, via), encode(vec![Point::new(1., 1.), via], 5));
+        assert_eq!(insert_via("", 5, via), encode(vec![via], 5));
+    }
+
+    #[test]
+    fn remove_range() {
+        let encoded = encode(route(), 5);
+        for start in 0..=5 {
+            for end in start..=5 {
+                assert_eq!(remove_range_encoded(&encoded, 5, start..end), Ok(spliced(start..end, &[])));
+            }
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let mut points = route();
+        let encoded = encode(&points, 5);
+        points.reverse();
+        assert_eq!(reverse_encoded(&encoded, 5), encode(&points, 5));
+        assert_eq!(reverse_encoded(&reverse_encoded(&encoded, 5), 5), encoded);
+        // superfluous chunks are gone, so is the incomplete point at the end
+        assert_eq!(reverse_encoded("_p~iF~ps|U__??_p~iF", 5), "_p~iF~ps|U??");
+        assert_eq!(reverse_encoded("", 5), "");
+    }
+
+    /// Polyline of the given integer deltas.
+    fn integer_groups(values: &[i64]) -> String {
+        let mut encoded = String::new();
+        for value in values.iter() {
+            push_integer(&mut encoded, *value);
+        }
+        return encoded;
+    }
+
+    #[test]
+    fn reverse_exact_integers() {
+        // 2^53 + 1 has no exact `f64`
+        let huge = (1_i64 << 53) + 1;
+        assert_eq!(reverse_encoded(&integer_groups(&[huge, 0, -huge, 0]), 5), integer_groups(&[0, 0, huge, 0]));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn stitch_fragments() {
+        let a = vec![Point::new(52.5, 13.4), Point::new(52.5, 13.41)];
+        let b = vec![Point::new(52.5, 13.41), Point::new(52.51, 13.42)];
+        let joined = encode(vec![a[0], a[1], b[1]], 5);
+        let (a_encoded, b_encoded) = (encode(&a, 5), encode(&b, 5));
+        let b_reversed = encode(b.iter().rev(), 5);
+
+        // shared end points are merged in every orientation
+        assert_eq!(stitch(&a_encoded, &b_encoded, 0., 5), Some(joined.clone()));
+        assert_eq!(stitch(&a_encoded, &b_reversed, 0., 5), Some(joined.clone()));
+        assert_eq!(stitch(&b_encoded, &a_encoded, 0., 5), Some(joined.clone()));
+        assert_eq!(stitch(&b_reversed, &a_encoded, 0., 5), Some(encode(vec![b[1], a[1], a[0]], 5)));
+
+        // the gap is bridged
+        let c = vec![Point::new(52.50002, 13.41), Point::new(52.6, 13.5)];
+        assert_eq!(stitch(&a_encoded, &encode(&c, 5), 3., 5), Some(encode(a.iter().chain(c.iter()), 5)));
+        assert_eq!(stitch(&a_encoded, &encode(&c, 5), 2., 5), None);
+
+        assert_eq!(stitch("", &b_encoded, 0., 5), Some(b_encoded.clone()));
+        assert_eq!(stitch(&a_encoded, "", 0., 5), Some(a_encoded.clone()));
+    }
+
+    #[test]
+    fn concat() {
+        let points = route();
+        for i in 0..=points.len() {
+            let (a, b) = (encode(&points[..i], 5), encode(&points[i..], 5));
+            assert_eq!(concat_encoded(&a, &b, 5), encode(&points, 5));
+        }
+        // the end point of the first leg is the start point of the second one
+        let legs = concat_encoded(&encode(&points[..2], 5), &encode(&points[1..], 5), 5);
+        assert_eq!(crate::decode(&legs, 5).len(), points.len() + 1);
+    }
+
+    #[test]
+    fn concat_exact_integers() {
+        // the new first delta of `b`, 2^53 + 1, has no exact `f64`
+        let huge = (1_i64 << 53) + 1;
+        assert_eq!(concat_encoded(&integer_groups(&[1, 0]), &integer_groups(&[huge + 1, 0]), 5), integer_groups(&[1, 0, huge, 0]));
+    }
+
+    #[test]
+    fn huge_deltas() {
+        // the largest positive delta, three of them overflow an i64
+        let polyline = "}~~~~~~~~~~~F?".repeat(3);
+        assert_eq!(reverse_encoded(&polyline, 5).len(), polyline.len());
+        assert!(!concat_encoded(&polyline, &polyline, 5).is_empty());
+        #[cfg(feature = "std")]
+        assert!(!insert_via(&polyline, 5, Point::new(1., 1.)).is_empty());
+    }
+}
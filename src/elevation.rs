@@ -0,0 +1,202 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Coordinate, DecodeError, decode_coordinates, push_element, last_group_offset, round};
+use crate::precision::scale;
+
+/// Single coordinate of a point on a polyline with elevation.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point3 {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// usually meters above sea level
+    pub elevation: f64
+}
+
+impl Point3 {
+    /// Creates a new `Point3`.
+    pub fn new(latitude: f64, longitude: f64, elevation: f64) -> Point3 {
+        return Point3 {
+            latitude,
+            longitude,
+            elevation
+        };
+    }
+}
+
+impl Coordinate for Point3 {
+    fn lat(&self) -> f64 {
+        return self.latitude;
+    }
+
+    fn lng(&self) -> f64 {
+        return self.longitude;
+    }
+
+    fn elevation(&self) -> Option<f64> {
+        return Some(self.elevation);
+    }
+}
+
+/// Encodes coordinates with elevation; every point is a latitude, longitude and elevation delta.
+///
+/// This is the format of elevation-enabled polylines of GraphHopper (`points_encoded` with
+/// `elevation=true`, elevation precision 2) and Valhalla.
+///
+/// `points`: points of the polyline, e.g. [`Point3`]s or domain types with an
+/// [`elevation`](Coordinate::elevation), see [`encode`](crate::encode); points without
+/// elevation keep the one of the previous point, 0 at the start
+///
+/// `precision`: precision of latitude and longitude, usually 5 or 6, see [`encode`](crate::encode)
+///
+/// `elevation_precision`: number of decimals of the elevation, e.g. 2 for centimeters
+///
+/// ```
+/// use polyline_rust::{Point3, encode3, decode3};
+///
+/// let points = vec![Point3::new(47.3, 8.5, 408.), Point3::new(47.31, 8.52, 415.25)];
+/// let polyline = encode3(&points, 5, 2);
+/// assert_eq!(decode3(&polyline, 5, 2), points);
+/// ```
+pub fn encode3<I>(points: I, precision: u32, elevation_precision: u32) -> String
+    where I: IntoIterator, I::Item: Coordinate {
+    let mut encoded = String::new();
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    let mut elevation: f64 = 0.;
+
+    for point in points {
+        let point_elevation = point.elevation().unwrap_or(elevation);
+        push_element(&mut encoded, point.lat() - latitude, precision);
+        push_element(&mut encoded, point.lng() - longitude, precision);
+        push_element(&mut encoded, point_elevation - elevation, elevation_precision);
+
+        latitude = point.lat();
+        longitude = point.lng();
+        elevation = point_elevation;
+    }
+
+    return encoded;
+}
+
+/// Decodes coordinates with elevation, see [`encode3`].
+///
+/// Like [`decode`](crate::decode) it stops at the first invalid character and silently
+/// drops an incomplete point at the end.
+///
+/// `polyline`: polyline string with elevation
+///
+/// `precision`: precision of latitude and longitude
+///
+/// `elevation_precision`: number of decimals of the elevation
+pub fn decode3(polyline: &str, precision: u32, elevation_precision: u32) -> Vec<Point3> {
+    let (values, _) = decode_coordinates(polyline, 0);
+    return tuple_values(&values, precision, elevation_precision);
+}
+
+/// Decodes coordinates with elevation and fails on malformed input, see [`try_decode`](crate::try_decode).
+///
+/// `polyline`: polyline string with elevation
+///
+/// `precision`: precision of latitude and longitude
+///
+/// `elevation_precision`: number of decimals of the elevation
+pub fn try_decode3(polyline: &str, precision: u32, elevation_precision: u32) -> Result<Vec<Point3>, DecodeError> {
+    let (values, error) = decode_coordinates(polyline, 0);
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    if values.len() % 3 != 0 {
+        // offset of the first group of the incomplete point
+        let mut offset = polyline.len();
+        for _ in 0..values.len() % 3 {
+            offset = last_group_offset(&polyline[..offset]);
+        }
+        return Err(DecodeError::DanglingCoordinate { offset });
+    }
+
+    return Ok(tuple_values(&values, precision, elevation_precision));
+}
+
+/// Turns unscaled deltas into points with the same rounding as `pair_coordinates`.
+fn tuple_values(values: &[f64], precision: u32, elevation_precision: u32) -> Vec<Point3> {
+    let elevation_scale = scale(elevation_precision);
+    let scale = scale(precision);
+
+    let mut points: Vec<Point3> = Vec::with_capacity(values.len() / 3);
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    let mut elevation: f64 = 0.;
+    for tuple in values.chunks_exact(3) {
+        latitude = round(latitude + round(tuple[0] / scale, precision), precision);
+        longitude = round(longitude + round(tuple[1] / scale, precision), precision);
+        elevation = round(elevation + round(tuple[2] / elevation_scale, elevation_precision), elevation_precision);
+        points.push(Point3::new(latitude, longitude, elevation));
+    }
+
+    return points;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Point3, Coord, DecodeError, encode3, decode3, try_decode3, encode};
+
+    struct GpsFix {
+        lat: f64,
+        lon: f64,
+        altitude: Option<f64>
+    }
+
+    impl Coord for GpsFix {
+        fn lat(&self) -> f64 {
+            return self.lat;
+        }
+
+        fn lng(&self) -> f64 {
+            return self.lon;
+        }
+
+        fn elevation(&self) -> Option<f64> {
+            return self.altitude;
+        }
+    }
+
+    #[test]
+    fn graphhopper() {
+        // elevations encoded like GraphHopper with a precision of 2
+        let points = vec![
+            Point3::new(49.94, 11.57, 461.),
+            Point3::new(49.941, 11.571, 459.5),
+            Point3::new(49.9405, 11.57211, -3.07)
+        ];
+        let polyline = encode3(&points, 5, 2);
+        assert!(polyline.starts_with(&encode(vec![Point::new(49.94, 11.57)], 5)));
+        assert_eq!(try_decode3(&polyline, 5, 2), Ok(points));
+    }
+
+    #[test]
+    fn incomplete_point() {
+        let polyline = encode3(&[Point3::new(1., 2., 3.), Point3::new(4., 5., 6.)], 5, 2);
+        let truncated = &polyline[..polyline.len() - 2];
+        assert_eq!(decode3(truncated, 5, 2), vec![Point3::new(1., 2., 3.)]);
+        assert_eq!(try_decode3(truncated, 5, 2), Err(DecodeError::DanglingCoordinate { offset: 10 }));
+        assert_eq!(try_decode3("_p~iF", 5, 2), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+
+    #[test]
+    fn domain_type() {
+        let fixes = vec![
+            GpsFix { lat: 49.94, lon: 11.57, altitude: Some(461.) },
+            GpsFix { lat: 49.941, lon: 11.571, altitude: None },
+            GpsFix { lat: 49.942, lon: 11.572, altitude: Some(459.5) }
+        ];
+        let points = vec![Point::new(49.94, 11.57), Point::new(49.941, 11.571), Point::new(49.942, 11.572)];
+        assert_eq!(encode(&fixes, 5), encode(&points, 5));
+        // the fix without altitude keeps the previous one instead of dropping to 0
+        assert_eq!(decode3(&encode3(&fixes, 5, 2), 5, 2),
+                   vec![Point3::new(49.94, 11.57, 461.), Point3::new(49.941, 11.571, 461.), Point3::new(49.942, 11.572, 459.5)]);
+        assert_eq!(decode3(&encode3(vec![(49.94, 11.57)], 5, 2), 5, 2), vec![Point3::new(49.94, 11.57, 0.)]);
+    }
+}
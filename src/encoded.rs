@@ -0,0 +1,284 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::convert::TryFrom;
+use core::str::FromStr;
+
+use crate::{Point, Coordinate, DecodeError, Precision, encode, decode, scan_groups, try_decode};
+#[cfg(feature = "std")]
+use crate::Bounds;
+
+/// An encoded polyline string together with the precision it was encoded with.
+///
+/// Passing an `EncodedPolyline` around instead of a bare `String` keeps the precision with the
+/// string. The string is taken as-is and may be malformed, [`Polyline`] is checked to be valid.
+///
+/// Ordering, equality and hashing are byte-wise on the encoded string first and on the
+/// precision second, which makes `EncodedPolyline` usable as key in `BTreeMap`/`HashMap`.
+/// Byte-wise comparison has no geometric meaning: it only groups polylines that start with the
+/// same points. Different strings can describe the same geometry (e.g. with superfluous zero
+/// chunks), use [`EncodedPolyline::canonical`] to make sure equal geometries compare equal.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
+pub struct EncodedPolyline {
+    encoded: String,
+    precision: u32
+}
+
+/// A well-formed encoded polyline together with the precision it was encoded with.
+///
+/// Unlike [`EncodedPolyline`] the string is checked when the `Polyline` is created, so decoding
+/// it can't fail. It displays as the encoded string and parses from one with Google's
+/// precision 5, use [`Polyline::parse`] for any other precision:
+///
+/// ```
+/// use polyline_rust::{Point, Polyline};
+///
+/// let polyline: Polyline = "_p~iF~ps|U_ulLnnqC".parse().unwrap();
+/// assert_eq!(polyline.len_points(), 2);
+/// assert_eq!(polyline.decode()[1], Point::new(40.7, -120.95));
+/// assert_eq!(polyline.to_string(), "_p~iF~ps|U_ulLnnqC");
+///
+/// assert!("_p~iF".parse::<Polyline>().is_err());
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
+pub struct Polyline(EncodedPolyline);
+
+impl EncodedPolyline {
+    /// Creates a new `EncodedPolyline` from an encoded string as-is.
+    pub fn new(encoded: String, precision: u32) -> EncodedPolyline {
+        return EncodedPolyline {
+            encoded,
+            precision
+        };
+    }
+
+    /// Encodes points into a new `EncodedPolyline`, see [`encode`].
+    pub fn encode<I>(points: I, precision: u32) -> EncodedPolyline
+        where I: IntoIterator, I::Item: Coordinate {
+        return EncodedPolyline {
+            encoded: encode(points, precision),
+            precision
+        };
+    }
+
+    /// Creates a new `EncodedPolyline` in canonical form, malformed strings are rejected.
+    ///
+    /// The polyline is decoded and encoded again, which yields the shortest encoding of every
+    /// coordinate. Two canonical polylines with the same precision are equal if and only if
+    /// they describe the same points.
+    pub fn canonical(encoded: &str, precision: u32) -> Result<EncodedPolyline, DecodeError> {
+        return Ok(EncodedPolyline {
+            encoded: encode(try_decode(encoded, precision)?, precision),
+            precision
+        });
+    }
+
+    /// The encoded polyline string.
+    pub fn as_str(&self) -> &str {
+        return &self.encoded;
+    }
+
+    /// The precision the polyline was encoded with.
+    pub fn precision(&self) -> u32 {
+        return self.precision;
+    }
+
+    /// Consumes the `EncodedPolyline` and returns the encoded string.
+    pub fn into_string(self) -> String {
+        return self.encoded;
+    }
+
+    /// Number of points, counted without decoding them.
+    ///
+    /// Like [`decode`] an invalid character or an incomplete point ends the polyline.
+    pub fn len_points(&self) -> usize {
+        let mut groups = 0;
+        let _ = scan_groups(&self.encoded, |_| groups += 1);
+
+        return groups / 2;
+    }
+
+    /// Decodes the points, see [`decode`].
+    pub fn decode(&self) -> Vec<Point> {
+        return decode(&self.encoded, self.precision);
+    }
+
+    /// Decodes the points and fails on malformed input, see [`try_decode`].
+    pub fn try_decode(&self) -> Result<Vec<Point>, DecodeError> {
+        return try_decode(&self.encoded, self.precision);
+    }
+
+    /// Bounding box of the points, `None` if there are none.
+    ///
+    /// Computed without decoding the points, see [`decode_bounds`](crate::decode_bounds). Like
+    /// [`decode`] an invalid character or an incomplete point ends the polyline.
+    #[cfg(feature = "std")]
+    pub fn bounds(&self) -> Option<Bounds> {
+        return match crate::decode_bounds(&self.encoded, self.precision) {
+            Ok(bounds) => bounds,
+            // only malformed polylines are decoded
+            Err(_) => crate::bounds(&self.decode())
+        };
+    }
+}
+
+impl fmt::Display for EncodedPolyline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str(&self.encoded);
+    }
+}
+
+impl Polyline {
+    /// Takes the string if it is a valid polyline, see [`try_decode`].
+    ///
+    /// `encoded`: polyline string in "Encoded Polyline Algorithm Format"
+    ///
+    /// `precision`: usually 5 or 6
+    pub fn parse(encoded: &str, precision: u32) -> Result<Polyline, DecodeError> {
+        try_decode(encoded, precision)?;
+
+        return Ok(Polyline(EncodedPolyline::new(String::from(encoded), precision)));
+    }
+
+    /// Encodes points into a new `Polyline`, see [`encode`].
+    pub fn encode<I>(points: I, precision: u32) -> Polyline
+        where I: IntoIterator, I::Item: Coordinate {
+        return Polyline(EncodedPolyline::encode(points, precision));
+    }
+
+    /// The encoded polyline string.
+    pub fn as_str(&self) -> &str {
+        return self.0.as_str();
+    }
+
+    /// The precision the polyline was encoded with.
+    pub fn precision(&self) -> u32 {
+        return self.0.precision();
+    }
+
+    /// Consumes the `Polyline` and returns the encoded string.
+    pub fn into_string(self) -> String {
+        return self.0.into_string();
+    }
+
+    /// Number of points, counted without decoding them.
+    pub fn len_points(&self) -> usize {
+        return self.0.len_points();
+    }
+
+    /// Decodes the points.
+    pub fn decode(&self) -> Vec<Point> {
+        return self.0.decode();
+    }
+
+    /// Bounding box of the points, `None` if there are none; computed without decoding the
+    /// points, see [`decode_bounds`](crate::decode_bounds).
+    #[cfg(feature = "std")]
+    pub fn bounds(&self) -> Option<Bounds> {
+        return self.0.bounds();
+    }
+}
+
+impl fmt::Display for Polyline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return fmt::Display::fmt(&self.0, f);
+    }
+}
+
+impl FromStr for Polyline {
+    type Err = DecodeError;
+
+    /// Takes the string with precision 5, if it is a valid polyline.
+    fn from_str(encoded: &str) -> Result<Polyline, DecodeError> {
+        return Polyline::parse(encoded, Precision::FIVE.digits());
+    }
+}
+
+impl TryFrom<EncodedPolyline> for Polyline {
+    type Error = DecodeError;
+
+    fn try_from(polyline: EncodedPolyline) -> Result<Polyline, DecodeError> {
+        try_decode(polyline.as_str(), polyline.precision())?;
+
+        return Ok(Polyline(polyline));
+    }
+}
+
+impl From<Polyline> for EncodedPolyline {
+    fn from(polyline: Polyline) -> EncodedPolyline {
+        return polyline.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use std::collections::BTreeMap;
+    use std::convert::TryFrom;
+    use crate::{Point, DecodeError, EncodedPolyline, Polyline};
+
+    #[test]
+    fn byte_wise_order() {
+        assert!(EncodedPolyline::new("A".to_string(), 5) < EncodedPolyline::new("a".to_string(), 5));
+        assert!(EncodedPolyline::new("a".to_string(), 5) < EncodedPolyline::new("a".to_string(), 6));
+    }
+
+    #[test]
+    fn canonical_removes_superfluous_chunks() {
+        assert_eq!(EncodedPolyline::canonical("_?_?", 5).unwrap().as_str(), "??");
+        assert_eq!(EncodedPolyline::canonical("_?_?", 5), EncodedPolyline::canonical("??", 5));
+    }
+
+    #[test]
+    fn canonical_rejects_malformed() {
+        assert_eq!(EncodedPolyline::canonical("_p~iF~ps|U_ulL", 5), Err(DecodeError::DanglingCoordinate { offset: 10 }));
+        assert!(EncodedPolyline::canonical("_p~iF~ps|U!", 5).is_err());
+    }
+
+    #[test]
+    fn btree_map_key() {
+        let mut map = BTreeMap::new();
+        map.insert(EncodedPolyline::canonical("_p~iF~ps|U", 5).unwrap(), 1);
+        map.insert(EncodedPolyline::canonical("_p~iF~ps|U", 5).unwrap(), 2);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[&EncodedPolyline::new("_p~iF~ps|U".to_string(), 5)], 2);
+    }
+
+    #[test]
+    fn methods() {
+        let polyline = EncodedPolyline::encode(vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)], 6);
+        assert_eq!(polyline.precision(), 6);
+        assert_eq!(polyline.len_points(), 2);
+        assert_eq!(polyline.try_decode(), Ok(vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)]));
+        assert_eq!(format!("{}", polyline), polyline.as_str());
+
+        let dangling = EncodedPolyline::new("_p~iF~ps|U_ulL".to_string(), 5);
+        assert_eq!(dangling.len_points(), dangling.decode().len());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn bounds() {
+        let polyline = EncodedPolyline::encode(vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)], 6);
+        assert_eq!(polyline.bounds(), Some(crate::Bounds::new(38.5, -120.95, 40.7, -120.2)));
+        assert_eq!(EncodedPolyline::new(String::new(), 5).bounds(), None);
+        assert_eq!(Polyline::encode(vec![Point::new(38.5, -120.2)], 5).bounds(), Some(crate::Bounds::new(38.5, -120.2, 38.5, -120.2)));
+
+        let dangling = EncodedPolyline::new("_p~iF~ps|U_ulL".to_string(), 5);
+        assert_eq!(dangling.bounds(), Some(crate::Bounds::new(38.5, -120.2, 38.5, -120.2)));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("_p~iF~ps|U".parse::<Polyline>().map(|polyline| polyline.precision()), Ok(5));
+        assert_eq!("_p~iF".parse::<Polyline>(), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+        assert_eq!(Polyline::parse("_p~iF~ps|U", 6).map(|polyline| polyline.decode()), Ok(vec![Point::new(3.85, -12.02)]));
+    }
+
+    #[test]
+    fn polyline_from_encoded() {
+        let valid = EncodedPolyline::new("_p~iF~ps|U".to_string(), 5);
+        assert_eq!(Polyline::try_from(valid.clone()).map(EncodedPolyline::from), Ok(valid));
+        assert!(Polyline::try_from(EncodedPolyline::new("_p~iF".to_string(), 5)).is_err());
+    }
+}
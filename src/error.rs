@@ -0,0 +1,321 @@
+use core::error;
+use core::fmt;
+use core::ops::Range;
+
+/// Error returned by the fallible decode functions.
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The polyline contains a character outside of the range `?` to `~`.
+    InvalidCharacter {
+        /// byte offset of the character
+        offset: usize,
+        character: char
+    },
+    /// A group encodes a value that doesn't fit into 64 bits.
+    ChunkOutOfRange {
+        /// byte offset of the group
+        offset: usize
+    },
+    /// The polyline ends in the middle of a group.
+    DanglingGroup {
+        /// byte offset of the unterminated group
+        offset: usize
+    },
+    /// The polyline ends with a latitude without longitude.
+    DanglingCoordinate {
+        /// byte offset of the latitude
+        offset: usize
+    },
+    /// The data doesn't start with the expected header, e.g. because it's empty.
+    MissingHeader,
+    /// Two consecutive points are farther apart than allowed by
+    /// [`DecodeOptions::max_jump_m`](crate::DecodeOptions::max_jump_m).
+    JumpTooLarge {
+        /// index of the second point of the jump
+        index: usize,
+        /// distance between the points in meters
+        distance_m: f64
+    },
+    /// A decoded point is outside of the valid range, see
+    /// [`DecodeOptions::reject_out_of_range`](crate::DecodeOptions::reject_out_of_range).
+    OutOfRange {
+        /// index of the point
+        index: usize,
+        latitude: f64,
+        longitude: f64
+    }
+}
+
+impl DecodeError {
+    /// Byte offset in the polyline of the problem, `None` if there isn't a single position.
+    pub fn offset(&self) -> Option<usize> {
+        return match self {
+            DecodeError::InvalidCharacter { offset, .. } => Some(*offset),
+            DecodeError::ChunkOutOfRange { offset } => Some(*offset),
+            DecodeError::DanglingGroup { offset } => Some(*offset),
+            DecodeError::DanglingCoordinate { offset } => Some(*offset),
+            DecodeError::MissingHeader => Some(0),
+            DecodeError::JumpTooLarge { .. } => None,
+            DecodeError::OutOfRange { .. } => None
+        };
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            DecodeError::InvalidCharacter { offset, character } => {
+                write!(f, "invalid character {:?} at offset {}", character, offset)
+            },
+            DecodeError::ChunkOutOfRange { offset } => write!(f, "value out of range at offset {}", offset),
+            DecodeError::DanglingGroup { offset } => write!(f, "unterminated group at offset {}", offset),
+            DecodeError::DanglingCoordinate { offset } => write!(f, "latitude without longitude at offset {}", offset),
+            DecodeError::MissingHeader => write!(f, "missing header"),
+            DecodeError::JumpTooLarge { index, distance_m } => {
+                write!(f, "point {} is {:.1}m away from its predecessor", index, distance_m)
+            },
+            DecodeError::OutOfRange { index, latitude, longitude } => {
+                write!(f, "point {} ({}, {}) is outside of the valid range", index, latitude, longitude)
+            }
+        };
+    }
+}
+
+impl error::Error for DecodeError {}
+
+/// Error returned when reading geometries from other formats, e.g. GeoJSON.
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum FormatError {
+    /// The input isn't well-formed.
+    Syntax {
+        /// byte offset of the problem
+        offset: usize
+    },
+    /// The input is well-formed but doesn't contain a `LineString`.
+    NotALineString,
+    /// A coordinate isn't a pair of numbers or is outside of the valid range.
+    InvalidCoordinate {
+        /// index of the point
+        index: usize
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            FormatError::Syntax { offset } => write!(f, "syntax error at offset {}", offset),
+            FormatError::NotALineString => write!(f, "not a LineString"),
+            FormatError::InvalidCoordinate { index } => write!(f, "invalid coordinate of point {}", index)
+        };
+    }
+}
+
+impl error::Error for FormatError {}
+
+/// Error returned by the cancellable operations when the cancellation flag was set, e.g.
+/// [`validate_batch_cancellable`](crate::validate_batch_cancellable).
+#[derive(PartialEq, Debug, Clone)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "operation cancelled");
+    }
+}
+
+impl error::Error for Cancelled {}
+
+/// Error returned when a range of points isn't part of a polyline, see
+/// [`splice_encoded`](crate::splice_encoded).
+#[derive(PartialEq, Debug, Clone)]
+pub struct RangeError {
+    /// the requested range
+    pub range: Range<usize>,
+    /// number of points of the polyline, up to the first invalid character
+    pub points: usize
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.range.start > self.range.end {
+            return write!(f, "range starts at {} but ends at {}", self.range.start, self.range.end);
+        }
+        return write!(f, "range end {} is out of range for a polyline of {} points", self.range.end, self.points);
+    }
+}
+
+impl error::Error for RangeError {}
+
+/// Error returned when points don't make up a valid polyline, see [`validate_points`](crate::validate_points).
+#[derive(PartialEq, Debug, Clone)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// A latitude is outside of [-90, 90] or a longitude outside of [-180, 180].
+    OutOfRange {
+        /// index of the point
+        index: usize,
+        latitude: f64,
+        longitude: f64
+    },
+    /// A coordinate is NaN or infinite.
+    NotFinite {
+        /// index of the point
+        index: usize
+    },
+    /// The last point of a ring isn't its first point, see [`decode_ring`](crate::decode_ring).
+    NotClosed,
+    /// A coordinate or its difference to the previous point doesn't fit into the fixed-point
+    /// integers of the target format at the requested precision, e.g. the 32-bit deltas of
+    /// `proto::LineString`.
+    Overflow {
+        /// index of the point
+        index: usize
+    },
+    /// The polyline string is malformed, see [`validate`](crate::validate).
+    Malformed(DecodeError)
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ValidationError::OutOfRange { index, latitude, longitude } if !(-90. ..=90.).contains(latitude) => {
+                write!(f, "latitude {} of point {} ({}, {}) is outside of [-90, 90]", latitude, index, latitude, longitude)
+            },
+            ValidationError::OutOfRange { index, latitude, longitude } => {
+                write!(f, "longitude {} of point {} ({}, {}) is outside of [-180, 180]", longitude, index, latitude, longitude)
+            },
+            ValidationError::NotFinite { index } => write!(f, "point {} has a coordinate that isn't a finite number", index),
+            ValidationError::NotClosed => write!(f, "the ring isn't closed"),
+            ValidationError::Overflow { index } => write!(f, "point {} overflows the fixed-point integers at this precision", index),
+            ValidationError::Malformed(error) => write!(f, "malformed polyline: {}", error)
+        };
+    }
+}
+
+impl error::Error for ValidationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        return match self {
+            ValidationError::Malformed(error) => Some(error),
+            _ => None
+        };
+    }
+}
+
+/// Any error of the crate, for applications that handle them in one place.
+///
+/// Every specific error converts into `Error` with `?`. The specific error is available by
+/// matching and as [`source`](error::Error::source), so reporters that walk the source chain
+/// (e.g. `anyhow`) print both.
+///
+/// ```
+/// use polyline_rust::{Error, DecodeError, try_decode};
+///
+/// fn first_latitude(polyline: &str) -> Result<f64, Error> {
+///     return Ok(try_decode(polyline, 5)?.first().map_or(0., |point| point.latitude));
+/// }
+///
+/// match first_latitude("_p~iF") {
+///     Err(Error::Decode(DecodeError::DanglingCoordinate { offset })) => assert_eq!(offset, 0),
+///     _ => unreachable!()
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A polyline string is malformed.
+    Decode(DecodeError),
+    /// Points don't make up a valid polyline.
+    Validation(ValidationError),
+    /// Input in another format, e.g. GeoJSON, can't be read.
+    Format(FormatError),
+    /// A range of points isn't part of a polyline.
+    Range(RangeError),
+    /// The operation was cancelled.
+    Cancelled(Cancelled),
+    /// Reading or writing a file failed.
+    #[cfg(feature = "std")]
+    Io(std::io::Error)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the details are in the source
+        return match self {
+            Error::Decode(_) => write!(f, "malformed polyline"),
+            Error::Validation(_) => write!(f, "invalid points"),
+            Error::Format(_) => write!(f, "unreadable input"),
+            Error::Range(_) => write!(f, "invalid range"),
+            Error::Cancelled(_) => write!(f, "cancelled"),
+            #[cfg(feature = "std")]
+            Error::Io(_) => write!(f, "I/O error")
+        };
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        return match self {
+            Error::Decode(error) => Some(error),
+            Error::Validation(error) => Some(error),
+            Error::Format(error) => Some(error),
+            Error::Range(error) => Some(error),
+            Error::Cancelled(error) => Some(error),
+            #[cfg(feature = "std")]
+            Error::Io(error) => Some(error)
+        };
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(error: DecodeError) -> Error {
+        return Error::Decode(error);
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(error: ValidationError) -> Error {
+        return Error::Validation(error);
+    }
+}
+
+impl From<FormatError> for Error {
+    fn from(error: FormatError) -> Error {
+        return Error::Format(error);
+    }
+}
+
+impl From<RangeError> for Error {
+    fn from(error: RangeError) -> Error {
+        return Error::Range(error);
+    }
+}
+
+impl From<Cancelled> for Error {
+    fn from(error: Cancelled) -> Error {
+        return Error::Cancelled(error);
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        return Error::Io(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use core::error::Error as _;
+    use crate::{Error, DecodeError, Cancelled};
+
+    #[test]
+    fn source_chain() {
+        let error = Error::from(DecodeError::DanglingGroup { offset: 3 });
+        assert_eq!(error.to_string(), "malformed polyline");
+        assert_eq!(error.source().map(|source| source.to_string()), Some(String::from("unterminated group at offset 3")));
+        assert!(matches!(Error::from(Cancelled), Error::Cancelled(Cancelled)));
+    }
+}
@@ -0,0 +1,145 @@
+use alloc::vec::Vec;
+
+use crate::{Point, signed_value, MAX_LAST_CHUNK};
+use crate::precision::scale;
+
+/// A byte of `1` in every lane of a word.
+const ONES: u64 = u64::from_ne_bytes([1; 8]);
+
+/// The highest bit of every lane of a word.
+const HIGHS: u64 = ONES * 0x80;
+
+/// Largest integer coordinate for which summing integers gives the same floats as the
+/// rounding of `pair_coordinates`; far beyond any coordinate on earth even at precision 9.
+const MAX_EXACT: u64 = 1 << 50;
+
+/// Decodes long polylines in a single pass over the bytes, without a buffer per group.
+///
+/// The bytes are checked and the points counted eight at a time (SWAR, "SIMD within a
+/// register"), so the output is allocated exactly once. Then every group is accumulated
+/// directly into an integer with shifts and masks, and the integer coordinates are summed up
+/// and divided only once per point instead of rounding every delta and sum. The points are
+/// bit-identical to the ones of the general decoder. Returns `None` for anything the general
+/// decoder has to deal with: malformed input, groups that don't fit into 64 bits, huge coordinates
+/// and an odd number of values.
+pub(crate) fn decode(polyline: &str, precision: u32) -> Option<Vec<Point>> {
+    let bytes = polyline.as_bytes();
+    let values = count_values(bytes)?;
+    if values % 2 == 1 {
+        return None;
+    }
+
+    let scale = scale(precision);
+    let mut points: Vec<Point> = Vec::with_capacity(values / 2);
+    let mut latitude: i64 = 0;
+    let mut longitude: i64 = 0;
+    let mut pending_latitude: Option<i64> = None;
+
+    let mut value: u64 = 0;
+    let mut shift = 0;
+    for byte in bytes.iter() {
+        let chunk = (*byte - 63) as u64;
+        value |= (chunk & 0x1f) << shift;
+        shift += 5;
+        if chunk & 0x20 != 0 {
+            // a 13th chunk has to end the group
+            if shift > 60 {
+                return None;
+            }
+            continue;
+        }
+        if shift > 60 && chunk > MAX_LAST_CHUNK as u64 {
+            return None;
+        }
+
+        let delta = signed_value(value);
+        value = 0;
+        shift = 0;
+        match pending_latitude.take() {
+            None => pending_latitude = Some(delta),
+            Some(delta_latitude) => {
+                latitude += delta_latitude;
+                longitude += delta;
+                if latitude.unsigned_abs() > MAX_EXACT || longitude.unsigned_abs() > MAX_EXACT {
+                    return None;
+                }
+                points.push(Point::new(latitude as f64 / scale, longitude as f64 / scale));
+            }
+        }
+    }
+    if shift != 0 {
+        return None;
+    }
+
+    return Some(points);
+}
+
+/// Number of groups, `None` if a byte is outside of `?` to `~`.
+fn count_values(bytes: &[u8]) -> Option<usize> {
+    let mut count = 0;
+    let mut words = bytes.chunks_exact(8);
+    for word in words.by_ref() {
+        let word = u64::from_ne_bytes([word[0], word[1], word[2], word[3], word[4], word[5], word[6], word[7]]);
+        // lanes below `?` borrow into their high bit, lanes above `~` carry into it
+        let below = word.wrapping_sub(ONES * 63) & !word & HIGHS;
+        let above = (word.wrapping_add(ONES) | word) & HIGHS;
+        if below | above != 0 {
+            return None;
+        }
+
+        // a group ends with a chunk without continuation bit, i.e. a byte below `_`; adding
+        // 33 sets the high bit of all other lanes and can't carry as all lanes are below 128
+        count += (!word.wrapping_add(ONES * 33) & HIGHS).count_ones() as usize;
+    }
+
+    for byte in words.remainder() {
+        if !(b'?'..=b'~').contains(byte) {
+            return None;
+        }
+        if *byte < b'_' {
+            count += 1;
+        }
+    }
+
+    return Some(count);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, encode, decode_coordinates, pair_coordinates};
+    use crate::fast::{decode, count_values};
+
+    #[test]
+    fn same_as_general_decoder() {
+        let mut track: Vec<Point> = (0..500)
+            .map(|i| Point::new(-89.9 + (i * 37 % 1799) as f64 * 0.1 + 0.0000017 * i as f64, 179.9 - (i * 53 % 3599) as f64 * 0.1))
+            .collect();
+        // negative deltas that round to zero are encoded as `?`
+        track.push(Point::new(track[499].latitude - 1e-12, track[499].longitude));
+        assert!(encode(&track, 5).ends_with("??"));
+        for precision in [0, 5, 6, 9] {
+            let polyline = encode(&track, precision);
+            let (coordinates, _) = decode_coordinates(&polyline, precision);
+            assert_eq!(decode(&polyline, precision), Some(pair_coordinates(&coordinates, precision)));
+        }
+    }
+
+    #[test]
+    fn falls_back() {
+        for polyline in ["_p~iF~ps|U_ulLnnqC_mqN", "_p~iF~ps|U_ulLnnq", "_p~iF~ps|U_ulL nnqC", "_p~iF~ps|U_ulL\u{7f}nnqC", "_p~iF~ps|U__________________?"] {
+            assert_eq!(decode(polyline, 5), None);
+        }
+        assert_eq!(decode("", 5), Some(vec![]));
+    }
+
+    #[test]
+    fn counting() {
+        assert_eq!(count_values(b"_p~iF~ps|U_ulLnnqC_mqNvxq`@"), Some(6));
+        assert_eq!(count_values(b"????????????????"), Some(16));
+        assert_eq!(count_values(b"~~~~~~~~~~~~~~~~"), Some(0));
+        for invalid in [&b">???????????"[..], b"???????\x7f????", b"???????????\x80", b"????????{??>"] {
+            assert_eq!(count_values(invalid), None);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! C interface for programs written in C or C++, e.g. routing engines.
+//!
+//! Build a static or shared library with
+//! `cargo rustc --release --features ffi --crate-type staticlib` (or `cdylib`) and include
+//! `include/polyline_rust.h`, which is generated by `cbindgen --config cbindgen.toml`.
+//!
+//! Points are passed as interleaved latitudes and longitudes, `{lat0, lng0, lat1, lng1, ...}`.
+//! Functions return `NULL` on invalid input. Everything the library returns has to be released
+//! by the library:
+//!
+//! ```c
+//! double coordinates[] = {38.5, -120.2, 40.7, -120.95};
+//! char *polyline = polyline_encode(coordinates, 4, 5);
+//!
+//! size_t length;
+//! double *decoded = polyline_decode(polyline, 5, &length);
+//!
+//! polyline_free_coordinates(decoded, length);
+//! polyline_free(polyline);
+//! ```
+
+use std::boxed::Box;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use std::vec::Vec;
+
+use crate::{Point, encode, try_decode_bytes};
+
+/// Encodes interleaved coordinates to a NUL terminated polyline, see [`encode`].
+///
+/// `coordinates`: latitude and longitude of every point, one after the other
+///
+/// `length`: number of coordinates, twice the number of points
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+///
+/// Returns `NULL` for an odd number of coordinates. Release the polyline with [`polyline_free`].
+///
+/// # Safety
+///
+/// `coordinates` has to point to `length` doubles, it may be `NULL` if `length` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn polyline_encode(coordinates: *const f64, length: usize, precision: u32) -> *mut c_char {
+    if length % 2 == 1 || (coordinates.is_null() && length > 0) {
+        return ptr::null_mut();
+    }
+    let coordinates: &[f64] = if length == 0 { &[] } else { slice::from_raw_parts(coordinates, length) };
+
+    let polyline = encode(coordinates.chunks_exact(2).map(|pair| Point::new(pair[0], pair[1])), precision);
+    // polylines consist of `?` to `~`, there is no NUL to fail on
+    return match CString::new(polyline) {
+        Ok(polyline) => polyline.into_raw(),
+        Err(_) => ptr::null_mut()
+    };
+}
+
+/// Decodes a NUL terminated polyline into interleaved coordinates, see
+/// [`try_decode`](crate::try_decode).
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`](crate::decode)
+///
+/// `length`: receives the number of coordinates, twice the number of points
+///
+/// Returns `NULL` for malformed polylines. Release the coordinates with
+/// [`polyline_free_coordinates`].
+///
+/// # Safety
+///
+/// `polyline` has to be a NUL terminated string and `length` has to point to a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn polyline_decode(polyline: *const c_char, precision: u32, length: *mut usize) -> *mut f64 {
+    if polyline.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    *length = 0;
+
+    let points = match try_decode_bytes(CStr::from_ptr(polyline).to_bytes(), precision) {
+        Ok(points) => points,
+        Err(_) => return ptr::null_mut()
+    };
+
+    let mut coordinates: Vec<f64> = Vec::with_capacity(points.len() * 2);
+    for point in points.iter() {
+        coordinates.push(point.latitude);
+        coordinates.push(point.longitude);
+    }
+
+    *length = coordinates.len();
+    return Box::into_raw(coordinates.into_boxed_slice()) as *mut f64;
+}
+
+/// Releases a polyline returned by [`polyline_encode`], `NULL` is ignored.
+///
+/// # Safety
+///
+/// `polyline` has to be returned by [`polyline_encode`] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn polyline_free(polyline: *mut c_char) {
+    if !polyline.is_null() {
+        drop(CString::from_raw(polyline));
+    }
+}
+
+/// Releases coordinates returned by [`polyline_decode`], `NULL` is ignored.
+///
+/// `length`: the number of coordinates returned by [`polyline_decode`]
+///
+/// # Safety
+///
+/// `coordinates` has to be returned by [`polyline_decode`] together with `length` and must not
+/// be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn polyline_free_coordinates(coordinates: *mut f64, length: usize) {
+    if !coordinates.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(coordinates, length)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+    use crate::ffi::{polyline_encode, polyline_decode, polyline_free, polyline_free_coordinates};
+
+    #[test]
+    fn round_trip() {
+        let coordinates = [38.5, -120.2, 40.7, -120.95, 43.252, -126.453];
+        unsafe {
+            let polyline = polyline_encode(coordinates.as_ptr(), coordinates.len(), 5);
+            assert_eq!(CStr::from_ptr(polyline).to_str(), Ok("_p~iF~ps|U_ulLnnqC_mqNvxq`@"));
+
+            let mut length = 0;
+            let decoded = polyline_decode(polyline, 5, &mut length);
+            assert_eq!(std::slice::from_raw_parts(decoded, length), &coordinates[..]);
+
+            polyline_free_coordinates(decoded, length);
+            polyline_free(polyline);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        unsafe {
+            let polyline = polyline_encode(ptr::null(), 0, 5);
+            assert_eq!(CStr::from_ptr(polyline).to_bytes(), b"");
+
+            let mut length = 1;
+            let decoded = polyline_decode(polyline, 5, &mut length);
+            assert!(!decoded.is_null());
+            assert_eq!(length, 0);
+
+            polyline_free_coordinates(decoded, length);
+            polyline_free(polyline);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let malformed = CString::new("_p~iF~ps|U_ulL").unwrap();
+        unsafe {
+            assert!(polyline_encode([38.5, -120.2, 40.7].as_ptr(), 3, 5).is_null());
+            assert!(polyline_encode(ptr::null(), 2, 5).is_null());
+
+            let mut length = 1;
+            assert!(polyline_decode(malformed.as_ptr(), 5, &mut length).is_null());
+            assert_eq!(length, 0);
+            assert!(polyline_decode(ptr::null(), 5, &mut length).is_null());
+            assert!(polyline_decode(malformed.as_ptr(), 5, ptr::null_mut()).is_null());
+
+            polyline_free(ptr::null_mut());
+            polyline_free_coordinates(ptr::null_mut(), 0);
+        }
+    }
+}
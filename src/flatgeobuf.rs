@@ -0,0 +1,326 @@
+//! FlatGeobuf export of decoded polylines.
+//!
+//! More info: [https://flatgeobuf.org](https://flatgeobuf.org)
+//!
+//! The files contain one `LineString` feature per polyline with a string column `id`, use
+//! WGS84 (EPSG:4326) and have no spatial index. They can be opened directly in QGIS or GDAL.
+
+use std::io::{self, Write};
+
+use crate::try_decode;
+
+const MAGIC_BYTES: [u8; 8] = [0x66, 0x67, 0x62, 0x03, 0x66, 0x67, 0x62, 0x00];
+
+const GEOMETRY_TYPE_LINE_STRING: u8 = 2;
+const COLUMN_TYPE_STRING: u8 = 11;
+
+/// Writes polylines as FlatGeobuf file.
+///
+/// `features`: pairs of feature id and polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with
+///
+/// `writer`: destination of the file, e.g. a `BufWriter<File>`
+///
+/// All polylines are decoded before writing, because the header contains the number of features
+/// and their bounding box. A malformed polyline fails with [`io::ErrorKind::InvalidData`]
+/// naming the feature id, nothing is written in that case.
+pub fn write_flatgeobuf<W, I, K, S>(features: I, precision: u32, writer: W) -> io::Result<()>
+    where W: Write, I: IntoIterator<Item = (K, S)>, K: AsRef<str>, S: AsRef<str> {
+    return write_flatgeobuf_with_progress(features, precision, writer, |_, _| {});
+}
+
+/// Like [`write_flatgeobuf`], and calls `progress` after every decoded feature.
+///
+/// `progress`: called with the number of decoded features and the total number of features
+pub fn write_flatgeobuf_with_progress<W, I, K, S, F>(features: I, precision: u32, mut writer: W, mut progress: F) -> io::Result<()>
+    where W: Write, I: IntoIterator<Item = (K, S)>, K: AsRef<str>, S: AsRef<str>, F: FnMut(usize, usize) {
+    let features: Vec<(K, S)> = features.into_iter().collect();
+    let mut envelope = [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY];
+    let mut buffers: Vec<Vec<u8>> = Vec::with_capacity(features.len());
+
+    for (id, polyline) in features.iter() {
+        let points = try_decode(polyline.as_ref(), precision).map_err(|error| {
+            return io::Error::new(io::ErrorKind::InvalidData, format!("feature {:?}: {}", id.as_ref(), error));
+        })?;
+
+        let mut xy: Vec<f64> = Vec::with_capacity(points.len() * 2);
+        for point in points.iter() {
+            envelope[0] = envelope[0].min(point.longitude);
+            envelope[1] = envelope[1].min(point.latitude);
+            envelope[2] = envelope[2].max(point.longitude);
+            envelope[3] = envelope[3].max(point.latitude);
+            xy.push(point.longitude);
+            xy.push(point.latitude);
+        }
+
+        let id = id.as_ref().as_bytes();
+        let mut properties: Vec<u8> = Vec::with_capacity(6 + id.len());
+        properties.extend_from_slice(&0_u16.to_le_bytes());
+        properties.extend_from_slice(&(id.len() as u32).to_le_bytes());
+        properties.extend_from_slice(id);
+
+        let geometry = Table::new(vec![(1, Value::Doubles(xy))]);
+        buffers.push(finish(Table::new(vec![
+            (0, Value::Table(geometry)),
+            (1, Value::Bytes(properties))
+        ])));
+        progress(buffers.len(), features.len());
+    }
+
+    let mut header = vec![
+        (0, Value::String("polylines".to_string())),
+        (2, Value::UByte(GEOMETRY_TYPE_LINE_STRING)),
+        (7, Value::Tables(vec![Table::new(vec![
+            (0, Value::String("id".to_string())),
+            (1, Value::UByte(COLUMN_TYPE_STRING))
+        ])])),
+        (8, Value::ULong(buffers.len() as u64)),
+        (9, Value::UShort(0)),
+        (10, Value::Table(Table::new(vec![(1, Value::Int(4326))])))
+    ];
+    if envelope[0] <= envelope[2] {
+        header.push((1, Value::Doubles(envelope.to_vec())));
+    }
+
+    writer.write_all(&MAGIC_BYTES)?;
+    write_size_prefixed(&mut writer, &finish(Table::new(header)))?;
+    for buffer in buffers.iter() {
+        write_size_prefixed(&mut writer, buffer)?;
+    }
+
+    return writer.flush();
+}
+
+fn write_size_prefixed<W: Write>(writer: &mut W, buffer: &[u8]) -> io::Result<()> {
+    writer.write_all(&(buffer.len() as u32).to_le_bytes())?;
+    return writer.write_all(buffer);
+}
+
+/// Field value of a FlatBuffers table; just the types needed for FlatGeobuf.
+enum Value {
+    UByte(u8),
+    UShort(u16),
+    Int(i32),
+    ULong(u64),
+    String(String),
+    Bytes(Vec<u8>),
+    Doubles(Vec<f64>),
+    Table(Table),
+    Tables(Vec<Table>)
+}
+
+impl Value {
+    /// Size and alignment of the value inside of the table.
+    fn inline_size(&self) -> usize {
+        return match self {
+            Value::UByte(_) => 1,
+            Value::UShort(_) => 2,
+            Value::ULong(_) => 8,
+            // all other values are stored as offset
+            _ => 4
+        };
+    }
+}
+
+/// A FlatBuffers table as list of field index and value.
+struct Table {
+    fields: Vec<(u16, Value)>
+}
+
+impl Table {
+    fn new(fields: Vec<(u16, Value)>) -> Table {
+        return Table { fields };
+    }
+}
+
+/// Serializes a root table into a FlatBuffers buffer.
+///
+/// Unlike the official builders this writes front to back: every table is preceded by its
+/// vtable and followed by its children, so all offsets point forward as required.
+fn finish(root: Table) -> Vec<u8> {
+    let mut buffer: Vec<u8> = vec![0; 4];
+    let position = write_table(&mut buffer, &root);
+    patch_offset(&mut buffer, 0, position);
+    return buffer;
+}
+
+fn write_table(buffer: &mut Vec<u8>, table: &Table) -> usize {
+    // fields ordered by decreasing size, so every field is naturally aligned
+    let mut fields: Vec<&(u16, Value)> = table.fields.iter().collect();
+    fields.sort_by_key(|(_, value)| std::cmp::Reverse(value.inline_size()));
+
+    let alignment = fields.first().map_or(4, |(_, value)| value.inline_size().max(4));
+    let mut field_offsets: Vec<usize> = Vec::with_capacity(fields.len());
+    let mut inline_size = 4;
+    for (_, value) in fields.iter() {
+        let size = value.inline_size();
+        inline_size = align_to(inline_size, size);
+        field_offsets.push(inline_size);
+        inline_size += size;
+    }
+
+    // vtable: its size, the inline size of the table and one offset per field slot
+    let slots = table.fields.iter().map(|(index, _)| *index as usize + 1).max().unwrap_or(0);
+    let vtable_size = 4 + 2 * slots;
+    // the table has to start right after the vtable at the required alignment
+    let vtable_position = align_to(buffer.len() + vtable_size, alignment) - vtable_size;
+    buffer.resize(vtable_position, 0);
+
+    let mut vtable = vec![0_u16; 2 + slots];
+    vtable[0] = vtable_size as u16;
+    vtable[1] = inline_size as u16;
+    for ((index, _), offset) in fields.iter().zip(field_offsets.iter()) {
+        vtable[2 + *index as usize] = *offset as u16;
+    }
+    for entry in vtable.iter() {
+        buffer.extend_from_slice(&entry.to_le_bytes());
+    }
+
+    let table_position = buffer.len();
+    buffer.extend_from_slice(&((table_position - vtable_position) as i32).to_le_bytes());
+    buffer.resize(table_position + inline_size, 0);
+
+    for ((_, value), offset) in fields.iter().zip(field_offsets.iter()) {
+        let field_position = table_position + offset;
+        let bytes: Vec<u8> = match value {
+            Value::UByte(v) => v.to_le_bytes().to_vec(),
+            Value::UShort(v) => v.to_le_bytes().to_vec(),
+            Value::Int(v) => v.to_le_bytes().to_vec(),
+            Value::ULong(v) => v.to_le_bytes().to_vec(),
+            _ => continue
+        };
+        buffer[field_position..field_position + bytes.len()].copy_from_slice(&bytes);
+    }
+
+    // children of the table
+    for ((_, value), offset) in fields.iter().zip(field_offsets.iter()) {
+        let field_position = table_position + offset;
+        let child_position = match value {
+            Value::String(string) => write_vector(buffer, string.as_bytes(), 1, 1, true),
+            Value::Bytes(bytes) => write_vector(buffer, bytes, 1, 1, false),
+            Value::Doubles(doubles) => {
+                let bytes: Vec<u8> = doubles.iter().flat_map(|d| d.to_le_bytes().to_vec()).collect();
+                write_vector(buffer, &bytes, 8, 8, false)
+            },
+            Value::Table(child) => write_table(buffer, child),
+            Value::Tables(children) => {
+                let vector_position = write_vector(buffer, &vec![0; 4 * children.len()], 4, 4, false);
+                for (i, child) in children.iter().enumerate() {
+                    let child_position = write_table(buffer, child);
+                    patch_offset(buffer, vector_position + 4 + 4 * i, child_position);
+                }
+                vector_position
+            },
+            _ => continue
+        };
+        patch_offset(buffer, field_position, child_position);
+    }
+
+    return table_position;
+}
+
+/// Writes a vector (length prefix and elements) and returns the position of the length prefix.
+fn write_vector(buffer: &mut Vec<u8>, bytes: &[u8], element_size: usize, alignment: usize, zero_terminated: bool) -> usize {
+    // the elements have to be aligned, the length prefix right before them to 4 bytes
+    let position = align_to(buffer.len() + 4, alignment.max(4)) - 4;
+    buffer.resize(position, 0);
+
+    buffer.extend_from_slice(&((bytes.len() / element_size) as u32).to_le_bytes());
+    buffer.extend_from_slice(bytes);
+    if zero_terminated {
+        buffer.push(0);
+    }
+
+    return position;
+}
+
+fn patch_offset(buffer: &mut [u8], position: usize, target: usize) {
+    buffer[position..position + 4].copy_from_slice(&((target - position) as u32).to_le_bytes());
+}
+
+fn align_to(position: usize, alignment: usize) -> usize {
+    return position.div_ceil(alignment) * alignment;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::flatgeobuf::{write_flatgeobuf, write_flatgeobuf_with_progress, MAGIC_BYTES};
+
+    fn u32_at(buffer: &[u8], position: usize) -> usize {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&buffer[position..position + 4]);
+        return u32::from_le_bytes(bytes) as usize;
+    }
+
+    /// Position of a field of the table at `table`, `None` if absent.
+    fn field(buffer: &[u8], table: usize, index: usize) -> Option<usize> {
+        let vtable = table - u32_at(buffer, table);
+        let vtable_size = u16::from_le_bytes([buffer[vtable], buffer[vtable + 1]]) as usize;
+        if 4 + 2 * index >= vtable_size {
+            return None;
+        }
+        let offset = u16::from_le_bytes([buffer[vtable + 4 + 2 * index], buffer[vtable + 5 + 2 * index]]) as usize;
+        return if offset == 0 { None } else { Some(table + offset) };
+    }
+
+    fn follow(buffer: &[u8], position: usize) -> usize {
+        return position + u32_at(buffer, position);
+    }
+
+    #[test]
+    fn header_and_features() {
+        let mut file: Vec<u8> = Vec::new();
+        write_flatgeobuf(vec![("a", "_p~iF~ps|U_ulLnnqC"), ("b", "??")], 5, &mut file).unwrap();
+
+        assert_eq!(file[..8], MAGIC_BYTES);
+        let header_size = u32_at(&file, 8);
+        let header = &file[12..12 + header_size];
+        let root = follow(header, 0);
+
+        let features_count = field(header, root, 8).unwrap();
+        assert_eq!(header[features_count..features_count + 8], 2_u64.to_le_bytes());
+        assert_eq!(header[field(header, root, 2).unwrap()], 2);
+        let envelope = follow(header, field(header, root, 1).unwrap());
+        assert_eq!(u32_at(header, envelope), 4);
+        assert_eq!(envelope % 8, 4);
+
+        let feature_start = 12 + header_size;
+        let feature_size = u32_at(&file, feature_start);
+        let feature = &file[feature_start + 4..feature_start + 4 + feature_size];
+        let root = follow(feature, 0);
+        let geometry = follow(feature, field(feature, root, 0).unwrap());
+        let xy = follow(feature, field(feature, geometry, 1).unwrap());
+        assert_eq!(u32_at(feature, xy), 4);
+        let mut first_x = [0; 8];
+        first_x.copy_from_slice(&feature[xy + 4..xy + 12]);
+        assert_eq!(f64::from_le_bytes(first_x), -120.2);
+
+        let properties = follow(feature, field(feature, root, 1).unwrap());
+        assert_eq!(feature[properties + 4..properties + 11], [0, 0, 1, 0, 0, 0, b'a']);
+
+        let second_start = feature_start + 4 + feature_size;
+        assert_eq!(file.len(), second_start + 4 + u32_at(&file, second_start));
+    }
+
+    #[test]
+    fn progress() {
+        let mut with_progress: Vec<u8> = Vec::new();
+        let mut reported = vec![];
+        write_flatgeobuf_with_progress(vec![("a", "??"), ("b", "??")], 5, &mut with_progress, |done, total| reported.push((done, total))).unwrap();
+        assert_eq!(reported, vec![(1, 2), (2, 2)]);
+
+        let mut file: Vec<u8> = Vec::new();
+        write_flatgeobuf(vec![("a", "??"), ("b", "??")], 5, &mut file).unwrap();
+        assert_eq!(with_progress, file);
+    }
+
+    #[test]
+    fn malformed_polyline() {
+        let mut file: Vec<u8> = Vec::new();
+        let error = write_flatgeobuf(vec![("a", "??"), ("b", "_p~iF")], 5, &mut file).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "feature \"b\": latitude without longitude at offset 0");
+        assert!(file.is_empty());
+    }
+}
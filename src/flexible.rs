@@ -0,0 +1,370 @@
+//! HERE "Flexible Polyline" format.
+//!
+//! More info: [https://github.com/heremaps/flexible-polyline](https://github.com/heremaps/flexible-polyline)
+//!
+//! Like the "Encoded Polyline Algorithm Format" coordinates are stored as zig-zag encoded
+//! deltas in groups of 5 bit chunks, but
+//!
+//! * the string starts with a header containing the format version, the precision and an
+//!   optional third dimension (e.g. altitude) with its own precision,
+//! * chunks use the URL-safe base64 alphabet instead of the range `?` to `~`,
+//! * deltas are computed on the scaled integers, so rounding errors don't accumulate.
+//!
+//! ```
+//! use polyline_rust::Point;
+//! use polyline_rust::flexible;
+//!
+//! let points = vec![Point::new(50.10228, 8.69821), Point::new(50.10201, 8.69567)];
+//! let polyline = flexible::encode(&points, 5);
+//! assert_eq!(polyline, "BFoz5xJ67i1B1B7P");
+//! assert_eq!(flexible::decode(&polyline).unwrap(), points);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, DecodeError, MAX_GROUP_LENGTH, MAX_LAST_CHUNK, round_to_integer};
+use crate::precision::scale;
+
+/// Version of the format written into and expected in the header.
+pub const FORMAT_VERSION: u64 = 1;
+
+/// Highest precision that fits into the header, for coordinates and the third dimension.
+pub const MAX_PRECISION: u32 = 15;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Meaning of the third value of every point.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ThirdDimension {
+    /// 2D polyline
+    Absent,
+    /// floor level
+    Level,
+    /// height above the ellipsoid
+    Altitude,
+    /// height above sea level
+    Elevation,
+    /// application specific
+    Custom1,
+    /// application specific
+    Custom2
+}
+
+impl ThirdDimension {
+    fn code(self) -> u64 {
+        return match self {
+            ThirdDimension::Absent => 0,
+            ThirdDimension::Level => 1,
+            ThirdDimension::Altitude => 2,
+            ThirdDimension::Elevation => 3,
+            ThirdDimension::Custom1 => 6,
+            ThirdDimension::Custom2 => 7
+        };
+    }
+
+    /// `None` for the reserved codes 4 and 5.
+    fn from_code(code: u64) -> Option<ThirdDimension> {
+        return match code {
+            0 => Some(ThirdDimension::Absent),
+            1 => Some(ThirdDimension::Level),
+            2 => Some(ThirdDimension::Altitude),
+            3 => Some(ThirdDimension::Elevation),
+            6 => Some(ThirdDimension::Custom1),
+            7 => Some(ThirdDimension::Custom2),
+            _ => None
+        };
+    }
+}
+
+/// Content of the header of a flexible polyline.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Header {
+    /// number of decimals of latitude and longitude, at most [`MAX_PRECISION`]
+    pub precision: u32,
+    pub third_dimension: ThirdDimension,
+    /// number of decimals of the third dimension, at most [`MAX_PRECISION`]
+    pub third_dimension_precision: u32
+}
+
+impl Header {
+    /// Creates a new `Header` for 2D polylines.
+    pub fn new(precision: u32) -> Header {
+        return Header {
+            precision,
+            third_dimension: ThirdDimension::Absent,
+            third_dimension_precision: 0
+        };
+    }
+
+    /// Adds a third dimension.
+    pub fn third_dimension(mut self, third_dimension: ThirdDimension, precision: u32) -> Header {
+        self.third_dimension = third_dimension;
+        self.third_dimension_precision = precision;
+        return self;
+    }
+
+    fn dimensions(&self) -> usize {
+        return if self.third_dimension == ThirdDimension::Absent { 2 } else { 3 };
+    }
+}
+
+/// Encodes points as 2D flexible polyline.
+///
+/// `points`: points of the polyline
+///
+/// `precision`: number of decimals, at most [`MAX_PRECISION`]; HERE services usually use 5
+///
+/// # Panics
+///
+/// If `precision` is larger than [`MAX_PRECISION`].
+pub fn encode(points: &[Point], precision: u32) -> String {
+    let header = Header::new(precision);
+    return encode_values(&header, points.iter().map(|point| (point, 0.)));
+}
+
+/// Encodes points with a third dimension, e.g. altitude.
+///
+/// `points`: points of the polyline with their third value; the third values are ignored if
+/// the header has no third dimension
+///
+/// `header`: precisions and type of the third dimension
+///
+/// # Panics
+///
+/// If a precision is larger than [`MAX_PRECISION`].
+///
+/// ```
+/// use polyline_rust::Point;
+/// use polyline_rust::flexible::{self, Header, ThirdDimension};
+///
+/// let header = Header::new(5).third_dimension(ThirdDimension::Altitude, 0);
+/// let polyline = flexible::encode_3d(&[(Point::new(50.1022829, 8.6982122), 10.)], &header);
+/// assert_eq!(polyline, "BlBoz5xJ67i1BU");
+/// ```
+pub fn encode_3d(points: &[(Point, f64)], header: &Header) -> String {
+    return encode_values(header, points.iter().map(|(point, third)| (point, *third)));
+}
+
+/// Decodes a flexible polyline, the third dimension is dropped if there is one.
+///
+/// `polyline`: flexible polyline with header; the precision is taken from the header
+pub fn decode(polyline: &str) -> Result<Vec<Point>, DecodeError> {
+    let (_, points) = decode_3d(polyline)?;
+    return Ok(points.into_iter().map(|(point, _)| point).collect());
+}
+
+/// Decodes a flexible polyline together with its header and third dimension.
+///
+/// The third values are `0` if the header has no third dimension.
+///
+/// `polyline`: flexible polyline with header
+pub fn decode_3d(polyline: &str) -> Result<(Header, Vec<(Point, f64)>), DecodeError> {
+    let values = decode_values(polyline)?;
+    let header = parse_header(&values)?;
+
+    let dimensions = header.dimensions();
+    let coordinates = &values[2..];
+    if coordinates.len() % dimensions != 0 {
+        let (_, offset) = coordinates[coordinates.len() - coordinates.len() % dimensions];
+        return Err(DecodeError::DanglingCoordinate { offset });
+    }
+
+    let third_scale = scale(header.third_dimension_precision);
+    let scale = scale(header.precision);
+    let mut last: [i64; 3] = [0; 3];
+    let mut points: Vec<(Point, f64)> = Vec::with_capacity(coordinates.len() / dimensions);
+    for tuple in coordinates.chunks(dimensions) {
+        for (i, (value, _)) in tuple.iter().enumerate() {
+            // zig-zag decoding
+            let delta = (*value >> 1) as i64 ^ -((*value & 1) as i64);
+            last[i] = last[i].wrapping_add(delta);
+        }
+        let third = if dimensions == 3 { last[2] as f64 / third_scale } else { 0. };
+        points.push((Point::new(last[0] as f64 / scale, last[1] as f64 / scale), third));
+    }
+
+    return Ok((header, points));
+}
+
+/// Reads only the header of a flexible polyline.
+///
+/// `polyline`: flexible polyline with header
+pub fn header(polyline: &str) -> Result<Header, DecodeError> {
+    let mut values: Vec<(u64, usize)> = Vec::with_capacity(2);
+    let mut groups = Groups { polyline, position: 0 };
+    while values.len() < 2 {
+        match groups.next() {
+            Some(value) => values.push(value?),
+            None => break
+        }
+    }
+
+    return parse_header(&values);
+}
+
+fn encode_values<'a, I>(header: &Header, points: I) -> String
+    where I: Iterator<Item = (&'a Point, f64)> {
+    assert!(header.precision <= MAX_PRECISION && header.third_dimension_precision <= MAX_PRECISION,
+        "precision of flexible polylines is at most {}", MAX_PRECISION);
+
+    let mut encoded = String::new();
+    write_unsigned(&mut encoded, FORMAT_VERSION);
+    write_unsigned(&mut encoded, header.precision as u64
+        | header.third_dimension.code() << 4
+        | (header.third_dimension_precision as u64) << 7);
+
+    let third_scale = scale(header.third_dimension_precision);
+    let scale = scale(header.precision);
+    let dimensions = header.dimensions();
+    let mut last: [i64; 3] = [0; 3];
+    for (point, third) in points {
+        let values = [
+            round_to_integer(point.latitude * scale) as i64,
+            round_to_integer(point.longitude * scale) as i64,
+            round_to_integer(third * third_scale) as i64
+        ];
+        for i in 0..dimensions {
+            let delta = values[i].wrapping_sub(last[i]);
+            // zig-zag encoding
+            write_unsigned(&mut encoded, ((delta << 1) ^ (delta >> 63)) as u64);
+            last[i] = values[i];
+        }
+    }
+
+    return encoded;
+}
+
+fn write_unsigned(encoded: &mut String, mut value: u64) {
+    while value > 0x1f {
+        encoded.push(ALPHABET[(value & 0x1f | 0x20) as usize] as char);
+        value >>= 5;
+    }
+    encoded.push(ALPHABET[value as usize] as char);
+}
+
+fn parse_header(values: &[(u64, usize)]) -> Result<Header, DecodeError> {
+    if values.len() < 2 || values[0].0 != FORMAT_VERSION {
+        return Err(DecodeError::MissingHeader);
+    }
+
+    let content = values[1].0;
+    if content >> 11 != 0 {
+        return Err(DecodeError::MissingHeader);
+    }
+    return Ok(Header {
+        precision: (content & 0xf) as u32,
+        third_dimension: ThirdDimension::from_code(content >> 4 & 0x7).ok_or(DecodeError::MissingHeader)?,
+        third_dimension_precision: (content >> 7 & 0xf) as u32
+    });
+}
+
+/// All unsigned values of the polyline together with the byte offsets of their groups.
+fn decode_values(polyline: &str) -> Result<Vec<(u64, usize)>, DecodeError> {
+    return Groups { polyline, position: 0 }.collect();
+}
+
+/// Iterator over the unsigned values of a flexible polyline and the offsets of their groups.
+struct Groups<'a> {
+    polyline: &'a str,
+    position: usize
+}
+
+impl Iterator for Groups<'_> {
+    type Item = Result<(u64, usize), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let group_start = self.position;
+        let mut value: u64 = 0;
+
+        for (offset, letter) in self.polyline[group_start..].char_indices() {
+            let offset = group_start + offset;
+            let chunk = match ALPHABET.iter().position(|allowed| *allowed as char == letter) {
+                Some(chunk) => chunk as u64,
+                None => {
+                    self.position = self.polyline.len();
+                    return Some(Err(DecodeError::InvalidCharacter { offset, character: letter }));
+                }
+            };
+
+            let group_length = offset - group_start + 1;
+            if group_length > MAX_GROUP_LENGTH || (group_length == MAX_GROUP_LENGTH && chunk & 0x1f > MAX_LAST_CHUNK as u64) {
+                self.position = self.polyline.len();
+                return Some(Err(DecodeError::ChunkOutOfRange { offset: group_start }));
+            }
+
+            value |= (chunk & 0x1f) << ((group_length - 1) * 5);
+            if chunk & 0x20 == 0 {
+                self.position = offset + 1;
+                return Some(Ok((value, group_start)));
+            }
+        }
+
+        if group_start < self.polyline.len() {
+            self.position = self.polyline.len();
+            return Some(Err(DecodeError::DanglingGroup { offset: group_start }));
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, DecodeError};
+    use crate::flexible::{encode, encode_3d, decode, decode_3d, header, Header, ThirdDimension};
+
+    fn reference() -> Vec<Point> {
+        return vec![
+            Point::new(50.10228, 8.69821),
+            Point::new(50.10201, 8.69567),
+            Point::new(50.10063, 8.6915),
+            Point::new(50.09878, 8.68752)
+        ];
+    }
+
+    #[test]
+    fn reference_2d() {
+        assert_eq!(encode(&reference(), 5), "BFoz5xJ67i1B1B7PzIhaxL7Y");
+        assert_eq!(decode("BFoz5xJ67i1B1B7PzIhaxL7Y"), Ok(reference()));
+        assert_eq!(header("BFoz5xJ67i1B1B7PzIhaxL7Y"), Ok(Header::new(5)));
+    }
+
+    #[test]
+    fn reference_3d() {
+        let points = vec![
+            (Point::new(50.1022829, 8.6982122), 10.),
+            (Point::new(50.1020076, 8.6956695), 20.),
+            (Point::new(50.1006313, 8.691496), 30.),
+            (Point::new(50.09878, 8.6875156), 40.)
+        ];
+        let header = Header::new(5).third_dimension(ThirdDimension::Altitude, 0);
+        let polyline = encode_3d(&points, &header);
+        assert_eq!(polyline, "BlBoz5xJ67i1BU1B7PUzIhaUxL7YU");
+
+        let (decoded_header, decoded) = decode_3d(&polyline).unwrap();
+        assert_eq!(decoded_header, header);
+        assert_eq!(decoded.iter().map(|(_, altitude)| *altitude).collect::<Vec<f64>>(), vec![10., 20., 30., 40.]);
+        assert_eq!(decode(&polyline).unwrap(), reference());
+    }
+
+    #[test]
+    fn high_precision_doesnt_drift() {
+        let points: Vec<Point> = (0..1000).map(|i| Point::new(0.1 * i as f64, -0.1 * i as f64)).collect();
+        let decoded = decode(&encode(&points, 10)).unwrap();
+        assert_eq!(decoded.last(), Some(&Point::new(99.9, -99.9)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(decode(""), Err(DecodeError::MissingHeader));
+        assert_eq!(decode("CF"), Err(DecodeError::MissingHeader));
+        assert_eq!(decode("BF?"), Err(DecodeError::InvalidCharacter { offset: 2, character: '?' }));
+        assert_eq!(decode("BFoz5xJ67i1B1B7PzIhaxL"), Err(DecodeError::DanglingCoordinate { offset: 20 }));
+        assert_eq!(decode("BFoz5x"), Err(DecodeError::DanglingGroup { offset: 2 }));
+        assert_eq!(decode("BF______________"), Err(DecodeError::ChunkOutOfRange { offset: 2 }));
+        // reserved third dimension
+        assert_eq!(header("BlC"), Err(DecodeError::MissingHeader));
+        assert_eq!(decode("BF"), Ok(vec![]));
+    }
+}
@@ -0,0 +1,158 @@
+use core::fmt;
+
+use crate::{Point, round};
+
+/// Digits of an `f64` after the decimal point, all following ones are zero.
+const MAX_DECIMALS: u32 = 1074;
+
+/// Writes `value` rounded to exactly `precision` decimals, e.g. `12.34000` instead of
+/// `12.340000000000001` for a precision of 5.
+///
+/// Rounding is the same as in [`encode`](crate::encode), so the digits are the ones stored in
+/// the polyline. Values rounding to zero are written without sign. Precisions above 1074 are
+/// written with 1074 decimals, all further digits of an `f64` are zero.
+pub(crate) fn write_fixed<W: fmt::Write>(writer: &mut W, value: f64, precision: u32) -> fmt::Result {
+    let mut rounded = round(value, precision);
+    if rounded == 0. {
+        rounded = 0.;
+    }
+    return write!(writer, "{:.*}", precision.min(MAX_DECIMALS) as usize, rounded);
+}
+
+/// Writes `latitude,longitude`; a precision given in the format string, e.g. `{:.5}`, is used for
+/// both coordinates, see [`Point::display`] for a fixed number of decimals.
+///
+/// ```
+/// use polyline_rust::Point;
+///
+/// let point = Point::new(38.5, -120.2);
+/// assert_eq!(point.to_string(), "38.5,-120.2");
+/// assert_eq!(format!("{:.2}", point), "38.50,-120.20");
+/// ```
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match f.precision() {
+            Some(precision) => write!(f, "{:.*},{:.*}", precision, self.latitude, precision, self.longitude),
+            None => write!(f, "{},{}", self.latitude, self.longitude)
+        };
+    }
+}
+
+impl Point {
+    /// Displays the point as `latitude,longitude` with exactly `precision` decimals, rounded the
+    /// same way as in [`encode`](crate::encode).
+    ///
+    /// ```
+    /// use polyline_rust::Point;
+    ///
+    /// assert_eq!(Point::new(12.340000000000001, -0.1 - 0.2).display(5).to_string(), "12.34000,-0.30000");
+    /// ```
+    pub fn display(&self, precision: u32) -> DisplayPoint<'_> {
+        return DisplayPoint { point: self, precision };
+    }
+}
+
+/// Display wrapper of a [`Point`] with a fixed number of decimals, see [`Point::display`].
+#[derive(Debug)]
+pub struct DisplayPoint<'a> {
+    point: &'a Point,
+    precision: u32
+}
+
+impl fmt::Display for DisplayPoint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fixed(f, self.point.latitude, self.precision)?;
+        f.write_str(",")?;
+        return write_fixed(f, self.point.longitude, self.precision);
+    }
+}
+
+/// Displays points as `[latitude,longitude; latitude,longitude; ...]` for logs and debugging,
+/// the alternate flag (`{:#}`) puts every point on its own line.
+///
+/// Nothing is formatted until the result is displayed.
+///
+/// `points`: points of the polyline
+///
+/// `precision`: number of decimals, see [`Point::display`]
+///
+/// ```
+/// use polyline_rust::{Point, display_points};
+///
+/// let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+/// assert_eq!(display_points(&points, 2).to_string(), "[38.50,-120.20; 40.70,-120.95]");
+/// assert_eq!(format!("{:#}", display_points(&points, 2)), "[\n    38.50,-120.20\n    40.70,-120.95\n]");
+/// ```
+pub fn display_points(points: &[Point], precision: u32) -> DisplayPoints<'_> {
+    return DisplayPoints { points, precision };
+}
+
+/// Display wrapper of a list of points, see [`display_points`].
+#[derive(Debug)]
+pub struct DisplayPoints<'a> {
+    points: &'a [Point],
+    precision: u32
+}
+
+impl fmt::Display for DisplayPoints<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pretty = f.alternate();
+        f.write_str("[")?;
+        for (i, point) in self.points.iter().enumerate() {
+            if pretty {
+                f.write_str("\n    ")?;
+            } else if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", point.display(self.precision))?;
+        }
+        if pretty && !self.points.is_empty() {
+            f.write_str("\n")?;
+        }
+        return f.write_str("]");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, display_points};
+    use crate::format::write_fixed;
+
+    fn fixed(value: f64, precision: u32) -> String {
+        let mut text = String::new();
+        write_fixed(&mut text, value, precision).unwrap();
+        return text;
+    }
+
+    #[test]
+    fn exact_decimals() {
+        assert_eq!(fixed(12.340000000000001, 5), "12.34000");
+        assert_eq!(fixed(-120.2, 6), "-120.200000");
+        assert_eq!(fixed(0.1 + 0.2, 1), "0.3");
+        assert_eq!(fixed(7., 0), "7");
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(fixed(1.000005, 5), "1.00001");
+        assert_eq!(fixed(-0.000001, 5), "0.00000");
+    }
+
+    #[test]
+    fn absurd_precision() {
+        assert_eq!(fixed(0.5, 400), format!("0.5{}", "0".repeat(399)));
+        assert_eq!(fixed(-1.25, 100_000), format!("-1.25{}", "0".repeat(1072)));
+        assert_eq!(fixed(5e-324, u32::MAX).len(), 1076);
+    }
+
+    #[test]
+    fn display() {
+        let point = Point::new(-0.000001, 89.999996);
+        assert_eq!(point.to_string(), "-0.000001,89.999996");
+        assert_eq!(format!("{:.1}", point), "-0.0,90.0");
+        assert_eq!(point.display(5).to_string(), "0.00000,90.00000");
+        assert_eq!(display_points(&[], 5).to_string(), "[]");
+        assert_eq!(format!("{:#}", display_points(&[], 5)), "[]");
+    }
+}
@@ -0,0 +1,112 @@
+//! Conversions between polylines and [`geo_types`], so decoded polylines can be used with the
+//! algorithms of the georust ecosystem.
+//!
+//! geo-types uses `x` for the longitude and `y` for the latitude.
+//!
+//! ```
+//! use polyline_rust::geo::{encode_linestring, decode_to_linestring};
+//!
+//! let line = decode_to_linestring("_p~iF~ps|U_ulLnnqC", 5);
+//! assert_eq!(line.0[0].x, -120.2);
+//! assert_eq!(encode_linestring(&line, 5), "_p~iF~ps|U_ulLnnqC");
+//! ```
+
+use alloc::string::String;
+use core::convert::TryFrom;
+
+use geo_types::{Coord, LineString};
+
+use crate::{Point, EncodedPolyline, DecodeError, encode, decode, try_decode};
+
+impl From<Point> for Coord<f64> {
+    fn from(point: Point) -> Coord<f64> {
+        return Coord { x: point.longitude, y: point.latitude };
+    }
+}
+
+impl From<&Point> for Coord<f64> {
+    fn from(point: &Point) -> Coord<f64> {
+        return Coord { x: point.longitude, y: point.latitude };
+    }
+}
+
+impl From<Coord<f64>> for Point {
+    fn from(coord: Coord<f64>) -> Point {
+        return Point::new(coord.y, coord.x);
+    }
+}
+
+impl From<Point> for geo_types::Point<f64> {
+    fn from(point: Point) -> geo_types::Point<f64> {
+        return geo_types::Point::new(point.longitude, point.latitude);
+    }
+}
+
+impl From<geo_types::Point<f64>> for Point {
+    fn from(point: geo_types::Point<f64>) -> Point {
+        return Point::new(point.y(), point.x());
+    }
+}
+
+impl TryFrom<&EncodedPolyline> for LineString<f64> {
+    type Error = DecodeError;
+
+    fn try_from(polyline: &EncodedPolyline) -> Result<LineString<f64>, DecodeError> {
+        let points = try_decode(polyline.as_str(), polyline.precision())?;
+        return Ok(points.iter().map(Coord::from).collect());
+    }
+}
+
+/// Encodes a `LineString` to the "Encoded Polyline Algorithm Format".
+///
+/// `line`: coordinates with the longitude as `x` and the latitude as `y`
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+pub fn encode_linestring(line: &LineString<f64>, precision: u32) -> String {
+    return encode(line.coords().map(|coord| Point::new(coord.y, coord.x)), precision);
+}
+
+/// Decodes a polyline into a `LineString` with the longitude as `x` and the latitude as `y`.
+///
+/// Like [`decode`] this is lenient about malformed input, convert an [`EncodedPolyline`] with
+/// `LineString::try_from` to get an error instead.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+pub fn decode_to_linestring(polyline: &str, precision: u32) -> LineString<f64> {
+    return decode(polyline, precision).iter().map(Coord::from).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use geo_types::{coord, LineString};
+
+    use crate::{Point, EncodedPolyline, DecodeError};
+    use crate::geo::{encode_linestring, decode_to_linestring};
+
+    #[test]
+    fn points_and_coords() {
+        assert_eq!(geo_types::Coord::from(Point::new(38.5, -120.2)), coord! { x: -120.2, y: 38.5 });
+        assert_eq!(Point::from(coord! { x: -120.2, y: 38.5 }), Point::new(38.5, -120.2));
+        assert_eq!(Point::from(geo_types::Point::from(Point::new(1., 2.))), Point::new(1., 2.));
+    }
+
+    #[test]
+    fn line_strings() {
+        let line = LineString::from(vec![(-120.2, 38.5), (-120.95, 40.7)]);
+        assert_eq!(encode_linestring(&line, 5), "_p~iF~ps|U_ulLnnqC");
+        assert_eq!(decode_to_linestring("_p~iF~ps|U_ulLnnqC", 5), line);
+    }
+
+    #[test]
+    fn from_encoded_polyline() {
+        let valid = EncodedPolyline::new("_p~iF~ps|U".to_string(), 5);
+        assert_eq!(LineString::try_from(&valid), Ok(LineString::from(vec![(-120.2, 38.5)])));
+
+        let dangling = EncodedPolyline::new("_p~iF".to_string(), 5);
+        assert_eq!(LineString::try_from(&dangling), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+}
@@ -0,0 +1,191 @@
+//! Ellipsoidal distance, azimuth and destination on the WGS84 ellipsoid.
+//!
+//! The functions in the crate root assume a spherical earth, which is off by up to 0.5%.
+//! This module implements Vincenty's formulae, not Karney's algorithm: they are accurate to
+//! about half a millimeter, well below the resolution of about one centimeter of precision 7
+//! polylines, but the inverse problem doesn't converge for nearly antipodal points. [`inverse`],
+//! [`distance`] and [`azimuth`] return `None` for those, use Karney's algorithm (e.g. the
+//! `geographiclib-rs` crate) if such points can occur.
+//!
+//! More info: [https://en.wikipedia.org/wiki/Vincenty%27s_formulae](https://en.wikipedia.org/wiki/Vincenty%27s_formulae)
+
+use crate::Point;
+use crate::geodesy::normalize_longitude;
+
+/// Semi-major axis of the WGS84 ellipsoid in meters.
+pub const WGS84_A: f64 = 6_378_137.;
+/// Flattening of the WGS84 ellipsoid.
+pub const WGS84_F: f64 = 1. / 298.257_223_563;
+
+const WGS84_B: f64 = WGS84_A * (1. - WGS84_F);
+const MAX_ITERATIONS: usize = 200;
+const CONVERGENCE_THRESHOLD: f64 = 1e-12;
+
+/// Solution of the inverse geodesic problem, see [`inverse`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct Inverse {
+    /// length of the geodesic in meters
+    pub distance_m: f64,
+    /// azimuth at the start point in degrees (0 to 360, clockwise from north)
+    pub initial_azimuth: f64,
+    /// azimuth at the end point in degrees (0 to 360, clockwise from north)
+    pub final_azimuth: f64
+}
+
+/// Solves the inverse geodesic problem: distance and azimuths between `a` and `b`.
+///
+/// Returns `None` if the iteration doesn't converge, which happens for nearly antipodal points,
+/// i.e. when one point is close to the antipode of the other one.
+pub fn inverse(a: &Point, b: &Point) -> Option<Inverse> {
+    let l = (b.longitude - a.longitude).to_radians();
+    let u1 = ((1. - WGS84_F) * a.latitude.to_radians().tan()).atan();
+    let u2 = ((1. - WGS84_F) * b.latitude.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2)).sqrt();
+        if sin_sigma == 0. {
+            // coincident points
+            return Some(Inverse { distance_m: 0., initial_azimuth: 0., final_azimuth: 0. });
+        }
+
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1. - sin_alpha * sin_alpha;
+        let cos_2sigma_m = if cos_sq_alpha == 0. {
+            // equatorial line
+            0.
+        } else {
+            cos_sigma - 2. * sin_u1 * sin_u2 / cos_sq_alpha
+        };
+
+        let c = WGS84_F / 16. * cos_sq_alpha * (4. + WGS84_F * (4. - 3. * cos_sq_alpha));
+        let previous = lambda;
+        lambda = l + (1. - c) * WGS84_F * sin_alpha * (sigma + c * sin_sigma
+            * (cos_2sigma_m + c * cos_sigma * (-1. + 2. * cos_2sigma_m * cos_2sigma_m)));
+
+        if (lambda - previous).abs() < CONVERGENCE_THRESHOLD {
+            let u_sq = cos_sq_alpha * (WGS84_A * WGS84_A - WGS84_B * WGS84_B) / (WGS84_B * WGS84_B);
+            let delta_sigma = delta_sigma(u_sq, sin_sigma, cos_sigma, cos_2sigma_m);
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+
+            let initial = (cos_u2 * sin_lambda).atan2(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            let r#final = (cos_u1 * sin_lambda).atan2(-sin_u1 * cos_u2 + cos_u1 * sin_u2 * cos_lambda);
+
+            return Some(Inverse {
+                distance_m: WGS84_B * big_a(u_sq) * (sigma - delta_sigma),
+                initial_azimuth: initial.to_degrees().rem_euclid(360.),
+                final_azimuth: r#final.to_degrees().rem_euclid(360.)
+            });
+        }
+    }
+
+    return None;
+}
+
+/// Ellipsoidal distance between `a` and `b` in meters, `None` for nearly antipodal points,
+/// see [`inverse`].
+pub fn distance(a: &Point, b: &Point) -> Option<f64> {
+    return inverse(a, b).map(|solution| solution.distance_m);
+}
+
+/// Initial azimuth from `a` to `b` in degrees (0 to 360, clockwise from north), `None` for
+/// nearly antipodal points, see [`inverse`].
+pub fn azimuth(a: &Point, b: &Point) -> Option<f64> {
+    return inverse(a, b).map(|solution| solution.initial_azimuth);
+}
+
+/// Solves the direct geodesic problem: the point reached when travelling `distance_m` meters
+/// from `start` with the initial azimuth `azimuth_deg` (degrees clockwise from north).
+pub fn destination(start: &Point, azimuth_deg: f64, distance_m: f64) -> Point {
+    let (sin_alpha1, cos_alpha1) = azimuth_deg.to_radians().sin_cos();
+    let tan_u1 = (1. - WGS84_F) * start.latitude.to_radians().tan();
+    let cos_u1 = 1. / (1. + tan_u1 * tan_u1).sqrt();
+    let sin_u1 = tan_u1 * cos_u1;
+
+    let sigma1 = tan_u1.atan2(cos_alpha1);
+    let sin_alpha = cos_u1 * sin_alpha1;
+    let cos_sq_alpha = 1. - sin_alpha * sin_alpha;
+    let u_sq = cos_sq_alpha * (WGS84_A * WGS84_A - WGS84_B * WGS84_B) / (WGS84_B * WGS84_B);
+    let big_a = big_a(u_sq);
+
+    let mut sigma = distance_m / (WGS84_B * big_a);
+    let mut cos_2sigma_m;
+    let mut sin_sigma;
+    let mut cos_sigma;
+    let mut iterations = 0;
+    loop {
+        cos_2sigma_m = (2. * sigma1 + sigma).cos();
+        sin_sigma = sigma.sin();
+        cos_sigma = sigma.cos();
+
+        let previous = sigma;
+        sigma = distance_m / (WGS84_B * big_a)
+            + delta_sigma(u_sq, sin_sigma, cos_sigma, cos_2sigma_m);
+
+        iterations += 1;
+        if (sigma - previous).abs() < CONVERGENCE_THRESHOLD || iterations >= MAX_ITERATIONS {
+            break;
+        }
+    }
+
+    let tmp = sin_u1 * sin_sigma - cos_u1 * cos_sigma * cos_alpha1;
+    let latitude = (sin_u1 * cos_sigma + cos_u1 * sin_sigma * cos_alpha1)
+        .atan2((1. - WGS84_F) * (sin_alpha * sin_alpha + tmp * tmp).sqrt());
+    let lambda = (sin_sigma * sin_alpha1).atan2(cos_u1 * cos_sigma - sin_u1 * sin_sigma * cos_alpha1);
+    let c = WGS84_F / 16. * cos_sq_alpha * (4. + WGS84_F * (4. - 3. * cos_sq_alpha));
+    let l = lambda - (1. - c) * WGS84_F * sin_alpha * (sigma + c * sin_sigma
+        * (cos_2sigma_m + c * cos_sigma * (-1. + 2. * cos_2sigma_m * cos_2sigma_m)));
+
+    return Point::new(
+        latitude.to_degrees(),
+        normalize_longitude(start.longitude + l.to_degrees())
+    );
+}
+
+fn big_a(u_sq: f64) -> f64 {
+    return 1. + u_sq / 16384. * (4096. + u_sq * (-768. + u_sq * (320. - 175. * u_sq)));
+}
+
+fn delta_sigma(u_sq: f64, sin_sigma: f64, cos_sigma: f64, cos_2sigma_m: f64) -> f64 {
+    let big_b = u_sq / 1024. * (256. + u_sq * (-128. + u_sq * (74. - 47. * u_sq)));
+
+    return big_b * sin_sigma * (cos_2sigma_m + big_b / 4. * (cos_sigma * (-1. + 2. * cos_2sigma_m * cos_2sigma_m)
+        - big_b / 6. * cos_2sigma_m * (-3. + 4. * sin_sigma * sin_sigma) * (-3. + 4. * cos_2sigma_m * cos_2sigma_m)));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+    use crate::geodesic::{inverse, distance, destination};
+
+    #[test]
+    fn flinders_peak_to_buninyong() {
+        // reference values from Vincenty's original paper
+        let flinders_peak = Point::new(-(37. + 57. / 60. + 3.72030 / 3600.), 144. + 25. / 60. + 29.52440 / 3600.);
+        let buninyong = Point::new(-(37. + 39. / 60. + 10.15610 / 3600.), 143. + 55. / 60. + 35.38390 / 3600.);
+
+        let solution = inverse(&flinders_peak, &buninyong).unwrap();
+        assert!((solution.distance_m - 54_972.271).abs() < 0.001);
+        assert!((solution.initial_azimuth - (306. + 52. / 60. + 5.37 / 3600.)).abs() < 1e-5);
+
+        let end = destination(&flinders_peak, solution.initial_azimuth, solution.distance_m);
+        assert!((end.latitude - buninyong.latitude).abs() < 1e-9);
+        assert!((end.longitude - buninyong.longitude).abs() < 1e-9);
+    }
+
+    #[test]
+    fn coincident_points() {
+        assert_eq!(distance(&Point::new(10., 10.), &Point::new(10., 10.)), Some(0.));
+    }
+
+    #[test]
+    fn antipodal_points() {
+        assert_eq!(distance(&Point::new(0., 0.), &Point::new(0.5, 179.7)), None);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::Point;
+
+/// Mean earth radius in meters, used by all spherical distance calculations.
+pub const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Great-circle distance between two points in meters (haversine formula).
+pub fn distance(a: &Point, b: &Point) -> f64 {
+    let lat1 = a.latitude.to_radians();
+    let lat2 = b.latitude.to_radians();
+    let d_lat = lat2 - lat1;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+
+    let h = (d_lat / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.).sin().powi(2);
+
+    return 2. * EARTH_RADIUS_M * h.sqrt().min(1.).asin();
+}
+
+/// Initial bearing in degrees (0 to 360, clockwise from north) of the great circle from `a`
+/// to `b`.
+pub fn bearing(a: &Point, b: &Point) -> f64 {
+    let lat1 = a.latitude.to_radians();
+    let lat2 = b.latitude.to_radians();
+    let d_lon = (b.longitude - a.longitude).to_radians();
+
+    let y = d_lon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+
+    return y.atan2(x).to_degrees().rem_euclid(360.);
+}
+
+/// Point halfway between `a` and `b` on the great circle through both points.
+pub fn midpoint(a: &Point, b: &Point) -> Point {
+    let lat1 = a.latitude.to_radians();
+    let lat2 = b.latitude.to_radians();
+    let lon1 = a.longitude.to_radians();
+    let d_lon = (b.longitude - a.longitude).to_radians();
+
+    let bx = lat2.cos() * d_lon.cos();
+    let by = lat2.cos() * d_lon.sin();
+
+    let latitude = (lat1.sin() + lat2.sin()).atan2(((lat1.cos() + bx).powi(2) + by * by).sqrt());
+    let longitude = lon1 + by.atan2(lat1.cos() + bx);
+
+    return Point::new(latitude.to_degrees(), normalize_longitude(longitude.to_degrees()));
+}
+
+/// Point reached when travelling `distance_m` meters from `start` along the great circle with
+/// the initial bearing `bearing_deg` (degrees clockwise from north).
+pub fn destination(start: &Point, bearing_deg: f64, distance_m: f64) -> Point {
+    let lat1 = start.latitude.to_radians();
+    let lon1 = start.longitude.to_radians();
+    let bearing = bearing_deg.to_radians();
+    let angular_distance = distance_m / EARTH_RADIUS_M;
+
+    let latitude = (lat1.sin() * angular_distance.cos()
+        + lat1.cos() * angular_distance.sin() * bearing.cos()).asin();
+    let longitude = lon1 + (bearing.sin() * angular_distance.sin() * lat1.cos())
+        .atan2(angular_distance.cos() - lat1.sin() * latitude.sin());
+
+    return Point::new(latitude.to_degrees(), normalize_longitude(longitude.to_degrees()));
+}
+
+/// Wraps a longitude into [-180, 180).
+pub(crate) fn normalize_longitude(longitude: f64) -> f64 {
+    return (longitude + 180.).rem_euclid(360.) - 180.;
+}
+
+/// Distance in meters from `p` to the segment `a`-`b`.
+pub(crate) fn distance_to_segment(p: &Point, a: &Point, b: &Point) -> f64 {
+    let (closest, _) = closest_on_segment(p, a, b);
+    return distance(p, &closest);
+}
+
+/// Distance in meters from `p` to the closest point of the polyline `line`.
+///
+/// Returns `f64::INFINITY` for an empty line.
+pub(crate) fn distance_to_polyline(p: &Point, line: &[Point]) -> f64 {
+    if line.len() == 1 {
+        return distance(p, &line[0]);
+    }
+
+    let mut min = f64::INFINITY;
+    for segment in line.windows(2) {
+        min = min.min(distance_to_segment(p, &segment[0], &segment[1]));
+    }
+
+    return min;
+}
+
+/// Finds the point on the segment `a`-`b` closest to `p`.
+///
+/// The projection happens on a local equirectangular plane centered on `p`, which is accurate
+/// for segments up to a few hundred kilometers. Returns the closest point and its segment
+/// parameter `t` (0 at `a`, 1 at `b`).
+pub(crate) fn closest_on_segment(p: &Point, a: &Point, b: &Point) -> (Point, f64) {
+    let (ax, ay) = local_xy(p, a);
+    let (bx, by) = local_xy(p, b);
+    let (dx, dy) = (bx - ax, by - ay);
+
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0. {
+        0.
+    } else {
+        (-(ax * dx + ay * dy) / length_squared).clamp(0., 1.)
+    };
+
+    return (interpolate(a, b, t), t);
+}
+
+/// Linear interpolation between `a` and `b`, taking the short way across the antimeridian.
+pub(crate) fn interpolate(a: &Point, b: &Point, t: f64) -> Point {
+    let longitude = a.longitude + wrap_longitude_delta(b.longitude - a.longitude) * t;
+
+    return Point::new(
+        a.latitude + (b.latitude - a.latitude) * t,
+        wrap_longitude_delta(longitude)
+    );
+}
+
+/// Wraps a longitude (difference) into [-180, 180].
+fn wrap_longitude_delta(mut d_lon: f64) -> f64 {
+    if d_lon > 180. {
+        d_lon -= 360.;
+    } else if d_lon < -180. {
+        d_lon += 360.;
+    }
+    return d_lon;
+}
+
+/// Position of `q` in meters relative to `origin` on a local equirectangular plane.
+///
+/// Longitude differences are wrapped into [-180, 180] so segments crossing the antimeridian
+/// stay short.
+fn local_xy(origin: &Point, q: &Point) -> (f64, f64) {
+    let d_lon = wrap_longitude_delta(q.longitude - origin.longitude);
+
+    let x = d_lon.to_radians() * origin.latitude.to_radians().cos() * EARTH_RADIUS_M;
+    let y = (q.latitude - origin.latitude).to_radians() * EARTH_RADIUS_M;
+    return (x, y);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+    use crate::geodesy::{distance, distance_to_segment, bearing, midpoint, destination, EARTH_RADIUS_M};
+
+    fn assert_close(actual: Point, expected: Point) {
+        assert!((actual.latitude - expected.latitude).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        assert!((actual.longitude - expected.longitude).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn distance_zero() {
+        assert_eq!(distance(&Point::new(48.2, 16.37), &Point::new(48.2, 16.37)), 0.);
+    }
+
+    #[test]
+    fn distance_one_degree_latitude() {
+        let d = distance(&Point::new(0., 0.), &Point::new(1., 0.));
+        assert!((d - 111_195.08).abs() < 0.1);
+    }
+
+    #[test]
+    fn distance_across_antimeridian() {
+        let d = distance(&Point::new(0., 179.5), &Point::new(0., -179.5));
+        assert!((d - 111_195.08).abs() < 0.1);
+    }
+
+    #[test]
+    fn segment_distance_perpendicular() {
+        let d = distance_to_segment(&Point::new(0.001, 0.5), &Point::new(0., 0.), &Point::new(0., 1.));
+        assert!((d - 111.195).abs() < 0.01);
+    }
+
+    #[test]
+    fn segment_distance_beyond_end() {
+        let d = distance_to_segment(&Point::new(0., 2.), &Point::new(0., 0.), &Point::new(0., 1.));
+        assert!((d - 111_195.08).abs() < 1.);
+    }
+
+    #[test]
+    fn bearings() {
+        assert_eq!(bearing(&Point::new(0., 0.), &Point::new(1., 0.)), 0.);
+        assert!((bearing(&Point::new(0., 0.), &Point::new(0., 1.)) - 90.).abs() < 1e-9);
+        assert!((bearing(&Point::new(0., 0.), &Point::new(0., -1.)) - 270.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn midpoint_on_equator_and_antimeridian() {
+        assert_close(midpoint(&Point::new(0., 10.), &Point::new(0., 20.)), Point::new(0., 15.));
+        assert_close(midpoint(&Point::new(0., 179.), &Point::new(0., -179.)), Point::new(0., -180.));
+    }
+
+    #[test]
+    fn destination_roundtrip() {
+        let start = Point::new(48.208771, 16.372572);
+        let end = destination(&start, 45., 10_000.);
+        assert!((distance(&start, &end) - 10_000.).abs() < 1e-6);
+        assert!((bearing(&start, &end) - 45.).abs() < 1e-6);
+        let one_degree = EARTH_RADIUS_M * std::f64::consts::PI / 180.;
+        assert_close(destination(&Point::new(0., 179.5), 90., one_degree), Point::new(0., -179.5));
+    }
+}
@@ -0,0 +1,106 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, FormatError};
+use crate::format::write_fixed;
+use crate::json::{parse, Value};
+
+/// Converts points into a GeoJSON `LineString` geometry.
+///
+/// More info: [https://datatracker.ietf.org/doc/html/rfc7946](https://datatracker.ietf.org/doc/html/rfc7946)
+///
+/// GeoJSON positions are `[longitude, latitude]`, the order is swapped accordingly.
+///
+/// `points`: points of the polyline
+///
+/// `precision`: number of decimals of the coordinates, see [`to_csv`](crate::to_csv)
+///
+/// ```
+/// use polyline_rust::{to_geojson, decode};
+///
+/// let geojson = to_geojson(&decode("_p~iF~ps|U_ulLnnqC", 5), 5);
+/// assert_eq!(geojson, r#"{"type":"LineString","coordinates":[[-120.20000,38.50000],[-120.95000,40.70000]]}"#);
+/// ```
+pub fn to_geojson(points: &[Point], precision: u32) -> String {
+    let mut geojson = String::from(r#"{"type":"LineString","coordinates":["#);
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            geojson.push(',');
+        }
+        // writing into a `String` never fails
+        geojson.push('[');
+        let _ = write_fixed(&mut geojson, point.longitude, precision);
+        geojson.push(',');
+        let _ = write_fixed(&mut geojson, point.latitude, precision);
+        geojson.push(']');
+    }
+    geojson.push_str("]}");
+
+    return geojson;
+}
+
+/// Reads the points of a GeoJSON `LineString`.
+///
+/// `geojson`: a `LineString` geometry or a `Feature` with a `LineString` geometry; altitudes
+/// and other members are ignored
+pub fn from_geojson(geojson: &str) -> Result<Vec<Point>, FormatError> {
+    let document = parse(geojson).map_err(|offset| FormatError::Syntax { offset })?;
+
+    let geometry = match document.get("type").and_then(Value::as_str) {
+        Some("Feature") => document.get("geometry").ok_or(FormatError::NotALineString)?,
+        _ => &document
+    };
+    if geometry.get("type").and_then(Value::as_str) != Some("LineString") {
+        return Err(FormatError::NotALineString);
+    }
+
+    let positions = geometry.get("coordinates").and_then(Value::as_array).ok_or(FormatError::NotALineString)?;
+    let mut points: Vec<Point> = Vec::with_capacity(positions.len());
+    for (index, position) in positions.iter().enumerate() {
+        let invalid = FormatError::InvalidCoordinate { index };
+        let position = position.as_array().ok_or(invalid.clone())?;
+        if position.len() < 2 {
+            return Err(invalid);
+        }
+
+        let longitude = position[0].as_f64().ok_or(invalid.clone())?;
+        let latitude = position[1].as_f64().ok_or(invalid.clone())?;
+        if !(-90. ..=90.).contains(&latitude) || !(-180. ..=180.).contains(&longitude) {
+            return Err(invalid);
+        }
+
+        points.push(Point::new(latitude, longitude));
+    }
+
+    return Ok(points);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, FormatError, to_geojson, from_geojson};
+
+    #[test]
+    fn roundtrip() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+        assert_eq!(from_geojson(&to_geojson(&points, 5)), Ok(points));
+        assert_eq!(to_geojson(&[], 5), r#"{"type":"LineString","coordinates":[]}"#);
+    }
+
+    #[test]
+    fn feature_with_altitude() {
+        let feature = r#"{
+            "type": "Feature",
+            "properties": {"name": "x"},
+            "geometry": {"type": "LineString", "coordinates": [[16.37, 48.2, 171.5], [16.38, 48.21, 180]]}
+        }"#;
+        assert_eq!(from_geojson(feature), Ok(vec![Point::new(48.2, 16.37), Point::new(48.21, 16.38)]));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(from_geojson("{\"type\":"), Err(FormatError::Syntax { offset: 8 }));
+        assert_eq!(from_geojson(r#"{"type":"Point","coordinates":[1,2]}"#), Err(FormatError::NotALineString));
+        assert_eq!(from_geojson(r#"{"type":"LineString","coordinates":[[1,2],[3]]}"#), Err(FormatError::InvalidCoordinate { index: 1 }));
+        assert_eq!(from_geojson(r#"{"type":"LineString","coordinates":[[1,91]]}"#), Err(FormatError::InvalidCoordinate { index: 0 }));
+    }
+}
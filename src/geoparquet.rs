@@ -0,0 +1,144 @@
+//! Geometry columns for GeoParquet files.
+//!
+//! More info: [https://geoparquet.org](https://geoparquet.org)
+//!
+//! This module doesn't write Parquet files itself. [`wkb_column`] prepares the values of a
+//! binary geometry column and the `geo` file metadata, which can be handed to any Parquet
+//! writer (e.g. `arrow`'s `BinaryArray` and the file's key-value metadata).
+//!
+//! ```
+//! use polyline_rust::geoparquet::wkb_column;
+//!
+//! let column = wkb_column(vec!["_p~iF~ps|U_ulLnnqC", "_mqNvxq`@"], 5).unwrap();
+//! assert_eq!(column.values.len(), 2);
+//! assert!(column.metadata("geometry").contains("\"encoding\":\"WKB\""));
+//! ```
+
+use crate::{Bounds, DecodeError, try_decode};
+use crate::bounds::bounds;
+use crate::wkb::write_line_string;
+
+/// GeoParquet specification version of the metadata.
+pub const GEOPARQUET_VERSION: &str = "1.1.0";
+
+/// WKB values of a geometry column with their bounding boxes.
+#[derive(PartialEq, Debug, Clone)]
+pub struct WkbColumn {
+    /// one WKB `LineString` per polyline, empty polylines become empty `LineString`s
+    pub values: Vec<Vec<u8>>,
+    /// bounding box of every row, `None` for empty polylines; can be written as bbox covering
+    /// column
+    pub row_bounds: Vec<Option<Bounds>>,
+    /// bounding box of the whole column, `None` if all polylines are empty
+    pub bounds: Option<Bounds>
+}
+
+impl WkbColumn {
+    /// JSON for the `geo` key of the Parquet file metadata.
+    ///
+    /// `column`: name of the geometry column in the file
+    ///
+    /// The coordinate reference system is omitted, which means OGC:CRS84 (WGS84 longitude/latitude).
+    pub fn metadata(&self, column: &str) -> String {
+        let mut json = format!(
+            "{{\"version\":\"{}\",\"primary_column\":{},\"columns\":{{{}:{{\"encoding\":\"WKB\",\"geometry_types\":[\"LineString\"]",
+            GEOPARQUET_VERSION, json_string(column), json_string(column)
+        );
+        if let Some(bounds) = self.bounds {
+            json += &format!(",\"bbox\":[{},{},{},{}]", bounds.min_lon, bounds.min_lat, bounds.max_lon, bounds.max_lat);
+        }
+        json += "}}}";
+
+        return json;
+    }
+}
+
+/// Converts polylines into a WKB geometry column.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with
+///
+/// Fails on the first malformed polyline with its index and the error, so corrupt rows don't
+/// end up as shortened `LineString`s.
+pub fn wkb_column<I, S>(polylines: I, precision: u32) -> Result<WkbColumn, (usize, DecodeError)>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    let mut column = WkbColumn {
+        values: Vec::new(),
+        row_bounds: Vec::new(),
+        bounds: None
+    };
+
+    for (index, polyline) in polylines.into_iter().enumerate() {
+        let points = try_decode(polyline.as_ref(), precision).map_err(|error| (index, error))?;
+
+        let mut value: Vec<u8> = Vec::new();
+        write_line_string(&mut value, &points);
+        column.values.push(value);
+
+        let row_bounds = bounds(&points);
+        column.bounds = match (column.bounds, row_bounds) {
+            (Some(a), Some(b)) => Some(Bounds::new(
+                a.min_lat.min(b.min_lat),
+                a.min_lon.min(b.min_lon),
+                a.max_lat.max(b.max_lat),
+                a.max_lon.max(b.max_lon)
+            )),
+            (a, b) => a.or(b)
+        };
+        column.row_bounds.push(row_bounds);
+    }
+
+    return Ok(column);
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for letter in value.chars() {
+        match letter {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            letter if (letter as u32) < 0x20 => json += &format!("\\u{:04x}", letter as u32),
+            letter => json.push(letter)
+        }
+    }
+    json.push('"');
+
+    return json;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bounds, DecodeError};
+    use crate::geoparquet::wkb_column;
+
+    #[test]
+    fn values_and_bounds() {
+        let column = wkb_column(vec!["_p~iF~ps|U_ulLnnqC", "", "_mqNvxq`@"], 5).unwrap();
+
+        assert_eq!(column.values.len(), 3);
+        assert_eq!(column.values[0].len(), 9 + 2 * 16);
+        assert_eq!(column.values[1], vec![1, 2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(column.row_bounds[0], Some(Bounds::new(38.5, -120.95, 40.7, -120.2)));
+        assert_eq!(column.row_bounds[1], None);
+        assert_eq!(column.bounds, Some(Bounds::new(2.552, -120.95, 40.7, -5.503)));
+    }
+
+    #[test]
+    fn metadata() {
+        let column = wkb_column(vec!["_p~iF~ps|U"], 5).unwrap();
+        assert_eq!(column.metadata("geo\"m"), concat!(
+            "{\"version\":\"1.1.0\",\"primary_column\":\"geo\\\"m\",\"columns\":{\"geo\\\"m\":",
+            "{\"encoding\":\"WKB\",\"geometry_types\":[\"LineString\"],\"bbox\":[-120.2,38.5,-120.2,38.5]}}}"
+        ));
+
+        let empty = wkb_column(Vec::<String>::new(), 5).unwrap();
+        assert!(!empty.metadata("geometry").contains("bbox"));
+    }
+
+    #[test]
+    fn malformed_row() {
+        assert_eq!(wkb_column(vec!["_p~iF~ps|U", "_p~iF~ps|U_ulL"], 5), Err((1, DecodeError::DanglingCoordinate { offset: 10 })));
+    }
+}
@@ -0,0 +1,190 @@
+//! GPX track import and export.
+//!
+//! More info: [GPX 1.1 Schema Documentation](https://www.topografix.com/GPX/1/1/)
+//!
+//! Only the track points (`<trkpt>`) of track segments (`<trkseg>`) are read; waypoints,
+//! routes, elevations, timestamps and extensions are ignored. The reader is deliberately
+//! lenient XML-wise and doesn't validate against the schema, so files of fitness apps with
+//! vendor extensions can be read as they are.
+//!
+//! ```
+//! use polyline_rust::gpx::{read_track, to_gpx};
+//! use polyline_rust::encode;
+//!
+//! let gpx = r#"<?xml version="1.0"?>
+//! <gpx version="1.1" creator="watch">
+//!   <trk><name>Morning run</name><trkseg>
+//!     <trkpt lat="38.5" lon="-120.2"><ele>21.3</ele></trkpt>
+//!     <trkpt lon="-120.95" lat="40.7"/>
+//!   </trkseg></trk>
+//! </gpx>"#;
+//!
+//! let points = read_track(gpx).unwrap();
+//! assert_eq!(encode(&points, 5), "_p~iF~ps|U_ulLnnqC");
+//! assert_eq!(read_track(&to_gpx(&points, 5)).unwrap(), points);
+//! ```
+
+use crate::{Point, DecodeError, FormatError, try_decode};
+use crate::format::write_fixed;
+use crate::xml::next_tag;
+
+/// Reads every track segment of a GPX document, in document order over all tracks.
+///
+/// `gpx`: the GPX document
+pub fn read_track_segments(gpx: &str) -> Result<Vec<Vec<Point>>, FormatError> {
+    let mut segments: Vec<Vec<Point>> = Vec::new();
+    let mut segment: Option<Vec<Point>> = None;
+    let mut is_gpx = false;
+    let mut index = 0;
+
+    let mut position = 0;
+    while let Some(tag) = next_tag(gpx, position)? {
+        position = tag.end;
+
+        match (tag.name, tag.closing) {
+            ("gpx", false) => is_gpx = true,
+            ("trkseg", false) => {
+                if let Some(points) = segment.take() {
+                    segments.push(points);
+                }
+                if !tag.self_closing {
+                    segment = Some(Vec::new());
+                } else {
+                    segments.push(Vec::new());
+                }
+            },
+            ("trkseg", true) => {
+                if let Some(points) = segment.take() {
+                    segments.push(points);
+                }
+            },
+            ("trkpt", false) => {
+                if let Some(points) = segment.as_mut() {
+                    let invalid = FormatError::InvalidCoordinate { index };
+                    let latitude = tag.number("lat").ok_or(invalid.clone())?;
+                    let longitude = tag.number("lon").ok_or(invalid.clone())?;
+                    if !(-90. ..=90.).contains(&latitude) || !(-180. ..=180.).contains(&longitude) {
+                        return Err(invalid);
+                    }
+                    points.push(Point::new(latitude, longitude));
+                    index += 1;
+                }
+            },
+            _ => {}
+        }
+    }
+
+    if !is_gpx {
+        return Err(FormatError::NotALineString);
+    }
+    if segment.is_some() {
+        // the document ends inside a segment
+        return Err(FormatError::Syntax { offset: gpx.len() });
+    }
+
+    return Ok(segments);
+}
+
+/// Reads the track points of all track segments of a GPX document into a single polyline.
+///
+/// `gpx`: the GPX document
+pub fn read_track(gpx: &str) -> Result<Vec<Point>, FormatError> {
+    return Ok(read_track_segments(gpx)?.into_iter().flatten().collect());
+}
+
+/// Writes points as GPX document with one track of one segment.
+///
+/// `points`: points of the track
+///
+/// `precision`: number of decimals of the coordinates, see [`to_csv`](crate::to_csv)
+pub fn to_gpx(points: &[Point], precision: u32) -> String {
+    return segments_to_gpx(&[points], precision);
+}
+
+/// Writes polylines as GPX document with one track and a segment per polyline.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format", malformed strings are
+/// rejected
+///
+/// `precision`: precision the polylines were encoded with; also the number of decimals in
+/// the output
+pub fn decode_to_gpx<I, S>(polylines: I, precision: u32) -> Result<String, DecodeError>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    let segments = polylines.into_iter()
+        .map(|polyline| try_decode(polyline.as_ref(), precision))
+        .collect::<Result<Vec<Vec<Point>>, DecodeError>>()?;
+    let segments: Vec<&[Point]> = segments.iter().map(|points| points.as_slice()).collect();
+
+    return Ok(segments_to_gpx(&segments, precision));
+}
+
+fn segments_to_gpx(segments: &[&[Point]], precision: u32) -> String {
+    let mut gpx = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<gpx version=\"1.1\" creator=\"polyline_rust\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+        "  <trk>\n"
+    ));
+    for points in segments.iter() {
+        gpx.push_str("    <trkseg>\n");
+        for point in points.iter() {
+            // writing into a `String` never fails
+            gpx.push_str("      <trkpt lat=\"");
+            let _ = write_fixed(&mut gpx, point.latitude, precision);
+            gpx.push_str("\" lon=\"");
+            let _ = write_fixed(&mut gpx, point.longitude, precision);
+            gpx.push_str("\"/>\n");
+        }
+        gpx.push_str("    </trkseg>\n");
+    }
+    gpx.push_str("  </trk>\n</gpx>\n");
+
+    return gpx;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, FormatError, DecodeError};
+    use crate::gpx::{read_track_segments, read_track, to_gpx, decode_to_gpx};
+
+    #[test]
+    fn segments() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- <trkpt lat="1" lon="1"/> -->
+            <gpx:gpx xmlns:gpx="http://www.topografix.com/GPX/1/1">
+              <gpx:wpt lat="5" lon="5"/>
+              <gpx:trk>
+                <gpx:trkseg><gpx:trkpt lat='1.5' lon='2'><gpx:time>2024-05-01T07:00:00Z</gpx:time></gpx:trkpt></gpx:trkseg>
+                <gpx:trkseg/>
+                <gpx:trkseg>
+                  <gpx:trkpt lat="3" lon="4"/>
+                  <gpx:trkpt lat="5" lon="6"/>
+                </gpx:trkseg>
+              </gpx:trk>
+            </gpx:gpx>"#;
+        assert_eq!(read_track_segments(gpx), Ok(vec![
+            vec![Point::new(1.5, 2.)],
+            vec![],
+            vec![Point::new(3., 4.), Point::new(5., 6.)]
+        ]));
+        assert_eq!(read_track(gpx).map(|points| points.len()), Ok(3));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+        assert_eq!(read_track_segments(&to_gpx(&points, 5)), Ok(vec![points]));
+
+        let gpx = decode_to_gpx(vec!["_p~iF~ps|U", "_ulLnnqC"], 5).unwrap();
+        assert_eq!(read_track_segments(&gpx), Ok(vec![vec![Point::new(38.5, -120.2)], vec![Point::new(2.2, -0.75)]]));
+        assert_eq!(decode_to_gpx(vec!["_p~iF"], 5), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(read_track("<kml></kml>"), Err(FormatError::NotALineString));
+        assert_eq!(read_track("<gpx><trk><trkseg><trkpt lat=\"1\" lon=\"2\""), Err(FormatError::Syntax { offset: 18 }));
+        assert_eq!(read_track("<gpx><trkseg><trkpt lat=\"1\"/></trkseg></gpx>"), Err(FormatError::InvalidCoordinate { index: 0 }));
+        assert_eq!(read_track("<gpx><trkseg><trkpt lat=\"91\" lon=\"0\"/></trkseg></gpx>"), Err(FormatError::InvalidCoordinate { index: 0 }));
+        assert_eq!(read_track("<gpx><!-- </gpx>"), Err(FormatError::Syntax { offset: 5 }));
+    }
+}
@@ -0,0 +1,95 @@
+//! H3 cells along polylines, e.g. for coverage or exposure analytics of routes.
+//!
+//! More info: [https://h3geo.org](https://h3geo.org)
+//!
+//! ```
+//! use h3o::Resolution;
+//! use polyline_rust::decode;
+//! use polyline_rust::h3::h3_cells_along;
+//!
+//! let route = decode("_p~iF~ps|U_ulLnnqC", 5);
+//! let cells = h3_cells_along(&route, Resolution::Five);
+//! assert!(cells.len() > 10);
+//! ```
+
+use std::collections::HashSet;
+
+use h3o::{CellIndex, LatLng, Resolution};
+
+use crate::Point;
+use crate::geodesy::{distance, interpolate};
+
+/// Cells traversed by the polyline in the order they are entered.
+///
+/// Every cell is only listed once, at its first visit, also if the route returns to it later.
+/// The segments are sampled (in plain latitude/longitude space) at a quarter of the edge length
+/// of the cells, so a cell only clipped at one of its corners may be missed. Points with an
+/// invalid latitude or longitude (e.g. NaN) are skipped.
+///
+/// `points`: points of the polyline
+///
+/// `resolution`: resolution of the cells
+pub fn h3_cells_along(points: &[Point], resolution: Resolution) -> Vec<CellIndex> {
+    let step = resolution.edge_length_m() / 4.;
+
+    let mut cells: Vec<CellIndex> = Vec::new();
+    let mut seen: HashSet<CellIndex> = HashSet::new();
+    let mut visit = |point: &Point| {
+        if let Ok(coordinate) = LatLng::new(point.latitude, point.longitude) {
+            let cell = coordinate.to_cell(resolution);
+            if seen.insert(cell) {
+                cells.push(cell);
+            }
+        }
+    };
+
+    if let Some(first) = points.first() {
+        visit(first);
+    }
+    for segment in points.windows(2) {
+        let samples = (distance(&segment[0], &segment[1]) / step).ceil() as usize;
+        for k in 1..samples {
+            visit(&interpolate(&segment[0], &segment[1], k as f64 / samples as f64));
+        }
+        visit(&segment[1]);
+    }
+
+    return cells;
+}
+
+#[cfg(test)]
+mod tests {
+    use h3o::{LatLng, Resolution};
+
+    use crate::Point;
+    use crate::h3::h3_cells_along;
+
+    #[test]
+    fn ordered_and_connected() {
+        let route = vec![Point::new(48.2, 16.3), Point::new(48.2, 16.4), Point::new(48.3, 16.4)];
+        let cells = h3_cells_along(&route, Resolution::Eight);
+
+        let first = LatLng::new(48.2, 16.3).unwrap().to_cell(Resolution::Eight);
+        let last = LatLng::new(48.3, 16.4).unwrap().to_cell(Resolution::Eight);
+        assert_eq!(cells.first(), Some(&first));
+        assert_eq!(cells.last(), Some(&last));
+        for pair in cells.windows(2) {
+            assert_eq!(pair[0].grid_distance(pair[1]), Ok(1));
+        }
+    }
+
+    #[test]
+    fn deduplicated() {
+        let there_and_back = vec![Point::new(48.2, 16.3), Point::new(48.2, 16.4), Point::new(48.2, 16.3)];
+        assert_eq!(
+            h3_cells_along(&there_and_back, Resolution::Seven),
+            h3_cells_along(&there_and_back[..2], Resolution::Seven)
+        );
+    }
+
+    #[test]
+    fn invalid_and_empty() {
+        assert_eq!(h3_cells_along(&[], Resolution::Five), vec![]);
+        assert_eq!(h3_cells_along(&[Point::new(f64::NAN, 0.)], Resolution::Five), vec![]);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{DecodeError, ValidationError, scan_groups, last_group_offset};
+
+/// Metadata of a well-formed polyline string, see [`validate`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct PolylineInfo {
+    /// number of points
+    pub points: usize,
+    /// length of the string in bytes
+    pub bytes: usize
+}
+
+/// Checks that a string is a well-formed polyline without decoding the points.
+///
+/// Only the syntax is checked: the characters are in the range `?` to `~`, every group is
+/// terminated and fits into 64 bits, and there's a longitude for every latitude. That's
+/// enough to reject garbage at an API boundary before decoding; the coordinates themselves
+/// aren't checked, see [`validate_points`](crate::validate_points) for that.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// Returns [`ValidationError::Malformed`] with the [`DecodeError`] that [`try_decode`](crate::try_decode)
+/// would give.
+///
+/// ```
+/// use polyline_rust::{validate, PolylineInfo, ValidationError, DecodeError};
+///
+/// assert_eq!(validate("_p~iF~ps|U_ulLnnqC"), Ok(PolylineInfo { points: 2, bytes: 18 }));
+/// assert_eq!(validate("_p~iF~ps|U_ulL"), Err(ValidationError::Malformed(DecodeError::DanglingCoordinate { offset: 10 })));
+/// ```
+pub fn validate(polyline: &str) -> Result<PolylineInfo, ValidationError> {
+    let groups = scan_groups(polyline, |_| {})
+        .map_err(ValidationError::Malformed)?;
+    if groups % 2 == 1 {
+        return Err(ValidationError::Malformed(DecodeError::DanglingCoordinate { offset: last_group_offset(polyline) }));
+    }
+
+    return Ok(PolylineInfo {
+        points: groups / 2,
+        bytes: polyline.len()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PolylineInfo, ValidationError, validate, try_decode};
+
+    #[test]
+    fn like_try_decode() {
+        for polyline in ["", "_p~iF~ps|U_ulLnnqC_mqNvxq`@", "_p~iF~ps|U_ulLnnqC_mqN", "_p~iF~ps|U_", "_p~iF~ps!U", "__________________?"] {
+            match try_decode(polyline, 5) {
+                Ok(points) => assert_eq!(validate(polyline), Ok(PolylineInfo { points: points.len(), bytes: polyline.len() })),
+                Err(error) => assert_eq!(validate(polyline), Err(ValidationError::Malformed(error)))
+            }
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::{Point, length};
+use crate::geodesy::{distance, interpolate};
+
+/// Point `meters` along the polyline from its first point, e.g. to place a marker on a route.
+///
+/// Segments are interpolated linearly in latitude/longitude, distances are great-circle
+/// distances like [`length`](crate::length).
+///
+/// `points`: points of the polyline
+///
+/// `meters`: distance from the first point
+///
+/// Returns `None` for an empty polyline and for distances outside of 0 to the length of
+/// the polyline.
+///
+/// ```
+/// use polyline_rust::{Point, length, point_at};
+///
+/// let route = vec![Point::new(0., 0.), Point::new(0., 1.)];
+/// let marker = point_at(&route, length(&route) / 2.).unwrap();
+/// assert!((marker.longitude - 0.5).abs() < 1e-9);
+/// assert_eq!(point_at(&route, 1_000_000.), None);
+/// ```
+pub fn point_at(points: &[Point], meters: f64) -> Option<Point> {
+    let (segment, t) = locate(points, meters)?;
+    if segment + 1 == points.len() {
+        return Some(points[segment]);
+    }
+
+    return Some(interpolate(&points[segment], &points[segment + 1], t));
+}
+
+/// Point at a fraction of the length of the polyline, see [`point_at`].
+///
+/// `points`: points of the polyline
+///
+/// `fraction`: 0 for the first point, 1 for the last point
+///
+/// Returns `None` for an empty polyline and for fractions outside of 0 to 1.
+pub fn point_at_fraction(points: &[Point], fraction: f64) -> Option<Point> {
+    if !(0. ..=1.).contains(&fraction) {
+        return None;
+    }
+    if fraction == 1. {
+        // exactly the last point, independent of rounding in the length
+        return points.last().cloned();
+    }
+
+    return point_at(points, fraction * length(points));
+}
+
+/// Cuts the polyline `meters` from its first point, e.g. into the driven and the remaining
+/// part of a route during navigation.
+///
+/// Both parts contain the cut point, interpolated like in [`point_at`], so they render without
+/// a gap. Distances before the first point cut at the first point, distances after the end at
+/// the last point.
+///
+/// `points`: points of the polyline
+///
+/// `meters`: distance of the cut from the first point
+///
+/// Returns the part before and the part after the cut; two empty parts for an empty polyline.
+///
+/// ```
+/// use polyline_rust::{Point, length, split_at};
+///
+/// let route = vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.)];
+/// let (driven, remaining) = split_at(&route, length(&route[..2]));
+/// assert_eq!(driven, vec![Point::new(0., 0.), Point::new(0., 1.)]);
+/// assert_eq!(remaining, vec![Point::new(0., 1.), Point::new(1., 1.)]);
+/// ```
+pub fn split_at(points: &[Point], meters: f64) -> (Vec<Point>, Vec<Point>) {
+    if points.is_empty() {
+        return (vec![], vec![]);
+    }
+
+    // `max` turns NaN into 0 as well
+    let (segment, t) = match locate(points, meters.max(0.)) {
+        Some(location) => location,
+        None => return (points.to_vec(), vec![points[points.len() - 1]])
+    };
+    if segment + 1 == points.len() {
+        return (points.to_vec(), points.to_vec());
+    }
+
+    let cut = interpolate(&points[segment], &points[segment + 1], t);
+    let mut before: Vec<Point> = points[..=segment].to_vec();
+    if before[segment] != cut {
+        before.push(cut);
+    }
+    let mut after: Vec<Point> = Vec::with_capacity(points.len() - segment);
+    if points[segment + 1] != cut {
+        after.push(cut);
+    }
+    after.extend_from_slice(&points[segment + 1..]);
+
+    return (before, after);
+}
+
+/// Part of the polyline between two distances from its first point (linear referencing), e.g.
+/// the geometry of a single step of a route.
+///
+/// Starts and ends with points interpolated like in [`point_at`] and contains all points in
+/// between. Distances outside of the polyline are clamped to its first and last point.
+///
+/// `points`: points of the polyline
+///
+/// `from_m`: distance of the start of the part from the first point
+///
+/// `to_m`: distance of the end of the part from the first point
+///
+/// Returns no points if `from_m` is after `to_m`, either of them is NaN or the polyline is empty.
+///
+/// ```
+/// use polyline_rust::{Point, length, slice_between};
+///
+/// let route = vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.)];
+/// let first_leg = length(&route[..2]);
+/// let part = slice_between(&route, first_leg / 2., first_leg + 1.);
+/// assert_eq!(part.len(), 3);
+/// assert_eq!(part[1], Point::new(0., 1.));
+/// ```
+pub fn slice_between(points: &[Point], from_m: f64, to_m: f64) -> Vec<Point> {
+    if points.is_empty() || from_m.is_nan() || to_m.is_nan() || from_m > to_m {
+        return vec![];
+    }
+    if points.len() == 1 {
+        return points.to_vec();
+    }
+
+    let last_segment = (points.len() - 2, 1.);
+    let (from_segment, from_t) = locate(points, from_m.max(0.)).unwrap_or(last_segment);
+    let (to_segment, to_t) = locate(points, to_m.max(0.)).unwrap_or(last_segment);
+
+    let start = interpolate(&points[from_segment], &points[from_segment + 1], from_t);
+    let end = interpolate(&points[to_segment], &points[to_segment + 1], to_t);
+    let mut part: Vec<Point> = points[from_segment + 1..=to_segment].to_vec();
+    // the interpolated points may be points of the polyline already
+    if part.first() != Some(&start) {
+        part.insert(0, start);
+    }
+    if part.last() != Some(&end) {
+        part.push(end);
+    }
+
+    return part;
+}
+
+/// Points every `spacing_m` meters along the polyline, e.g. to sample elevations or as
+/// keyframes of an animation.
+///
+/// The result starts at the first point and ends at the last point; the last step is
+/// shorter than `spacing_m` unless the length is a multiple of it. Like [`point_at`] it
+/// interpolates linearly within the segments, so corners between two samples are cut.
+///
+/// `points`: points of the polyline
+///
+/// `spacing_m`: distance in meters between consecutive points of the result, a copy of
+/// `points` is returned if it isn't positive
+///
+/// ```
+/// use polyline_rust::{Point, length, resample};
+///
+/// let route = vec![Point::new(0., 0.), Point::new(0., 0.01)];
+/// let samples = resample(&route, length(&route) / 4.);
+/// assert_eq!(samples.len(), 5);
+/// assert_eq!(samples[4], Point::new(0., 0.01));
+/// ```
+pub fn resample(points: &[Point], spacing_m: f64) -> Vec<Point> {
+    if points.len() < 2 || spacing_m.is_nan() || spacing_m <= 0. {
+        return points.to_vec();
+    }
+
+    let mut resampled: Vec<Point> = vec![points[0]];
+    let mut start = 0.;
+    let mut target = spacing_m;
+    for segment in points.windows(2) {
+        let segment_length = distance(&segment[0], &segment[1]);
+        let end = start + segment_length;
+        while target < end {
+            resampled.push(interpolate(&segment[0], &segment[1], (target - start) / segment_length));
+            // multiples instead of a sum, so errors don't accumulate over long lines
+            target = spacing_m * (resampled.len() as f64);
+        }
+        start = end;
+    }
+
+    let last = &points[points.len() - 1];
+    // the last sample may be the last point already, up to rounding
+    if resampled.len() > 1 && distance(&resampled[resampled.len() - 1], last) < spacing_m * 1e-9 {
+        resampled.pop();
+    }
+    resampled.push(*last);
+
+    return resampled;
+}
+
+/// Segment containing the position `meters` along the polyline and the parameter `t` of the
+/// position on it (0 at the start, 1 at the end of the segment).
+///
+/// A polyline of a single point only has the position 0, returned as segment 0.
+pub(crate) fn locate(points: &[Point], meters: f64) -> Option<(usize, f64)> {
+    if points.is_empty() || meters.is_nan() || meters < 0. {
+        return None;
+    }
+
+    let mut start = 0.;
+    for (i, segment) in points.windows(2).enumerate() {
+        let segment_length = distance(&segment[0], &segment[1]);
+        // the same sum as `length`, so the last point is found for `meters == length(points)`
+        let end = start + segment_length;
+        if meters <= end {
+            let t = if segment_length == 0. { 0. } else { ((meters - start) / segment_length).clamp(0., 1.) };
+            return Some((i, t));
+        }
+        start = end;
+    }
+
+    if meters == 0. {
+        return Some((0, 0.));
+    }
+    return None;
+}
+
+/// Resamples the polyline to `count` points evenly spaced along its length.
+///
+/// The first and last point are kept. Returns an empty `Vec` for an empty polyline or a
+/// `count` of zero.
+pub(crate) fn resample_count(points: &[Point], count: usize) -> Vec<Point> {
+    if points.is_empty() || count == 0 {
+        return vec![];
+    }
+    if count == 1 || points.len() == 1 {
+        let first = &points[0];
+        return (0..count).map(|_| Point::new(first.latitude, first.longitude)).collect();
+    }
+
+    let segment_lengths: Vec<f64> = points.windows(2)
+        .map(|segment| distance(&segment[0], &segment[1]))
+        .collect();
+    let total: f64 = segment_lengths.iter().sum();
+
+    let mut resampled: Vec<Point> = Vec::with_capacity(count);
+    let mut segment = 0;
+    let mut segment_start = 0.;
+    for k in 0..count {
+        let target = total * k as f64 / (count - 1) as f64;
+        while segment < segment_lengths.len() - 1 && segment_start + segment_lengths[segment] < target {
+            segment_start += segment_lengths[segment];
+            segment += 1;
+        }
+
+        let t = if segment_lengths[segment] == 0. {
+            0.
+        } else {
+            ((target - segment_start) / segment_lengths[segment]).clamp(0., 1.)
+        };
+        resampled.push(interpolate(&points[segment], &points[segment + 1], t));
+    }
+
+    return resampled;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, distance, length, point_at, point_at_fraction, resample, slice_between, split_at};
+
+    fn route() -> Vec<Point> {
+        return vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(0., 1.), Point::new(1., 1.)];
+    }
+
+    #[test]
+    fn at_distance() {
+        let route = route();
+        let first_segment = length(&route[..2]);
+        assert_eq!(point_at(&route, 0.), Some(Point::new(0., 0.)));
+        assert_eq!(point_at(&route, first_segment), Some(Point::new(0., 1.)));
+        assert_eq!(point_at(&route, length(&route)), Some(Point::new(1., 1.)));
+
+        let between = point_at(&route, first_segment + length(&route[2..]) / 4.).unwrap();
+        assert!((between.latitude - 0.25).abs() < 1e-9 && between.longitude == 1.);
+
+        assert_eq!(point_at(&route, -1.), None);
+        assert_eq!(point_at(&route, length(&route) + 1.), None);
+        assert_eq!(point_at(&route, f64::NAN), None);
+        assert_eq!(point_at(&[], 0.), None);
+        assert_eq!(point_at(&[Point::new(1., 2.)], 0.), Some(Point::new(1., 2.)));
+    }
+
+    #[test]
+    fn at_fraction() {
+        let route = route();
+        assert_eq!(point_at_fraction(&route, 0.), Some(Point::new(0., 0.)));
+        assert_eq!(point_at_fraction(&route, 1.), Some(Point::new(1., 1.)));
+        let middle = point_at_fraction(&route, 0.5).unwrap();
+        assert!(middle.latitude.abs() < 1e-9 && (middle.longitude - 1.).abs() < 1e-3);
+        assert_eq!(point_at_fraction(&route, 1.5), None);
+        assert_eq!(point_at_fraction(&[], 0.5), None);
+    }
+
+    #[test]
+    fn evenly_spaced() {
+        let route = vec![Point::new(0., 0.), Point::new(0., 0.01), Point::new(0.01, 0.01), Point::new(0.01, 0.01)];
+        let samples = resample(&route, 100.);
+        assert_eq!(samples.first(), route.first());
+        assert_eq!(samples.last(), route.last());
+        assert_eq!(samples.len(), (length(&route) / 100.).ceil() as usize + 1);
+        // one sample is the corner, the distances around it are shorter than straight ones
+        for pair in samples[..samples.len() - 1].windows(2) {
+            let step = distance(&pair[0], &pair[1]);
+            assert!(step > 70. && step < 100.0001, "{}", step);
+        }
+
+        let exact = resample(&route[..2], length(&route[..2]) / 2.);
+        assert_eq!(exact.len(), 3);
+        assert_eq!(exact[2], Point::new(0., 0.01));
+
+        assert_eq!(resample(&route, 0.), route);
+        assert_eq!(resample(&route, f64::NAN), route);
+        assert_eq!(resample(&route, 1e9), vec![route[0], route[3]]);
+        assert_eq!(resample(&route[..1], 10.), route[..1].to_vec());
+        assert_eq!(resample(&[], 10.), vec![]);
+    }
+
+    #[test]
+    fn split() {
+        let route = route();
+        let (before, after) = split_at(&route, length(&route[..2]) / 2.);
+        assert_eq!(before.len(), 2);
+        assert!(before[1].latitude == 0. && (before[1].longitude - 0.5).abs() < 1e-9);
+        assert_eq!(after[0], before[1]);
+        assert_eq!(after[1..], route[1..]);
+
+        assert_eq!(split_at(&route, 0.), (vec![route[0]], route.clone()));
+        assert_eq!(split_at(&route, -5.), (vec![route[0]], route.clone()));
+        assert_eq!(split_at(&route, f64::NAN), (vec![route[0]], route.clone()));
+        assert_eq!(split_at(&route, length(&route)), (route.clone(), vec![route[3]]));
+        assert_eq!(split_at(&route, 1e9), (route.clone(), vec![route[3]]));
+        assert_eq!(split_at(&route[..1], 10.), (route[..1].to_vec(), route[..1].to_vec()));
+        assert_eq!(split_at(&[], 10.), (vec![], vec![]));
+    }
+
+    #[test]
+    fn slice() {
+        let route = route();
+        let first_leg = length(&route[..2]);
+        let total = length(&route);
+
+        let part = slice_between(&route, first_leg / 4., first_leg / 2.);
+        assert_eq!(part.len(), 2);
+        assert!((part[0].longitude - 0.25).abs() < 1e-9 && (part[1].longitude - 0.5).abs() < 1e-9);
+
+        // the duplicate point stays, it's part of the polyline
+        let part = slice_between(&route, first_leg / 2., total);
+        assert_eq!(part[1..], route[1..]);
+
+        assert_eq!(slice_between(&route, -10., total + 10.), route);
+        assert_eq!(slice_between(&route, first_leg, first_leg), vec![route[1]]);
+        assert_eq!(slice_between(&route, total + 1., total + 2.), vec![route[3]]);
+        assert_eq!(slice_between(&route, 2., 1.), vec![]);
+        assert_eq!(slice_between(&route, 150_000., f64::NAN), vec![]);
+        assert_eq!(slice_between(&route, f64::NAN, 1.), vec![]);
+        assert_eq!(slice_between(&route[..1], 0., 1.), route[..1].to_vec());
+        assert_eq!(slice_between(&[], 0., 1.), vec![]);
+    }
+}
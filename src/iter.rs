@@ -0,0 +1,110 @@
+use core::iter::FusedIterator;
+
+use crate::{Point, decode_element, round, MAX_GROUP_LENGTH, MAX_LAST_CHUNK};
+
+/// Lazy iterator over the points of an encoded polyline, see [`decode_iter`].
+#[derive(Debug, Clone)]
+pub struct DecodeIter<'a> {
+    polyline: &'a str,
+    position: usize,
+    precision: u32,
+    latitude: f64,
+    longitude: f64
+}
+
+/// Decodes coordinates from the "Encoded Polyline Algorithm Format" one point at a time.
+///
+/// Yields the same points as [`decode`](crate::decode) without collecting them into a `Vec`,
+/// so long polylines can be scanned with constant memory. Like `decode` the iterator ends
+/// at the first malformed group.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`](crate::decode)
+///
+/// ```
+/// use polyline_rust::decode_iter;
+///
+/// let northernmost = decode_iter("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5)
+///     .map(|point| point.latitude)
+///     .fold(f64::MIN, f64::max);
+/// assert_eq!(northernmost, 43.252);
+/// ```
+pub fn decode_iter(polyline: &str, precision: u32) -> DecodeIter<'_> {
+    return DecodeIter {
+        polyline,
+        position: 0,
+        precision,
+        latitude: 0.,
+        longitude: 0.
+    };
+}
+
+impl<'a> DecodeIter<'a> {
+    /// Returns the next group, `None` at the end of the polyline or if the group is malformed.
+    fn next_group(&mut self) -> Option<&'a str> {
+        let bytes = self.polyline.as_bytes();
+        let start = self.position;
+        // a malformed group ends the iteration for good
+        self.position = bytes.len();
+
+        for (i, letter) in bytes[start..].iter().enumerate() {
+            if !(63..=126).contains(letter) {
+                return None;
+            }
+
+            let chunk = letter - 63;
+            if i + 1 > MAX_GROUP_LENGTH || (i + 1 == MAX_GROUP_LENGTH && (chunk & 0x1f) as u32 > MAX_LAST_CHUNK) {
+                return None;
+            }
+
+            if chunk & 0x20 == 0 {
+                self.position = start + i + 1;
+                return Some(&self.polyline[start..self.position]);
+            }
+        }
+
+        return None;
+    }
+}
+
+impl<'a> Iterator for DecodeIter<'a> {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let latitude = self.next_group()?;
+        let longitude = self.next_group()?;
+
+        let d_latitude = round(decode_element(latitude, self.precision), self.precision);
+        let d_longitude = round(decode_element(longitude, self.precision), self.precision);
+        self.latitude = round(self.latitude + d_latitude, self.precision);
+        self.longitude = round(self.longitude + d_longitude, self.precision);
+
+        return Some(Point::new(self.latitude, self.longitude));
+    }
+}
+
+impl<'a> FusedIterator for DecodeIter<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, decode, decode_iter};
+
+    #[test]
+    fn same_as_decode() {
+        for polyline in ["", "a", "_p~iF~ps|U_ulLnnqC_mqNvxq`@", "_p~iF~ps|U_ulL", "_p~iF~ps|U _ulLnnqC", "ewl}zAwthf^ctAobBsUnl@"].iter() {
+            assert_eq!(decode_iter(polyline, 5).collect::<Vec<Point>>(), decode(polyline, 5));
+            assert_eq!(decode_iter(polyline, 6).collect::<Vec<Point>>(), decode(polyline, 6));
+        }
+    }
+
+    #[test]
+    fn lazy() {
+        let mut points = decode_iter("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5);
+        assert_eq!(points.next(), Some(Point::new(38.5, -120.2)));
+        assert_eq!(points.next(), Some(Point::new(40.7, -120.95)));
+        assert_eq!(points.next(), Some(Point::new(43.252, -126.453)));
+        assert_eq!(points.next(), None);
+    }
+}
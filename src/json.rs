@@ -0,0 +1,315 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A parsed JSON value of the minimal JSON reader used for GeoJSON.
+///
+/// Object members keep their order, duplicate keys are kept.
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>)
+}
+
+impl Value {
+    /// Value of the first member named `key`, `None` if this isn't an object or has no such member.
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        return match self {
+            Value::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None
+        };
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        return match self {
+            Value::String(string) => Some(string),
+            _ => None
+        };
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        return match self {
+            Value::Number(number) => Some(*number),
+            _ => None
+        };
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Value]> {
+        return match self {
+            Value::Array(values) => Some(values),
+            _ => None
+        };
+    }
+}
+
+/// Parses a JSON document, returns the byte offset of the first problem on error.
+pub(crate) fn parse(json: &str) -> Result<Value, usize> {
+    let mut parser = Parser { bytes: json.as_bytes(), position: 0 };
+    let value = parser.value(0)?;
+    parser.whitespace();
+    if parser.position < parser.bytes.len() {
+        return Err(parser.position);
+    }
+
+    return Ok(value);
+}
+
+/// Nesting depth after which parsing fails instead of risking a stack overflow.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+
+impl<'a> Parser<'a> {
+    fn value(&mut self, depth: usize) -> Result<Value, usize> {
+        self.whitespace();
+        if depth > MAX_DEPTH {
+            return Err(self.position);
+        }
+
+        return match self.bytes.get(self.position) {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ => Err(self.position)
+        };
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Value, usize> {
+        self.position += 1;
+        let mut members: Vec<(String, Value)> = Vec::new();
+
+        self.whitespace();
+        if self.bytes.get(self.position) == Some(&b'}') {
+            self.position += 1;
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.whitespace();
+            if self.bytes.get(self.position) != Some(&b'"') {
+                return Err(self.position);
+            }
+            let key = self.string()?;
+
+            self.whitespace();
+            self.expect(b':')?;
+            let value = self.value(depth + 1)?;
+            members.push((key, value));
+
+            self.whitespace();
+            match self.bytes.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(members));
+                },
+                _ => return Err(self.position)
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value, usize> {
+        self.position += 1;
+        let mut values: Vec<Value> = Vec::new();
+
+        self.whitespace();
+        if self.bytes.get(self.position) == Some(&b']') {
+            self.position += 1;
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value(depth + 1)?);
+
+            self.whitespace();
+            match self.bytes.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Value::Array(values));
+                },
+                _ => return Err(self.position)
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, usize> {
+        let start = self.position;
+        self.position += 1;
+        let mut string = String::new();
+
+        loop {
+            let run_start = self.position;
+            while let Some(byte) = self.bytes.get(self.position) {
+                if *byte == b'"' || *byte == b'\\' || *byte < 0x20 {
+                    break;
+                }
+                self.position += 1;
+            }
+            // the input is a `str` and runs end at ASCII characters, so they are valid UTF-8
+            string.push_str(core::str::from_utf8(&self.bytes[run_start..self.position]).map_err(|_| run_start)?);
+
+            match self.bytes.get(self.position) {
+                Some(b'"') => {
+                    self.position += 1;
+                    return Ok(string);
+                },
+                Some(b'\\') => {
+                    let escape = self.position;
+                    self.position += 2;
+                    let letter = match self.bytes.get(escape + 1) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape(escape)?,
+                        _ => return Err(escape)
+                    };
+                    string.push(letter);
+                },
+                _ => return Err(if self.position >= self.bytes.len() { start } else { self.position })
+            }
+        }
+    }
+
+    /// Reads the 4 hex digits after `\u` and a second escape for surrogate pairs.
+    fn unicode_escape(&mut self, escape: usize) -> Result<char, usize> {
+        let high = self.hex4().ok_or(escape)?;
+        if !(0xd800..0xdc00).contains(&high) {
+            return char::from_u32(high).ok_or(escape);
+        }
+
+        if self.bytes.get(self.position) != Some(&b'\\') || self.bytes.get(self.position + 1) != Some(&b'u') {
+            return Err(escape);
+        }
+        self.position += 2;
+        let low = self.hex4().ok_or(escape)?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return Err(escape);
+        }
+
+        return char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)).ok_or(escape);
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.bytes.get(self.position..self.position + 4)?;
+        let mut value = 0;
+        for digit in digits.iter() {
+            value = value * 16 + (*digit as char).to_digit(16)?;
+        }
+        self.position += 4;
+
+        return Some(value);
+    }
+
+    fn number(&mut self) -> Result<Value, usize> {
+        let start = self.position;
+        if self.bytes.get(self.position) == Some(&b'-') {
+            self.position += 1;
+        }
+
+        match self.bytes.get(self.position) {
+            Some(b'0') => self.position += 1,
+            Some(b'1'..=b'9') => {
+                self.digits();
+            },
+            _ => return Err(self.position)
+        }
+        if self.bytes.get(self.position) == Some(&b'.') {
+            self.position += 1;
+            if !self.digits() {
+                return Err(self.position);
+            }
+        }
+        if let Some(b'e') | Some(b'E') = self.bytes.get(self.position) {
+            self.position += 1;
+            if let Some(b'+') | Some(b'-') = self.bytes.get(self.position) {
+                self.position += 1;
+            }
+            if !self.digits() {
+                return Err(self.position);
+            }
+        }
+
+        // only ASCII digits, signs, dots and exponents were consumed
+        let text = core::str::from_utf8(&self.bytes[start..self.position]).map_err(|_| start)?;
+        return text.parse::<f64>().map(Value::Number).map_err(|_| start);
+    }
+
+    /// Skips ASCII digits, returns `false` if there were none.
+    fn digits(&mut self) -> bool {
+        let start = self.position;
+        while let Some(b'0'..=b'9') = self.bytes.get(self.position) {
+            self.position += 1;
+        }
+        return self.position > start;
+    }
+
+    fn literal(&mut self, literal: &str, value: Value) -> Result<Value, usize> {
+        if !self.bytes[self.position..].starts_with(literal.as_bytes()) {
+            return Err(self.position);
+        }
+        self.position += literal.len();
+
+        return Ok(value);
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), usize> {
+        if self.bytes.get(self.position) != Some(&byte) {
+            return Err(self.position);
+        }
+        self.position += 1;
+
+        return Ok(());
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.bytes.get(self.position) {
+            self.position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::{parse, Value};
+
+    #[test]
+    fn values() {
+        let value = parse(r#" {"a": [1, -2.5e1, true, null], "b": "x\"ä😀", "c": {}} "#).unwrap();
+        assert_eq!(value.get("a"), Some(&Value::Array(vec![
+            Value::Number(1.),
+            Value::Number(-25.),
+            Value::Bool(true),
+            Value::Null
+        ])));
+        assert_eq!(value.get("b").and_then(Value::as_str), Some("x\"ä😀"));
+        assert_eq!(value.get("c"), Some(&Value::Object(vec![])));
+        assert_eq!(value.get("d"), None);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse(""), Err(0));
+        assert_eq!(parse("[1,]"), Err(3));
+        assert_eq!(parse("{\"a\" 1}"), Err(5));
+        assert_eq!(parse("\"abc"), Err(0));
+        assert_eq!(parse("01"), Err(1));
+        assert_eq!(parse("[1] x"), Err(4));
+        assert_eq!(parse(&"[".repeat(1000)), Err(129));
+    }
+}
@@ -0,0 +1,202 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, Point3, DecodeError, FormatError, encode, try_decode};
+use crate::format::write_fixed;
+use crate::xml::next_tag;
+
+/// Converts points into a KML `<LineString>` element.
+///
+/// More info: [https://developers.google.com/kml/documentation/kmlreference#linestring](https://developers.google.com/kml/documentation/kmlreference#linestring)
+///
+/// KML tuples are `longitude,latitude`, the order is swapped accordingly.
+///
+/// `points`: points of the polyline
+///
+/// `precision`: number of decimals of the coordinates, see [`to_csv`](crate::to_csv)
+///
+/// ```
+/// use polyline_rust::{Point, to_kml};
+///
+/// let kml = to_kml(&[Point::new(38.5, -120.2), Point::new(40.7, -120.95)], 5);
+/// assert_eq!(kml, "<LineString><coordinates>-120.20000,38.50000 -120.95000,40.70000</coordinates></LineString>");
+/// ```
+pub fn to_kml(points: &[Point], precision: u32) -> String {
+    let mut kml = String::from("<LineString><coordinates>");
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            kml.push(' ');
+        }
+        // writing into a `String` never fails
+        let _ = write_fixed(&mut kml, point.longitude, precision);
+        kml.push(',');
+        let _ = write_fixed(&mut kml, point.latitude, precision);
+    }
+    kml.push_str("</coordinates></LineString>");
+
+    return kml;
+}
+
+/// Converts points with elevation into a KML `<LineString>` element with altitudes, see [`to_kml`].
+///
+/// `points`: points of the polyline; the elevation is written as altitude
+///
+/// `precision`: number of decimals of the coordinates and altitudes
+pub fn to_kml3(points: &[Point3], precision: u32) -> String {
+    let mut kml = String::from("<LineString><coordinates>");
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            kml.push(' ');
+        }
+        // writing into a `String` never fails
+        let _ = write_fixed(&mut kml, point.longitude, precision);
+        kml.push(',');
+        let _ = write_fixed(&mut kml, point.latitude, precision);
+        kml.push(',');
+        let _ = write_fixed(&mut kml, point.elevation, precision);
+    }
+    kml.push_str("</coordinates></LineString>");
+
+    return kml;
+}
+
+/// Reads the points of the first KML `<LineString>`.
+///
+/// The `<LineString>` can be anywhere in the document, e.g. in a `<Placemark>` of a Google
+/// Earth export. Altitudes are ignored, use [`from_kml3`] to keep them.
+///
+/// `kml`: a KML document or fragment
+pub fn from_kml(kml: &str) -> Result<Vec<Point>, FormatError> {
+    let points = from_kml3(kml)?;
+    return Ok(points.into_iter().map(|point| Point::new(point.latitude, point.longitude)).collect());
+}
+
+/// Reads the points of the first KML `<LineString>` together with their altitudes, see [`from_kml`].
+///
+/// Missing altitudes are `0`.
+///
+/// `kml`: a KML document or fragment
+pub fn from_kml3(kml: &str) -> Result<Vec<Point3>, FormatError> {
+    let mut in_line_string = false;
+    let mut coordinates_start: Option<usize> = None;
+
+    let mut position = 0;
+    while let Some(tag) = next_tag(kml, position)? {
+        position = tag.end;
+
+        match (tag.name, tag.closing) {
+            ("LineString", false) => in_line_string = !tag.self_closing,
+            ("LineString", true) => in_line_string = false,
+            ("coordinates", false) if in_line_string => {
+                if tag.self_closing {
+                    return Ok(Vec::new());
+                }
+                coordinates_start = Some(tag.end);
+            },
+            ("coordinates", true) if in_line_string => {
+                if let Some(start) = coordinates_start {
+                    return parse_coordinates(&kml[start..tag.start]);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    if let Some(start) = coordinates_start {
+        // the document ends inside `<coordinates>`
+        return Err(FormatError::Syntax { offset: start });
+    }
+    return Err(FormatError::NotALineString);
+}
+
+/// Encodes the first KML `<LineString>` to the "Encoded Polyline Algorithm Format".
+///
+/// `kml`: a KML document or fragment, see [`from_kml`]
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+pub fn encode_kml(kml: &str, precision: u32) -> Result<String, FormatError> {
+    return Ok(encode(from_kml(kml)?, precision));
+}
+
+/// Decodes a polyline into a KML `<LineString>` element, see [`to_kml`].
+///
+/// `polyline`: encoded polyline string, malformed strings are rejected
+///
+/// `precision`: usually 5 or 6, see [`encode`]; also the number of decimals in the output
+pub fn decode_to_kml(polyline: &str, precision: u32) -> Result<String, DecodeError> {
+    return Ok(to_kml(&try_decode(polyline, precision)?, precision));
+}
+
+/// Parses whitespace separated `longitude,latitude[,altitude]` tuples.
+fn parse_coordinates(coordinates: &str) -> Result<Vec<Point3>, FormatError> {
+    let mut points: Vec<Point3> = Vec::new();
+    for (index, tuple) in coordinates.split_ascii_whitespace().enumerate() {
+        let invalid = FormatError::InvalidCoordinate { index };
+        let values = tuple.split(',')
+            .map(|value| value.parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| invalid.clone())?;
+        if values.len() < 2 || values.len() > 3 {
+            return Err(invalid);
+        }
+
+        let (longitude, latitude) = (values[0], values[1]);
+        if !(-90. ..=90.).contains(&latitude) || !(-180. ..=180.).contains(&longitude) {
+            return Err(invalid);
+        }
+        points.push(Point3::new(latitude, longitude, values.get(2).copied().unwrap_or(0.)));
+    }
+
+    return Ok(points);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, Point3, FormatError, to_kml, to_kml3, from_kml, from_kml3, encode_kml, decode_to_kml};
+
+    #[test]
+    fn roundtrip() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+        assert_eq!(from_kml(&to_kml(&points, 5)), Ok(points));
+        assert_eq!(from_kml(&to_kml(&[], 5)), Ok(vec![]));
+        assert_eq!(decode_to_kml("_p~iF~ps|U", 5), Ok(String::from("<LineString><coordinates>-120.20000,38.50000</coordinates></LineString>")));
+    }
+
+    #[test]
+    fn altitudes() {
+        let points = vec![Point3::new(38.5, -120.2, 1200.5), Point3::new(40.7, -120.95, -3.)];
+        assert_eq!(from_kml3(&to_kml3(&points, 5)), Ok(points));
+        assert_eq!(from_kml3("<LineString><coordinates>1,2</coordinates></LineString>"), Ok(vec![Point3::new(2., 1., 0.)]));
+    }
+
+    #[test]
+    fn google_earth_export() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <kml xmlns="http://www.opengis.net/kml/2.2">
+              <Document>
+                <Placemark>
+                  <name>Path</name>
+                  <Point><coordinates>0,0,0</coordinates></Point>
+                  <LineString>
+                    <tessellate>1</tessellate>
+                    <coordinates>
+                      -120.2,38.5,0 -120.95,40.7,0
+                      -126.453,43.252,0
+                    </coordinates>
+                  </LineString>
+                </Placemark>
+              </Document>
+            </kml>"#;
+        assert_eq!(encode_kml(kml, 5), Ok(String::from("_p~iF~ps|U_ulLnnqC_mqNvxq`@")));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(from_kml("<Point><coordinates>1,2</coordinates></Point>"), Err(FormatError::NotALineString));
+        assert_eq!(from_kml("<LineString><coordinates>1,2 3</coordinates></LineString>"), Err(FormatError::InvalidCoordinate { index: 1 }));
+        assert_eq!(from_kml("<LineString><coordinates>1,2,3,4</coordinates></LineString>"), Err(FormatError::InvalidCoordinate { index: 0 }));
+        assert_eq!(from_kml("<LineString><coordinates>1,x</coordinates></LineString>"), Err(FormatError::InvalidCoordinate { index: 0 }));
+        assert_eq!(from_kml("<LineString><coordinates>1,2"), Err(FormatError::Syntax { offset: 25 }));
+    }
+}
@@ -25,11 +25,243 @@
 //!      */
 //! }
 //! ```
+//!
+//! ## `no_std`
+//!
+//! The crate only needs an allocator for encoding and decoding. Disable the default `std`
+//! feature to use it on targets without the standard library:
+//!
+//! ```toml
+//! polyline_rust = { version = "0.1", default-features = false }
+//! ```
+//!
+//! Without `std` the codec ([`encode`], [`encode_to`], [`decode`], [`try_decode`],
+//! [`decode_iter`], ...), [`EncodedPolyline`] and the [`bytes`] format are available.
+//! [`encode_to`] writes into any `fmt::Write`, e.g. a fixed capacity string, and
+//! [`decode_iter`] yields points without collecting them. Everything based on distances or
+//! `std::io` requires `std`.
+//!
+//! ## Features
+//!
+//! All features are additive, `full` enables every integration of the library:
+//!
+//! | feature      | needs `std` | adds                                                   |
+//! |--------------|-------------|--------------------------------------------------------|
+//! | `std`        |             | everything based on distances or `std::io` (default)   |
+//! | `breaks`     | no          | the `breaks` module                                    |
+//! | `geo`        | no          | the `geo` module, conversions to `geo-types`           |
+//! | `prost`      | no          | the `proto` module, protobuf messages                  |
+//! | `serde`      | no          | `Serialize`/`Deserialize` for `Point`, `serde_polyline5` |
+//! | `context`    | yes         | the `context` module                                   |
+//! | `ffi`        | yes         | the `ffi` module, a C interface                        |
+//! | `flatgeobuf` | yes         | the `flatgeobuf` module                                |
+//! | `geodesic`   | yes         | the `geodesic` module                                  |
+//! | `gpx`        | yes         | the `gpx` module                                       |
+//! | `h3`         | yes         | the `h3` module                                        |
+//! | `rayon`      | yes         | the `parallel` module                                  |
+//! | `s2`         | yes         | the `s2` module                                        |
+//! | `shapefile`  | yes         | the `shapefile` module                                 |
+//! | `wasm`       | yes         | the `wasm` module, bindings for JavaScript             |
+//! | `cli`        | yes         | the `polyline` command line tool                       |
+//!
+//! Cargo enables `std` for the features that need it. Builds that set the features directly,
+//! e.g. with Bazel or Buck, fail with a `compile_error!` naming the feature if `std` is missing.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::needless_return)]
+
+extern crate alloc;
+// the tests use `std` even without the feature
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+#[cfg(all(not(feature = "std"), feature = "context"))]
+compile_error!("the feature `context` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "ffi"))]
+compile_error!("the feature `ffi` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "flatgeobuf"))]
+compile_error!("the feature `flatgeobuf` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "geodesic"))]
+compile_error!("the feature `geodesic` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "gpx"))]
+compile_error!("the feature `gpx` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "h3"))]
+compile_error!("the feature `h3` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "rayon"))]
+compile_error!("the feature `rayon` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "s2"))]
+compile_error!("the feature `s2` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "shapefile"))]
+compile_error!("the feature `shapefile` requires the feature `std`");
+#[cfg(all(not(feature = "std"), feature = "wasm"))]
+compile_error!("the feature `wasm` requires the feature `std`");
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
 
-mod chunks;
+mod batch;
+#[cfg(feature = "breaks")]
+pub mod breaks;
+#[cfg(feature = "std")]
+mod bounds;
+mod builder;
+pub mod bytes;
+#[cfg(feature = "std")]
+mod cancel;
+#[cfg(feature = "context")]
+pub mod context;
+#[cfg(feature = "std")]
+mod cluster;
+mod codec;
+#[cfg(feature = "std")]
+mod compare;
+mod coordinate;
+#[cfg(feature = "std")]
+mod corridor;
+mod csv;
+#[cfg(feature = "std")]
+pub mod datasets;
+mod decoder;
+#[cfg(feature = "std")]
+mod diagnose;
+#[cfg(feature = "std")]
+mod diff;
+mod edit;
+mod encoded;
+mod elevation;
+mod error;
+mod fast;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "flatgeobuf")]
+pub mod flatgeobuf;
+pub mod flexible;
+mod format;
+#[cfg(feature = "geo")]
+pub mod geo;
+mod geojson;
+#[cfg(feature = "geodesic")]
+pub mod geodesic;
+#[cfg(feature = "std")]
+mod geodesy;
+#[cfg(feature = "std")]
+pub mod geoparquet;
+#[cfg(feature = "gpx")]
+pub mod gpx;
+#[cfg(feature = "h3")]
+pub mod h3;
+#[cfg(feature = "std")]
+mod interpolate;
+mod info;
+mod iter;
+mod json;
+mod kml;
+#[cfg(feature = "std")]
+mod matching;
+#[cfg(feature = "std")]
+mod metrics;
+mod multi;
+#[cfg(feature = "std")]
+mod nearest;
+mod options;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+mod precision;
+#[cfg(feature = "prost")]
+pub mod proto;
+mod ring;
+#[cfg(feature = "s2")]
+pub mod s2;
+#[cfg(feature = "serde")]
+mod serde_polyline;
+#[cfg(feature = "shapefile")]
+pub mod shapefile;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod simplify;
+mod small;
+mod timed;
+#[cfg(feature = "std")]
+mod transform;
+pub mod v2;
+#[cfg(feature = "std")]
+mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+mod wkb;
+mod wkt;
+mod xml;
+
+pub use batch::{decode_batch, decode_batch_with_progress};
+#[cfg(feature = "std")]
+pub use bounds::{Bounds, bounds, decode_bounds, decode_within_bbox};
+pub use builder::PolylineBuilder;
+#[cfg(feature = "std")]
+pub use cluster::{cluster, cluster_cancellable, consensus_line};
+pub use codec::{Codec, set_default_codec, default_codec, encode_default, decode_default, try_decode_default};
+#[cfg(feature = "std")]
+pub use compare::{compare, ComparisonReport, OVERLAP_TOLERANCE_M};
+pub use coordinate::{Coordinate, FromCoordinate};
+/// Shorter name of [`Coordinate`], with the longitude as `lon` or `lng`.
+pub use coordinate::Coordinate as Coord;
+#[cfg(feature = "std")]
+pub use corridor::corridor_polygon;
+pub use csv::to_csv;
+pub use decoder::Decoder;
+#[cfg(feature = "std")]
+pub use diagnose::{diagnose, Diagnosis, Symptom};
+#[cfg(feature = "std")]
+pub use diff::{changed_sections, ChangedSection};
+pub use edit::{splice_encoded, remove_range_encoded, reverse_encoded, concat_encoded};
+#[cfg(feature = "std")]
+pub use edit::{insert_via, stitch};
+pub use elevation::{Point3, encode3, decode3, try_decode3};
+pub use encoded::{EncodedPolyline, Polyline};
+pub use error::{Error, DecodeError, ValidationError, FormatError, RangeError, Cancelled};
+pub use format::{display_points, DisplayPoint, DisplayPoints};
+#[cfg(feature = "std")]
+pub use geodesy::{distance, bearing, midpoint, destination, EARTH_RADIUS_M};
+pub use geojson::{to_geojson, from_geojson};
+#[cfg(feature = "std")]
+pub use interpolate::{point_at, point_at_fraction, resample, slice_between, split_at};
+pub use info::{validate, PolylineInfo};
+pub use iter::{decode_iter, DecodeIter};
+pub use kml::{to_kml, to_kml3, from_kml, from_kml3, encode_kml, decode_to_kml};
+#[cfg(feature = "std")]
+pub use matching::{MapMatcher, MatchedPath, SnapToRoute, match_encoded, matching_metrics, MatchingMetrics};
+#[cfg(feature = "std")]
+pub use metrics::{length, length_with, hausdorff, frechet, DistanceModel};
+pub use multi::{encode_multi, decode_multi, try_decode_multi, MULTI_SEPARATOR};
+#[cfg(feature = "std")]
+pub use nearest::{nearest_point, nearest_polyline};
+pub use options::{EncodeOptions, DecodeOptions, OddTrailingPolicy, AxisOrder};
+pub use precision::{Precision, encode_const, decode_const};
+pub use ring::{encode_ring, decode_ring, is_closed};
+#[cfg(feature = "serde")]
+pub use serde_polyline::{serde_polyline5, serde_polyline6};
+#[cfg(feature = "std")]
+pub use shared::SharedPolyline;
+#[cfg(feature = "std")]
+pub use simplify::{simplify, simplify_with, simplify_with_progress, simplify_cancellable, simplify_encoded, thin, SimplifyAlgorithm};
+pub use timed::{TimedPoint, encode_timed, decode_timed, try_decode_timed};
+#[cfg(feature = "std")]
+pub use transform::{translate, rotate_around, scale_about};
+#[cfg(feature = "std")]
+pub use validate::{validate_batch, validate_batch_cancellable, validate_points, find_jumps, ValidationReport, IssueCount, SUSPICIOUS_LENGTH};
+#[cfg(feature = "std")]
+pub use wkb::{to_wkb, from_wkb, encode_wkb, decode_to_wkb};
+pub use wkt::{to_wkt, from_wkt, encode_wkt, decode_to_wkt};
 
 /// Single Coordinate of a point on the polyline
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub latitude: f64,
     pub longitude: f64
@@ -43,52 +275,212 @@ impl Point {
             longitude
         };
     }
+
+    /// Whether both coordinates differ by at most `epsilon` degrees.
+    ///
+    /// Decoded coordinates are rarely bit-identical to the encoded ones, half a unit of the
+    /// precision (`0.000005` for precision 5) is a good `epsilon` to compare them.
+    ///
+    /// ```
+    /// use polyline_rust::{Point, encode, decode};
+    ///
+    /// let point = Point::new(38.123456, -120.2);
+    /// let decoded = decode(&encode(vec![point], 5), 5);
+    /// assert!(decoded[0].approx_eq(&point, 0.000005));
+    /// ```
+    pub fn approx_eq(&self, other: &Point, epsilon: f64) -> bool {
+        return (self.latitude - other.latitude).abs() <= epsilon && (self.longitude - other.longitude).abs() <= epsilon;
+    }
+}
+
+/// Whether both polylines have the same number of points and all of them are equal within
+/// `epsilon` degrees, see [`Point::approx_eq`].
+///
+/// ```
+/// use polyline_rust::{Point, encode, decode, points_approx_eq};
+///
+/// let points = vec![Point::new(38.123456, -120.2), Point::new(40.7, -120.950004)];
+/// assert!(points_approx_eq(&decode(&encode(&points, 5), 5), &points, 0.000005));
+/// ```
+pub fn points_approx_eq(a: &[Point], b: &[Point], epsilon: f64) -> bool {
+    return a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.approx_eq(b, epsilon));
 }
 
 /// Encodes coordinates to the "Encoded Polyline Algorithm Format".
 ///
 /// More info: [https://developers.google.com/maps/documentation/utilities/polylinealgorithm](https://developers.google.com/maps/documentation/utilities/polylinealgorithm)
 ///
-/// `points`: points of the polyline
+/// `points`: points of the polyline; anything iterable over `Point`s or other [`Coordinate`]s
+/// and references to them, e.g. a `Vec<Point>`, a `&[Point]` or an iterator adapter
 ///
 /// `precision`: usually 5 or 6; Google's original algorithm uses 5 digits of decimal precision,
-/// which is accurate to about a meter. A precision of 6 gives you an accuracy of about 10cm
+/// which is accurate to about a meter. A precision of 6 gives you an accuracy of about 10cm.
+/// At most 9 is supported
 ///
 /// More info: [https://mapzen.com/blog/polyline-precision/](https://mapzen.com/blog/polyline-precision/)
-pub fn encode(points: Vec<Point>, precision: u32) -> String {
+pub fn encode<I>(points: I, precision: u32) -> String
+    where I: IntoIterator, I::Item: Coordinate {
+    let points = points.into_iter();
+    if points.size_hint().1.is_some_and(|length| length <= small::SMALL_INPUT_POINTS) {
+        return small::encode(points, precision);
+    }
+
+    let mut encoded = String::with_capacity(estimate_length(points.size_hint().0, precision));
+    // writing into a `String` never fails
+    let _ = encode_to(points, precision, &mut encoded);
+
+    return encoded;
+}
+
+/// Typical length of a polyline of `points` points, enough for most tracks and routes.
+fn estimate_length(points: usize, precision: u32) -> usize {
+    // deltas of consecutive points are usually small, with a group of 2 to 4 characters at
+    // precision 5 and one more for every additional digit; higher precisions than the supported
+    // ones don't make the groups longer than 13 characters
+    let precision = precision.min(Precision::MAX) as usize;
+    return points.saturating_mul(2 * precision.saturating_sub(1).max(2));
+}
+
+/// Encodes coordinates like [`encode`] but rejects coordinates that aren't on earth.
+///
+/// `encode` happily encodes a latitude of 1234.5 into a string that decodes fine but makes
+/// no sense. `try_encode` catches that at the boundary instead.
+///
+/// `points`: points of the polyline, see [`encode`]
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+///
+/// Returns [`ValidationError::NotFinite`] for NaN or infinite coordinates and
+/// [`ValidationError::OutOfRange`] for latitudes outside of [-90, 90] and longitudes outside
+/// of [-180, 180], both with the index of the first invalid point.
+///
+/// ```
+/// use polyline_rust::{Point, try_encode};
+///
+/// assert_eq!(try_encode(vec![Point::new(38.5, -120.2)], 5).unwrap(), "_p~iF~ps|U");
+///
+/// let error = try_encode(vec![Point::new(38.5, -120.2), Point::new(1234.5, 0.)], 5).unwrap_err();
+/// assert_eq!(error.to_string(), "latitude 1234.5 of point 1 (1234.5, 0) is outside of [-90, 90]");
+/// ```
+pub fn try_encode<I>(points: I, precision: u32) -> Result<String, ValidationError>
+    where I: IntoIterator, I::Item: Coordinate {
     let mut encoded = String::new();
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
 
+    for (index, point) in points.into_iter().enumerate() {
+        if !point.lat().is_finite() || !point.lng().is_finite() {
+            return Err(ValidationError::NotFinite { index });
+        }
+        if !(-90. ..=90.).contains(&point.lat()) || !(-180. ..=180.).contains(&point.lng()) {
+            return Err(ValidationError::OutOfRange { index, latitude: point.lat(), longitude: point.lng() });
+        }
+
+        push_element(&mut encoded, point.lat()-latitude, precision);
+        push_element(&mut encoded, point.lng()-longitude, precision);
+
+        latitude = point.lat();
+        longitude = point.lng();
+    }
+
+    return Ok(encoded);
+}
+
+/// Encodes coordinates and writes them to `writer` as they are encoded, without building the
+/// whole polyline string first.
+///
+/// `points`: points of the polyline, see [`encode`]
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+///
+/// `writer`: destination of the polyline string, e.g. a `String`, a `fmt::Formatter` or an
+/// arena string like `bumpalo::collections::String`
+///
+/// ```
+/// use polyline_rust::{Point, encode_to};
+///
+/// let mut json = String::from("{\"polyline\":\"");
+/// encode_to(vec![Point::new(38.5, -120.2)], 5, &mut json).unwrap();
+/// json.push_str("\"}");
+/// assert_eq!(json, "{\"polyline\":\"_p~iF~ps|U\"}");
+/// ```
+pub fn encode_to<I, W>(points: I, precision: u32, writer: &mut W) -> fmt::Result
+    where I: IntoIterator, I::Item: Coordinate, W: fmt::Write {
+    let scale = precision::scale(precision);
+    let mut group = [0_u8; MAX_GROUP_LENGTH];
     let mut latitude: f64 = 0.;
     let mut longitude: f64 = 0.;
 
-    for point in points.iter() {
-        let poly_latitude = encode_element(point.latitude-latitude, precision);
-        encoded += poly_latitude.as_str();
+    for point in points {
+        let length = write_element(point.lat()-latitude, scale, &mut group);
+        writer.write_str(small::as_str(&group[..length]))?;
+        let length = write_element(point.lng()-longitude, scale, &mut group);
+        writer.write_str(small::as_str(&group[..length]))?;
+
+        latitude = point.lat();
+        longitude = point.lng();
+    }
 
-        let poly_longitude = encode_element(point.longitude-longitude, precision);
-        encoded += poly_longitude.as_str();
+    return Ok(());
+}
 
-        latitude = point.latitude;
-        longitude = point.longitude;
+/// Encodes coordinates and writes them to an `io::Write`, e.g. a file or socket, see [`encode_to`].
+///
+/// The writer isn't buffered or flushed, wrap it in a `BufWriter` for unbuffered destinations.
+#[cfg(feature = "std")]
+pub fn encode_to_writer<I, W>(points: I, precision: u32, writer: &mut W) -> io::Result<()>
+    where I: IntoIterator, I::Item: Coordinate, W: io::Write {
+    let scale = precision::scale(precision);
+    let mut group = [0_u8; MAX_GROUP_LENGTH];
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+
+    for point in points {
+        let length = write_element(point.lat()-latitude, scale, &mut group);
+        writer.write_all(&group[..length])?;
+        let length = write_element(point.lng()-longitude, scale, &mut group);
+        writer.write_all(&group[..length])?;
+
+        latitude = point.lat();
+        longitude = point.lng();
     }
 
-    return encoded;
+    return Ok(());
 }
 
 /// Shorthand call for encode with precision set to 5.
 ///
 /// Accuracy is about one meter.
-pub fn encode5(points: Vec<Point>) -> String {
+#[deprecated(note = "use `v2::encode(&points, 5)` or `Codec::POLYLINE5.encode(points)`")]
+pub fn encode5<I>(points: I) -> String
+    where I: IntoIterator, I::Item: Coordinate {
     return encode(points, 5);
 }
 
 /// Shorthand call for encode with precision set to 6.
 ///
 /// Accuracy is about ten centimeters.
-pub fn encode6(points: Vec<Point>) -> String {
+#[deprecated(note = "use `v2::encode(&points, 6)` or `Codec::POLYLINE6.encode(points)`")]
+pub fn encode6<I>(points: I) -> String
+    where I: IntoIterator, I::Item: Coordinate {
     return encode(points, 6);
 }
 
+/// Encodes `(latitude, longitude)` pairs, see [`encode`].
+///
+/// `points`: latitude and longitude of every point
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+///
+/// ```
+/// use polyline_rust::encode_tuples;
+///
+/// assert_eq!(encode_tuples(&[(38.5, -120.2), (40.7, -120.95)], 5), "_p~iF~ps|U_ulLnnqC");
+/// ```
+pub fn encode_tuples(points: &[(f64, f64)], precision: u32) -> String {
+    return encode(points, precision);
+}
+
 /// Decodes coordinates from the "Encoded Polyline Algorithm Format".
 ///
 /// More info: [https://developers.google.com/maps/documentation/utilities/polylinealgorithm](https://developers.google.com/maps/documentation/utilities/polylinealgorithm)
@@ -97,22 +489,427 @@ pub fn encode6(points: Vec<Point>) -> String {
 ///
 /// `precision`: Usually 5 or 6; Google's original algorithm uses 5 digits of decimal precision,
 /// which is accurate to about a meter. A precision of 6 gives you an accuracy of about 10cm.
+/// At most 9 is supported.
 ///
 /// More info: [https://mapzen.com/blog/polyline-precision/](https://mapzen.com/blog/polyline-precision/)
+///
+/// Never panics, arbitrary input gives the points up to the first invalid character.
 pub fn decode(polyline: &str, precision: u32) -> Vec<Point> {
+    if let Some(points) = small::decode(polyline, precision) {
+        return points;
+    }
+    if let Some(points) = fast::decode(polyline, precision) {
+        return points;
+    }
+
+    let (coordinates, _) = decode_coordinates(polyline, precision);
+    return pair_coordinates(&coordinates, precision);
+}
+
+/// Decodes coordinates from the "Encoded Polyline Algorithm Format" and fails on malformed input.
+///
+/// [`decode`] is lenient: it stops at the first invalid character and silently drops
+/// incomplete data at the end. `try_decode` instead returns an error, see [`DecodeError`].
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+pub fn try_decode(polyline: &str, precision: u32) -> Result<Vec<Point>, DecodeError> {
+    if let Some(points) = small::decode(polyline, precision) {
+        return Ok(points);
+    }
+    if let Some(points) = fast::decode(polyline, precision) {
+        return Ok(points);
+    }
+
+    let (coordinates, error) = decode_coordinates(polyline, precision);
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    if coordinates.len() % 2 == 1 {
+        return Err(DecodeError::DanglingCoordinate { offset: last_group_offset(polyline) });
+    }
+
+    return Ok(pair_coordinates(&coordinates, precision));
+}
+
+/// Decodes coordinates like [`decode`] from raw bytes, e.g. a network buffer or a memory
+/// mapped file.
+///
+/// The bytes don't have to be valid UTF-8: like any other invalid character, decoding stops
+/// at the first byte that isn't.
+///
+/// `polyline`: polyline in "Encoded Polyline Algorithm Format" as ASCII bytes
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+///
+/// ```
+/// use polyline_rust::{Point, decode_bytes};
+///
+/// assert_eq!(decode_bytes(b"_p~iF~ps|U", 5), vec![Point::new(38.5, -120.2)]);
+/// assert_eq!(decode_bytes(b"_p~iF~ps|U\xff_ulLnnqC", 5), vec![Point::new(38.5, -120.2)]);
+/// ```
+pub fn decode_bytes(polyline: &[u8], precision: u32) -> Vec<Point> {
+    return decode(valid_prefix(polyline), precision);
+}
+
+/// Decodes coordinates like [`try_decode`] from raw bytes, see [`decode_bytes`].
+///
+/// A byte that isn't valid UTF-8 is reported as [`DecodeError::InvalidCharacter`] with
+/// [`char::REPLACEMENT_CHARACTER`]; offsets are byte offsets into `polyline`.
+///
+/// `polyline`: polyline in "Encoded Polyline Algorithm Format" as ASCII bytes
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+pub fn try_decode_bytes(polyline: &[u8], precision: u32) -> Result<Vec<Point>, DecodeError> {
+    let prefix = valid_prefix(polyline);
+    if prefix.len() == polyline.len() {
+        return try_decode(prefix, precision);
+    }
+
+    return match try_decode(prefix, precision) {
+        // the prefix ends where the invalid byte cuts off the polyline, that's not dangling
+        Err(DecodeError::DanglingGroup { .. }) | Err(DecodeError::DanglingCoordinate { .. }) | Ok(_) => {
+            Err(DecodeError::InvalidCharacter { offset: prefix.len(), character: char::REPLACEMENT_CHARACTER })
+        },
+        Err(error) => Err(error)
+    };
+}
+
+/// The longest prefix of `bytes` that is valid UTF-8.
+fn valid_prefix(bytes: &[u8]) -> &str {
+    return match core::str::from_utf8(bytes) {
+        Ok(valid) => valid,
+        Err(error) => core::str::from_utf8(&bytes[..error.valid_up_to()]).unwrap_or("")
+    };
+}
+
+/// Decodes coordinates like [`decode`] but doesn't allocate for polylines without points.
+///
+/// Empty (and invalid) polylines borrow a static empty slice, single points are decoded
+/// directly into a `Vec` of exactly one point without intermediate buffers. Useful for
+/// endpoints where most requests carry no geometry.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+///
+/// ```
+/// use std::borrow::Cow;
+/// use polyline_rust::decode_cow;
+///
+/// assert!(matches!(decode_cow("", 5), Cow::Borrowed(&[])));
+/// assert_eq!(decode_cow("_p~iF~ps|U", 5).len(), 1);
+/// ```
+pub fn decode_cow(polyline: &str, precision: u32) -> Cow<'static, [Point]> {
+    let mut points = decode_iter(polyline, precision);
+    let first = match points.next() {
+        Some(first) => first,
+        None => return Cow::Borrowed(&[])
+    };
+    if points.next().is_none() {
+        return Cow::Owned(Vec::from([first]));
+    }
+
+    return Cow::Owned(decode(polyline, precision));
+}
+
+/// Decodes coordinates like [`decode`] and appends them to any collection, e.g. a `Vec` in
+/// an arena or with a custom allocator.
+///
+/// No memory is allocated besides the growth of `points`, so with an arena collection (e.g.
+/// `bumpalo::collections::Vec`) all memory of a request can be freed at once.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+///
+/// `points`: the points are appended to it
+///
+/// ```
+/// use bumpalo::Bump;
+/// use bumpalo::collections::Vec as BumpVec;
+/// use polyline_rust::{Point, decode_into};
+///
+/// let arena = Bump::new();
+/// let mut points = BumpVec::new_in(&arena);
+/// decode_into("_p~iF~ps|U_ulLnnqC", 5, &mut points);
+/// assert_eq!(points[1], Point::new(40.7, -120.95));
+/// ```
+pub fn decode_into<C>(polyline: &str, precision: u32, points: &mut C)
+    where C: Extend<Point> {
+    points.extend(decode_iter(polyline, precision));
+}
+
+/// Decodes coordinates like [`try_decode`] and appends them to any collection, see [`decode_into`].
+///
+/// The polyline is checked before decoding, nothing is appended if it is malformed.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+///
+/// `points`: the points are appended to it
+pub fn try_decode_into<C>(polyline: &str, precision: u32, points: &mut C) -> Result<(), DecodeError>
+    where C: Extend<Point> {
+    if scan_groups(polyline, |_| {})? % 2 == 1 {
+        return Err(DecodeError::DanglingCoordinate { offset: last_group_offset(polyline) });
+    }
+
+    points.extend(decode_iter(polyline, precision));
+    return Ok(());
+}
+
+/// Decodes coordinates like [`decode`] directly into values of a user type.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+///
+/// ```
+/// use polyline_rust::{FromCoordinate, decode_as};
+///
+/// struct Stop {
+///     lat: f64,
+///     lon: f64,
+///     name: Option<String>
+/// }
+///
+/// impl FromCoordinate for Stop {
+///     fn from_coordinate(lat: f64, lng: f64) -> Stop {
+///         return Stop { lat, lon: lng, name: None };
+///     }
+/// }
+///
+/// let stops: Vec<Stop> = decode_as("_p~iF~ps|U_ulLnnqC", 5);
+/// assert_eq!((stops[1].lat, stops[1].lon, stops[1].name.is_none()), (40.7, -120.95, true));
+/// ```
+pub fn decode_as<T>(polyline: &str, precision: u32) -> Vec<T>
+    where T: FromCoordinate {
+    return decode_iter(polyline, precision)
+        .map(|point| T::from_coordinate(point.latitude, point.longitude))
+        .collect();
+}
+
+/// Decodes coordinates like [`try_decode`] directly into values of a user type, see [`decode_as`].
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+pub fn try_decode_as<T>(polyline: &str, precision: u32) -> Result<Vec<T>, DecodeError>
+    where T: FromCoordinate {
+    let mut values: Vec<T> = Vec::new();
+    try_decode_into(polyline, precision, &mut DecodeAs(&mut values))?;
+
+    return Ok(values);
+}
+
+/// Adapter that turns the `Point`s of [`try_decode_into`] into user types.
+struct DecodeAs<'a, T>(&'a mut Vec<T>);
+
+impl<'a, T: FromCoordinate> Extend<Point> for DecodeAs<'a, T> {
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, points: I) {
+        self.0.extend(points.into_iter().map(|point| T::from_coordinate(point.latitude, point.longitude)));
+    }
+}
+
+/// Shorthand call for Decode with precision set to 5.
+///
+/// Accuracy is about one meter.
+#[deprecated(note = "use `v2::decode(polyline, &DecodeOptions::new(5))` or `Codec::POLYLINE5.decode(polyline)`")]
+pub fn decode5(polyline: &str) -> Vec<Point> {
+    return decode(polyline, 5);
+}
+
+/// Shorthand call for Decode with precision set to 6.
+///
+/// Accuracy is about ten centimeters.
+#[deprecated(note = "use `v2::decode(polyline, &DecodeOptions::new(6))` or `Codec::POLYLINE6.decode(polyline)`")]
+pub fn decode6(polyline: &str) -> Vec<Point> {
+    return decode(polyline, 6);
+}
+
+/// Encodes coordinates like [`encode`] with the adjustments and checks enabled in `options`.
+///
+/// `points`: points of the polyline, see [`encode`]
+///
+/// `options`: precision, adjustments and checks, see [`EncodeOptions`]
+///
+/// Returns an error only if [`EncodeOptions::validate`] is enabled, see [`try_encode`].
+///
+/// ```
+/// use polyline_rust::{Point, EncodeOptions, encode_with};
+///
+/// let options = EncodeOptions::new(5).dedup().validate();
+/// let track = vec![Point::new(38.5, -120.2), Point::new(38.500001, -120.2), Point::new(40.7, -120.95)];
+/// assert_eq!(encode_with(&track, &options).unwrap(), "_p~iF~ps|U_ulLnnqC");
+/// ```
+pub fn encode_with<I>(points: I, options: &EncodeOptions) -> Result<String, ValidationError>
+    where I: IntoIterator, I::Item: Coordinate {
+    let precision = options.precision;
+    let mut encoded = String::new();
+    let mut previous: Option<(f64, f64)> = None;
+
+    for (index, point) in points.into_iter().enumerate() {
+        let latitude = point.lat();
+        let longitude = if options.normalize_longitude { options::wrap_longitude(point.lng()) } else { point.lng() };
+        if options.validate {
+            if !latitude.is_finite() || !longitude.is_finite() {
+                return Err(ValidationError::NotFinite { index });
+            }
+            if !(-90. ..=90.).contains(&latitude) || !(-180. ..=180.).contains(&longitude) {
+                return Err(ValidationError::OutOfRange { index, latitude, longitude });
+            }
+        }
+
+        // the encoder writes the "latitude" first
+        let current = match options.axis_order {
+            AxisOrder::LatLng => (latitude, longitude),
+            AxisOrder::LngLat => (longitude, latitude)
+        };
+        let (last_first, last_second) = previous.unwrap_or((0., 0.));
+        if options.dedup && previous.is_some() && round(current.0, precision) == round(last_first, precision)
+            && round(current.1, precision) == round(last_second, precision) {
+            continue;
+        }
+
+        push_element(&mut encoded, current.0 - last_first, precision);
+        push_element(&mut encoded, current.1 - last_second, precision);
+        previous = Some(current);
+    }
+
+    return Ok(encoded);
+}
+
+/// Decodes coordinates from the "Encoded Polyline Algorithm Format" and runs the checks
+/// enabled in `options`.
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `options`: precision and checks, see [`DecodeOptions`]
+pub fn decode_with(polyline: &str, options: &DecodeOptions) -> Result<Vec<Point>, DecodeError> {
+    let (mut coordinates, error) = decode_coordinates(polyline, options.precision);
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    if coordinates.len() % 2 == 1 {
+        match options.odd_trailing {
+            OddTrailingPolicy::DropSilently => {},
+            OddTrailingPolicy::Error => {
+                return Err(DecodeError::DanglingCoordinate { offset: last_group_offset(polyline) });
+            },
+            OddTrailingPolicy::PairWithZero => coordinates.push(0.)
+        }
+    }
+    let mut points = pair_coordinates(&coordinates, options.precision);
+    if options.axis_order == AxisOrder::LngLat {
+        for point in points.iter_mut() {
+            core::mem::swap(&mut point.latitude, &mut point.longitude);
+        }
+    }
+    if options.dedup {
+        points.dedup();
+    }
+
+    if options.reject_out_of_range {
+        let out_of_range = points.iter()
+            .position(|point| !(-90. ..=90.).contains(&point.latitude) || !(-180. ..=180.).contains(&point.longitude));
+        if let Some(index) = out_of_range {
+            return Err(DecodeError::OutOfRange {
+                index,
+                latitude: points[index].latitude,
+                longitude: points[index].longitude
+            });
+        }
+    }
+
+    #[cfg(feature = "std")]
+    if let Some(max_jump_m) = options.max_jump_m {
+        if let Some(&index) = find_jumps(&points, max_jump_m).first() {
+            return Err(DecodeError::JumpTooLarge {
+                index,
+                distance_m: distance(&points[index-1], &points[index])
+            });
+        }
+    }
+
+    return Ok(points);
+}
+
+/// Maximum number of 5 bit chunks in a group; the last one may only use the remaining 4 bits
+/// of a 64 bit integer.
+const MAX_GROUP_LENGTH: usize = 13;
 
-    let mut group = String::new();
+/// Highest value of the last chunk of a group with [`MAX_GROUP_LENGTH`] chunks.
+const MAX_LAST_CHUNK: u32 = 0b1111;
+
+/// Splits the polyline into groups and decodes every group into a coordinate delta.
+///
+/// Stops at the first problem and returns the coordinates decoded so far together with the error.
+fn decode_coordinates(polyline: &str, precision: u32) -> (Vec<f64>, Option<DecodeError>) {
     let mut coordinates: Vec<f64> = Vec::new();
+    let scale = precision::scale(precision);
+    let error = scan_groups(polyline, |value| coordinates.push(signed_value(value) as f64 / scale)).err();
 
-    for letter in polyline.chars() {
-        group += letter.to_string().as_str();
+    return (coordinates, error);
+}
+
+/// Calls `on_group` with the value of every group of the polyline until the first problem,
+/// see [`group_value`].
+///
+/// Returns the number of groups.
+fn scan_groups<F>(polyline: &str, mut on_group: F) -> Result<usize, DecodeError>
+    where F: FnMut(u64) {
+    return scan_group_offsets(polyline, |value, _| on_group(value));
+}
+
+/// Like [`scan_groups`], but also passes the byte offset at which each group starts.
+fn scan_group_offsets<F>(polyline: &str, mut on_group: F) -> Result<usize, DecodeError>
+    where F: FnMut(u64, usize) {
+    let mut group_start = 0;
+    let mut count = 0;
+    let mut value: u64 = 0;
+
+    for (offset, letter) in polyline.char_indices() {
+        if !('?'..='~').contains(&letter) {
+            return Err(DecodeError::InvalidCharacter { offset, character: letter });
+        }
+
+        let chunk = letter as u32 - 63;
+        let group_length = offset - group_start + 1;
+        if group_length > MAX_GROUP_LENGTH || (group_length == MAX_GROUP_LENGTH && chunk & 0x1f > MAX_LAST_CHUNK) {
+            return Err(DecodeError::ChunkOutOfRange { offset: group_start });
+        }
 
-        if (letter as i32 - 63) & 0x20 == 0 {
-            coordinates.push(decode_element(group.as_str(), precision));
-            group = String::new();
+        // at most 13 chunks, so the shift stays below 64
+        value |= ((chunk & 0x1f) as u64) << (5 * (group_length - 1));
+        if chunk & 0x20 == 0 {
+            on_group(value, group_start);
+            count += 1;
+            group_start = offset + 1;
+            value = 0;
         }
     }
 
+    if group_start < polyline.len() {
+        return Err(DecodeError::DanglingGroup { offset: group_start });
+    }
+
+    return Ok(count);
+}
+
+/// Byte offset of the last group of a polyline without dangling group.
+fn last_group_offset(polyline: &str) -> usize {
+    // all groups are terminated, so the last one starts after the second to last terminator
+    return polyline[..polyline.len()-1]
+        .rfind(|letter: char| (letter as u32 - 63) & 0x20 == 0)
+        .map_or(0, |terminator| terminator + 1);
+}
+
+/// Turns coordinate deltas into points; a dangling latitude at the end is ignored.
+fn pair_coordinates(coordinates: &[f64], precision: u32) -> Vec<Point> {
     let mut points: Vec<Point> = Vec::new();
     let mut i = 1;
     while i < coordinates.len() {
@@ -135,47 +932,105 @@ pub fn decode(polyline: &str, precision: u32) -> Vec<Point> {
     return points;
 }
 
-/// Shorthand call for Decode with precision set to 5.
-///
-/// Accuracy is about one meter.
-pub fn decode5(polyline: &str) -> Vec<Point> {
-    return decode(polyline, 5);
+/// Appends the group of one coordinate delta to `encoded`, without allocating anything else.
+fn push_element(encoded: &mut String, element: f64, precision: u32) {
+    let mut group = [0_u8; MAX_GROUP_LENGTH];
+    let length = write_element(element, precision::scale(precision), &mut group);
+    encoded.push_str(small::as_str(&group[..length]));
 }
 
-/// Shorthand call for Decode with precision set to 6.
+/// Appends the group of a delta that is already an integer to `encoded`, without going through
+/// `f64`, e.g. timestamps or deltas copied from another polyline.
+fn push_integer(encoded: &mut String, value: i64) {
+    let mut group = [0_u8; MAX_GROUP_LENGTH];
+    let length = write_integer(value, &mut group);
+    encoded.push_str(small::as_str(&group[..length]));
+}
+
+/// Writes the group of one coordinate delta into `buffer`, returns the number of bytes written.
 ///
-/// Accuracy is about ten centimeters.
-pub fn decode6(polyline: &str) -> Vec<Point> {
-    return decode(polyline, 6);
+/// `scale`: `10^precision`
+///
+/// `buffer`: at least [`MAX_GROUP_LENGTH`] bytes
+fn write_element(element: f64, scale: f64, buffer: &mut [u8]) -> usize {
+    // the sign of the rounded value, small negative deltas round to 0 and are written as `?`
+    return write_integer(round_to_integer(element * scale) as i64, buffer);
 }
 
-fn encode_element(element: f64, precision: u32) -> String {
-    let base10: u32 = 10;
-    let mut element_int: i32 = (element * base10.pow(precision) as f64).round() as i32;
-    element_int = element_int << 1;
-    if element < 0 as f64 {
-        element_int = !element_int;
+/// Writes the group of an integer delta into `buffer`, returns the number of bytes written.
+///
+/// `buffer`: at least [`MAX_GROUP_LENGTH`] bytes
+fn write_integer(value: i64, buffer: &mut [u8]) -> usize {
+    let mut remaining = ((value << 1) ^ (value >> 63)) as u64;
+    let mut length = 0;
+    while remaining >= 0x20 {
+        buffer[length] = (0x20 | (remaining & 0x1f)) as u8 + 63;
+        remaining >>= 5;
+        length += 1;
     }
-    
-    let mut c = chunks::Chunks::new();
-    c.parse(element_int as u32);
-
-    return c.string();
+    buffer[length] = remaining as u8 + 63;
 
+    return length + 1;
 }
 
 fn decode_element(group: &str, precision: u32) -> f64 {
+    return signed_value(group_value(group)) as f64 / precision::scale(precision);
+}
+
+/// Joins the 5 bit chunks of a group into the encoded value, before undoing the sign encoding.
+fn group_value(group: &str) -> u64 {
+    let mut value: u64 = 0;
+    for (i, byte) in group.bytes().enumerate() {
+        // groups longer than 13 chunks don't fit into 64 bits and are rejected while decoding
+        if i < MAX_GROUP_LENGTH {
+            value |= (u64::from(byte.wrapping_sub(63)) & 0x1f) << (5 * i);
+        }
+    }
 
-    let mut c = chunks::Chunks::new();
-    c.parse_line(group);
-    return c.coordinate(precision);
+    return value;
+}
 
+/// Undoes the sign encoding of a group value: the lowest bit is set for negative values,
+/// which are inverted.
+fn signed_value(value: u64) -> i64 {
+    let signed = value as i64;
+    return (signed ^ -(signed & 1)) >> 1;
 }
 
+/// Rounds `n` to `precision` decimals.
+///
+/// Nothing is rounded if `n` has no digits below the precision, e.g. for absurd precisions
+/// whose scale is infinite, so finite values stay finite.
 fn round(n: f64, precision: u32) -> f64 {
-    let factor = 10_u32.pow(precision) as f64;
+    let factor = precision::scale(precision);
+    let scaled = n*factor;
+    // from 2^52 on all floats are integers, an infinite factor gives infinity or NaN
+    if scaled.is_nan() || scaled.abs() >= 4_503_599_627_370_496. {
+        return n;
+    }
+
+    return round_to_integer(scaled) / factor;
+}
+
+/// Rounds half away from zero like `f64::round`, which isn't available without `std`.
+#[cfg(feature = "std")]
+fn round_to_integer(n: f64) -> f64 {
+    return n.round();
+}
 
-    return (n*factor).round() / factor;
+/// Rounds half away from zero like `f64::round`, which isn't available without `std`.
+#[cfg(not(feature = "std"))]
+fn round_to_integer(n: f64) -> f64 {
+    // from 2^52 on all floats are integers
+    if n.is_nan() || n.abs() >= 4_503_599_627_370_496. {
+        return n;
+    }
+
+    let truncated = n as i64 as f64;
+    if (n - truncated).abs() >= 0.5 {
+        return truncated + if n < 0. { -1. } else { 1. };
+    }
+    return truncated;
 }
 
 #[cfg(test)]
@@ -183,11 +1038,14 @@ mod tests {
     mod encode_tests {
 
         mod precision_5 {
-            use crate::{Point, encode, encode5};
+            use std::prelude::v1::*;
+            use crate::{Point, ValidationError, encode, try_encode, encode_to};
+            #[allow(deprecated)]
+            use crate::encode5;
 
             #[test]
             fn empty_string() {
-                assert_eq!(encode(vec![], 5), "");
+                assert_eq!(encode(Vec::<Point>::new(), 5), "");
             }
 
             #[test]
@@ -213,17 +1071,74 @@ mod tests {
             }
 
             #[test]
+            fn absurd_precision() {
+                // the estimated length of the string doesn't grow with the precision
+                let points = vec![Point::new(38.5, -120.2); 32];
+                assert_eq!(encode(&points, u32::MAX).len(), 2 * 13 + 31 * 2);
+            }
+
+            #[test]
+            fn jitter_rounding_to_zero() {
+                assert_eq!(encode(vec![Point::new(0.000001, 0.), Point::new(0., 0.)], 5), "????");
+                assert_eq!(encode(vec![Point::new(0., -0.000004)], 5), "??");
+                assert_eq!(encode(vec![Point::new(-0.000006, 0.)], 5), "@?");
+            }
+
+            #[test]
+            fn slices_and_iterators() {
+                let points = vec![
+                    Point::new(38.5, -120.2),
+                    Point::new(40.7, -120.95),
+                    Point::new(43.252, -126.453)
+                ];
+                assert_eq!(encode(&points, 5), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+                assert_eq!(encode(&points[..2], 5), "_p~iF~ps|U_ulLnnqC");
+                assert_eq!(encode(points.iter().skip(2), 5), encode(vec![Point::new(43.252, -126.453)], 5));
+                assert_eq!(encode(points.iter().map(|p| Point::new(p.latitude, 0.)), 5), "_p~iF?_ulL?_mqN?");
+            }
+
+            #[test]
+            fn streaming() {
+                let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+
+                let mut encoded = String::from("polyline: ");
+                encode_to(&points, 5, &mut encoded).unwrap();
+                assert_eq!(encoded, "polyline: _p~iF~ps|U_ulLnnqC");
+            }
+
+            #[test]
+            #[cfg(feature = "std")]
+            fn streaming_to_writer() {
+                let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+                let mut bytes: Vec<u8> = Vec::new();
+                crate::encode_to_writer(&points, 5, &mut bytes).unwrap();
+                assert_eq!(bytes, b"_p~iF~ps|U_ulLnnqC");
+            }
+
+            #[test]
+            fn try_encode_checks_range() {
+                let points = vec![Point::new(-79.448, -179.9832104), Point::new(90., 180.)];
+                assert_eq!(try_encode(&points, 5), Ok(encode(&points, 5)));
+                assert_eq!(try_encode(vec![Point::new(0., -180.5)], 5), Err(ValidationError::OutOfRange { index: 0, latitude: 0., longitude: -180.5 }));
+                assert_eq!(try_encode(vec![Point::new(0., 0.), Point::new(f64::NAN, 0.)], 5), Err(ValidationError::NotFinite { index: 1 }));
+            }
+
+            #[test]
+            #[allow(deprecated)]
             fn test_encode5() {
                 assert_eq!(encode5(vec![Point::new(-79.448, -179.9832104)]), "~d|cN`~oia@");
             }
         }
 
         mod precision_6 {
-            use crate::{Point, encode, encode6};
+            use std::prelude::v1::*;
+            use crate::{Point, encode};
+            #[allow(deprecated)]
+            use crate::encode6;
 
             #[test]
             fn empty_string() {
-                assert_eq!(encode(vec![], 6), "");
+                assert_eq!(encode(Vec::<Point>::new(), 6), "");
             }
 
             #[test]
@@ -255,6 +1170,7 @@ mod tests {
             }
 
             #[test]
+            #[allow(deprecated)]
             fn test_encode6() {
                 assert_eq!(encode6(vec![Point::new(-79.4486385, -179.9832104)]), "|bdpvCruhhvI");
             }
@@ -263,7 +1179,9 @@ mod tests {
 
     mod decode_tests {
         mod precision_5 {
-            use crate::{Point, decode, decode5};
+            use crate::{Point, decode};
+            #[allow(deprecated)]
+            use crate::decode5;
             #[test]
             fn empty_string() {
                 assert_eq!(decode("", 5), vec![]);
@@ -299,6 +1217,7 @@ mod tests {
             }
 
             #[test]
+            #[allow(deprecated)]
             fn test_decode5() {
                 assert_eq!(decode5("~d|cN`~oia@"), vec![
                     Point::new(-79.448, -179.98321)
@@ -307,7 +1226,9 @@ mod tests {
         }
 
         mod precision_6 {
-            use crate::{Point, decode, decode6};
+            use crate::{Point, decode};
+            #[allow(deprecated)]
+            use crate::decode6;
             #[test]
             fn empty_string() {
                 assert_eq!(decode("", 6), vec![]);
@@ -347,9 +1268,386 @@ mod tests {
             }
 
             #[test]
+            #[allow(deprecated)]
             fn test_decode6() {
                 assert_eq!(decode6("|bdpvCruhhvI"), vec![Point::new(-79.448639, -179.98321)]);
             }
         }
+
+        mod precision_7_to_9 {
+            use std::prelude::v1::*;
+            use crate::{Point, encode, decode, try_decode};
+
+            fn extremes() -> Vec<Point> {
+                return vec![
+                    Point::new(-90., -180.),
+                    Point::new(90., 180.),
+                    Point::new(89.999999999, 179.999999999),
+                    Point::new(-0.000000001, -179.999999999)
+                ];
+            }
+
+            #[test]
+            fn extreme_coordinates() {
+                for precision in 7..=9 {
+                    let encoded = encode(extremes(), precision);
+                    assert_eq!(try_decode(&encoded, precision).map(|points| points.len()), Ok(4));
+
+                    let factor = 10_f64.powi(precision as i32);
+                    for (decoded, original) in decode(&encoded, precision).iter().zip(extremes().iter()) {
+                        assert!((decoded.latitude - original.latitude).abs() * factor <= 0.5);
+                        assert!((decoded.longitude - original.longitude).abs() * factor <= 0.5);
+                    }
+                }
+            }
+
+            #[test]
+            fn longitude_beyond_32_bits() {
+                // 180 * 10^7 * 2 doesn't fit into 32 bits anymore
+                let encoded = encode(vec![Point::new(0., 180.)], 7);
+                assert_eq!(decode(&encoded, 7), vec![Point::new(0., 180.)]);
+                assert_eq!(decode(&encode(vec![Point::new(-45.123456789, 179.999999999)], 9), 9), vec![Point::new(-45.123456789, 179.999999999)]);
+            }
+        }
+
+        mod try_decode {
+            use crate::{Point, DecodeError, try_decode, decode};
+
+            #[test]
+            fn valid() {
+                assert_eq!(try_decode("_p~iF~ps|U_ulLnnqC", 5), Ok(vec![
+                    Point::new(38.5, -120.2),
+                    Point::new(40.7, -120.95)
+                ]));
+                assert_eq!(try_decode("", 5), Ok(vec![]));
+            }
+
+            #[test]
+            fn invalid_character() {
+                assert_eq!(try_decode("_p~iF ~ps|U", 5), Err(DecodeError::InvalidCharacter { offset: 5, character: ' ' }));
+                assert_eq!(try_decode("_p~iFä", 5), Err(DecodeError::InvalidCharacter { offset: 5, character: 'ä' }));
+                assert_eq!(decode("_p~iF~ps|U ", 5), vec![Point::new(38.5, -120.2)]);
+            }
+
+            #[test]
+            fn chunk_out_of_range() {
+                assert_eq!(try_decode("??~~~~~~~~~~~~~?", 5), Err(DecodeError::ChunkOutOfRange { offset: 2 }));
+                assert_eq!(try_decode("??~~~~~~~~~~~~O", 5), Err(DecodeError::ChunkOutOfRange { offset: 2 }));
+            }
+
+            #[test]
+            fn dangling_group() {
+                assert_eq!(try_decode("_p~iF~ps|", 5), Err(DecodeError::DanglingGroup { offset: 5 }));
+            }
+
+            #[test]
+            fn dangling_coordinate() {
+                assert_eq!(try_decode("_p~iF~ps|U_ulL", 5), Err(DecodeError::DanglingCoordinate { offset: 10 }));
+                assert_eq!(try_decode("?", 5), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+            }
+        }
+
+        mod options {
+            use crate::{Point, DecodeError, DecodeOptions, OddTrailingPolicy, AxisOrder, decode_with, encode};
+
+            #[test]
+            fn odd_trailing() {
+                let polyline = "_p~iF~ps|U_ulL";
+                assert_eq!(
+                    decode_with(polyline, &DecodeOptions::new(5)),
+                    Err(DecodeError::DanglingCoordinate { offset: 10 })
+                );
+                assert_eq!(
+                    decode_with(polyline, &DecodeOptions::new(5).odd_trailing(OddTrailingPolicy::DropSilently)),
+                    Ok(vec![Point::new(38.5, -120.2)])
+                );
+                assert_eq!(
+                    decode_with(polyline, &DecodeOptions::new(5).odd_trailing(OddTrailingPolicy::PairWithZero)),
+                    Ok(vec![Point::new(38.5, -120.2), Point::new(40.7, -120.2)])
+                );
+                assert_eq!(
+                    decode_with("_p~iF", &DecodeOptions::new(5).odd_trailing(OddTrailingPolicy::PairWithZero)),
+                    Ok(vec![Point::new(38.5, 0.)])
+                );
+            }
+
+            #[test]
+            #[cfg(feature = "std")]
+            fn max_jump_not_exceeded() {
+                assert_eq!(decode_with("_p~iF~ps|U_ulLnnqC", &DecodeOptions::new(5).max_jump_m(300_000.)), Ok(vec![
+                    Point::new(38.5, -120.2),
+                    Point::new(40.7, -120.95)
+                ]));
+            }
+
+            #[test]
+            #[cfg(feature = "std")]
+            fn max_jump_exceeded() {
+                match decode_with("_p~iF~ps|U_ulLnnqC_mqNvxq`@", &DecodeOptions::new(5).max_jump_m(300_000.)) {
+                    Err(DecodeError::JumpTooLarge { index, .. }) => assert_eq!(index, 2),
+                    result => panic!("unexpected result {:?}", result)
+                }
+            }
+
+            #[test]
+            fn out_of_range() {
+                let polyline = encode(vec![Point::new(38.5, 10.), Point::new(38.5, 239.8)], 5);
+                assert!(decode_with(&polyline, &DecodeOptions::new(5)).is_ok());
+                assert_eq!(decode_with(&polyline, &DecodeOptions::new(5).reject_out_of_range()), Err(DecodeError::OutOfRange {
+                    index: 1,
+                    latitude: 38.5,
+                    longitude: 239.8
+                }));
+            }
+
+            #[test]
+            fn longitude_first() {
+                let polyline = encode(vec![Point::new(150., 40.), Point::new(-170.5, -30.)], 5);
+                let options = DecodeOptions::new(5).axis_order(AxisOrder::LngLat).reject_out_of_range();
+                assert_eq!(decode_with(&polyline, &options), Ok(vec![Point::new(40., 150.), Point::new(-30., -170.5)]));
+            }
+        }
+
+        mod into {
+            use std::prelude::v1::*;
+            use crate::{Point, DecodeError, decode_into, try_decode_into};
+
+            #[test]
+            fn appends() {
+                let mut points = vec![Point::new(1., 2.)];
+                decode_into("_p~iF~ps|U_ulL", 5, &mut points);
+                assert_eq!(points, vec![Point::new(1., 2.), Point::new(38.5, -120.2)]);
+                assert_eq!(try_decode_into("_ulLnnqC", 5, &mut points), Ok(()));
+                assert_eq!(points.len(), 3);
+            }
+
+            #[test]
+            fn nothing_appended_on_error() {
+                let mut points: Vec<Point> = Vec::new();
+                assert_eq!(try_decode_into("_p~iF~ps|U_ulL", 5, &mut points), Err(DecodeError::DanglingCoordinate { offset: 10 }));
+                assert_eq!(try_decode_into("_p~iF~ps|U!", 5, &mut points), Err(DecodeError::InvalidCharacter { offset: 10, character: '!' }));
+                assert!(points.is_empty());
+            }
+        }
+
+        mod user_types {
+            use crate::{Point, DecodeError, FromCoordinate, decode, decode_as, try_decode_as};
+
+            #[derive(PartialEq, Debug)]
+            struct LonLat(f64, f64);
+
+            impl FromCoordinate for LonLat {
+                fn from_coordinate(lat: f64, lng: f64) -> LonLat {
+                    return LonLat(lng, lat);
+                }
+            }
+
+            #[test]
+            fn same_as_decode() {
+                let polyline = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+                assert_eq!(decode_as::<Point>(polyline, 5), decode(polyline, 5));
+                assert_eq!(try_decode_as::<LonLat>(polyline, 5).map(|values| values.into_iter().next()), Ok(Some(LonLat(-120.2, 38.5))));
+                assert_eq!(try_decode_as::<LonLat>("_p~iF", 5), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+            }
+        }
+
+        mod cow {
+            use std::borrow::Cow;
+            use crate::{Point, decode_cow};
+
+            #[test]
+            fn empty_is_borrowed() {
+                assert!(matches!(decode_cow("", 5), Cow::Borrowed(_)));
+                assert!(matches!(decode_cow("_p~iF", 5), Cow::Borrowed(_)));
+            }
+
+            #[test]
+            fn owned() {
+                assert_eq!(decode_cow("_p~iF~ps|U", 5).into_owned(), vec![Point::new(38.5, -120.2)]);
+                assert_eq!(decode_cow("_p~iF~ps|U_ulLnnqC", 5).into_owned(), vec![
+                    Point::new(38.5, -120.2),
+                    Point::new(40.7, -120.95)
+                ]);
+            }
+        }
+
+        mod bytes {
+            use crate::{Point, DecodeError, decode, try_decode, decode_bytes, try_decode_bytes};
+
+            #[test]
+            fn same_as_str() {
+                for polyline in ["", "_p~iF~ps|U_ulLnnqC_mqNvxq`@", "_p~iF~ps|U_ulL", "_p~iF~ps|U_ul", "_p~iF~ps|U_ulL!nnqC", "_p~iF~ps|Uä"] {
+                    assert_eq!(decode_bytes(polyline.as_bytes(), 5), decode(polyline, 5));
+                    assert_eq!(try_decode_bytes(polyline.as_bytes(), 5), try_decode(polyline, 5));
+                }
+            }
+
+            #[test]
+            fn invalid_utf8() {
+                assert_eq!(decode_bytes(b"_p~iF~ps|U_ulL\xffnnqC", 5), vec![Point::new(38.5, -120.2)]);
+                for (polyline, offset) in [(&b"_p~iF~ps|U_ulL\xffnnqC"[..], 14), (b"_p~iF~ps|U_ul\xff", 13), (b"\xc3", 0)] {
+                    assert_eq!(try_decode_bytes(polyline, 5), Err(DecodeError::InvalidCharacter { offset, character: char::REPLACEMENT_CHARACTER }));
+                }
+                assert_eq!(try_decode_bytes(b"_p~iF!~ps|U\xff", 5), Err(DecodeError::InvalidCharacter { offset: 5, character: '!' }));
+            }
+        }
+    }
+
+    // arbitrary input must never panic, see also the fuzz target in fuzz/
+    mod property_tests {
+        use std::prelude::v1::*;
+        use proptest::prelude::*;
+        use crate::{Point, Decoder, decode, try_decode, decode_bytes, try_decode_bytes, decode_iter, decode_multi, encode, validate, concat_encoded, reverse_encoded,
+                    decode_to_wkt, decode_to_kml, splice_encoded, remove_range_encoded};
+
+        proptest! {
+            #[test]
+            fn any_string(polyline in "[?-~]{0,64}|\\PC{0,16}", precision in 0_u32..40) {
+                let points = decode(&polyline, precision);
+                if let Ok(strict) = try_decode(&polyline, precision) {
+                    prop_assert_eq!(strict, points.clone());
+                }
+                prop_assert_eq!(decode_iter(&polyline, precision).count(), points.len());
+
+                let mut decoder = Decoder::new(precision);
+                decoder.feed(&polyline);
+                let _ = decoder.finish();
+                let _ = decode_multi(&polyline, precision);
+                let _ = validate(&polyline);
+                let _ = concat_encoded(&polyline, &polyline, precision);
+                let _ = reverse_encoded(&polyline, precision);
+
+                let end = polyline.len() / 4;
+                let _ = splice_encoded(&polyline, end / 2..end, [Point::new(1., 2.)], precision);
+                let _ = remove_range_encoded(&polyline, precision, end..end / 3);
+            }
+
+            #[test]
+            fn any_bytes(polyline in proptest::collection::vec(any::<u8>(), 0..64), precision in any::<u32>()) {
+                let points = decode_bytes(&polyline, precision);
+                if let Ok(strict) = try_decode_bytes(&polyline, precision) {
+                    prop_assert_eq!(strict, points.clone());
+                }
+                let _ = crate::bytes::from_bytes(&polyline);
+            }
+
+            #[test]
+            fn any_precision(polyline in "[?-~]{0,32}", precision in any::<u32>()) {
+                let points = decode(&polyline, precision);
+                prop_assert!(points.iter().all(|point| point.latitude.is_finite() && point.longitude.is_finite()));
+
+                #[cfg(feature = "std")]
+                let _ = crate::nearest_polyline(vec![&polyline, &polyline], precision, &Point::new(1., 2.));
+                let _ = decode_to_wkt(&polyline, precision);
+                let _ = decode_to_kml(&polyline, precision);
+            }
+
+            #[test]
+            fn round_trip(coordinates in proptest::collection::vec((-90_f64..=90., -180_f64..=180.), 0..32), precision in 0_u32..=9) {
+                let points: Vec<Point> = coordinates.iter().map(|(latitude, longitude)| Point::new(*latitude, *longitude)).collect();
+                let decoded = decode(&encode(&points, precision), precision);
+                prop_assert_eq!(decoded.len(), points.len());
+
+                // every delta is rounded on its own, so the error may add up along the line
+                let step = 0.5 / crate::precision::scale(precision);
+                for (index, (point, decoded)) in points.iter().zip(decoded.iter()).enumerate() {
+                    let tolerance = step * (index + 1) as f64 + 1e-9;
+                    prop_assert!((point.latitude - decoded.latitude).abs() <= tolerance);
+                    prop_assert!((point.longitude - decoded.longitude).abs() <= tolerance);
+                }
+            }
+        }
+    }
+
+    // one smoke test per optional feature, run them with `--features full`
+    mod feature_tests {
+        #[allow(unused_imports)]
+        use crate::{Point, decode};
+
+        #[allow(dead_code)]
+        const POLYLINE: &str = "_p~iF~ps|U_ulLnnqC";
+
+        #[test]
+        #[cfg(feature = "breaks")]
+        fn breaks() {
+            assert_eq!(crate::breaks::decode_with_breaks(POLYLINE, 5), vec![decode(POLYLINE, 5)]);
+        }
+
+        #[test]
+        #[cfg(feature = "context")]
+        fn context() {
+            use crate::context::PolylineContext;
+            assert!(crate::try_decode(POLYLINE, 5).with_polyline_context(POLYLINE, 5).is_ok());
+        }
+
+        #[test]
+        #[cfg(feature = "ffi")]
+        fn ffi() {
+            let polyline = std::ffi::CString::new(POLYLINE).unwrap();
+            let mut length = 0;
+            unsafe {
+                let coordinates = crate::ffi::polyline_decode(polyline.as_ptr(), 5, &mut length);
+                assert_eq!(length, 4);
+                crate::ffi::polyline_free_coordinates(coordinates, length);
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "geo")]
+        fn geo() {
+            assert_eq!(crate::geo::encode_linestring(&crate::geo::decode_to_linestring(POLYLINE, 5), 5), POLYLINE);
+        }
+
+        #[test]
+        #[cfg(feature = "geodesic")]
+        fn geodesic() {
+            let points = decode(POLYLINE, 5);
+            assert!(crate::geodesic::distance(&points[0], &points[1]).unwrap() > 200_000.);
+        }
+
+        #[test]
+        #[cfg(feature = "gpx")]
+        fn gpx() {
+            let points = decode(POLYLINE, 5);
+            assert_eq!(crate::gpx::read_track(&crate::gpx::to_gpx(&points, 5)), Ok(points));
+        }
+
+        #[test]
+        #[cfg(feature = "h3")]
+        fn h3() {
+            assert!(!crate::h3::h3_cells_along(&decode(POLYLINE, 5), h3o::Resolution::Two).is_empty());
+        }
+
+        #[test]
+        #[cfg(feature = "prost")]
+        fn prost() {
+            let points = decode(POLYLINE, 5);
+            assert_eq!(crate::proto::LineString::from_points(&points, 5).unwrap().to_points(), points);
+        }
+
+        #[test]
+        #[cfg(feature = "rayon")]
+        fn rayon() {
+            assert_eq!(crate::parallel::par_decode_batch(&[POLYLINE], 5), vec![Ok(decode(POLYLINE, 5))]);
+        }
+
+        #[test]
+        #[cfg(feature = "s2")]
+        fn s2() {
+            assert!(!crate::s2::s2_covering(&decode(POLYLINE, 5), 4, 10, 8).is_empty());
+        }
+
+        #[test]
+        #[cfg(feature = "serde")]
+        fn serde() {
+            let json = serde_json::to_string(&Point::new(38.5, -120.2)).unwrap();
+            assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), Point::new(38.5, -120.2));
+        }
+
+        #[test]
+        #[cfg(feature = "wasm")]
+        fn wasm() {
+            assert_eq!(crate::wasm::decode_flat(POLYLINE, 5), Ok(vec![38.5, -120.2, 40.7, -120.95]));
+        }
     }
 }
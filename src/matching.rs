@@ -0,0 +1,260 @@
+use core::convert::Infallible;
+
+use crate::{Point, TimedPoint, decode_timed, encode, length, slice_between};
+use crate::geodesy::{distance, distance_to_polyline, closest_on_segment};
+
+/// Result of map matching a trace, see [`MapMatcher`].
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct MatchedPath {
+    /// geometry of the matched path along the road network
+    pub points: Vec<Point>,
+    /// position on the network of every point of the trace, `None` if the matcher couldn't
+    /// match the point (e.g. an outlier)
+    pub snapped: Vec<Option<Point>>
+}
+
+impl MatchedPath {
+    /// Encodes the geometry of the matched path, see [`encode`].
+    pub fn encode(&self, precision: u32) -> String {
+        return encode(&self.points, precision);
+    }
+}
+
+/// Snaps GPS traces to a road network.
+///
+/// Implement it for a client of a map matching service, e.g. the `match` endpoints of OSRM or
+/// Valhalla, to use the service with the polyline handling of this crate, see
+/// [`match_encoded`]. [`SnapToRoute`] is an implementation for traces along a known route.
+pub trait MapMatcher {
+    /// error of the matcher, e.g. a failed request
+    type Error;
+
+    /// Matches the points of a trace, in order of their timestamps.
+    fn match_trace(&self, trace: &[TimedPoint]) -> Result<MatchedPath, Self::Error>;
+}
+
+impl<M> MapMatcher for &M where M: MapMatcher + ?Sized {
+    type Error = M::Error;
+
+    fn match_trace(&self, trace: &[TimedPoint]) -> Result<MatchedPath, M::Error> {
+        return (**self).match_trace(trace);
+    }
+}
+
+/// Decodes a trace encoded with [`encode_timed`](crate::encode_timed), matches it and encodes
+/// the matched path.
+///
+/// `matcher`: the map matcher
+///
+/// `trace`: polyline string of the trace with elevation and time
+///
+/// `precision`: precision of latitude and longitude, also used for the matched path
+///
+/// `elevation_precision`: number of decimals of the elevation of the trace
+pub fn match_encoded<M>(matcher: &M, trace: &str, precision: u32, elevation_precision: u32) -> Result<String, M::Error>
+    where M: MapMatcher + ?Sized {
+    let points = decode_timed(trace, precision, elevation_precision);
+    return Ok(matcher.match_trace(&points)?.encode(precision));
+}
+
+/// Matches traces to a single known route, e.g. the planned route of a navigation session.
+///
+/// Every point of the trace snaps to the closest point of the route; points farther away than
+/// `max_distance_m` stay unmatched. The matched path is the part of the route between the
+/// first and the last snapped position.
+///
+/// ```
+/// use polyline_rust::{MapMatcher, Point, SnapToRoute, TimedPoint, encode};
+///
+/// let matcher = SnapToRoute::new(vec![Point::new(0., 0.), Point::new(0., 1.)], 50.);
+/// let trace = vec![TimedPoint::new(0.0001, 0.2, 0., 0), TimedPoint::new(-0.0001, 0.3, 0., 10)];
+/// let matched = matcher.match_trace(&trace).unwrap();
+/// assert_eq!(matched.encode(5), encode(vec![Point::new(0., 0.2), Point::new(0., 0.3)], 5));
+/// ```
+#[derive(PartialEq, Debug, Clone)]
+pub struct SnapToRoute {
+    route: Vec<Point>,
+    max_distance_m: f64
+}
+
+impl SnapToRoute {
+    /// Creates a new `SnapToRoute`.
+    ///
+    /// `route`: points of the route
+    ///
+    /// `max_distance_m`: largest distance in meters of a matched point from the route
+    pub fn new(route: Vec<Point>, max_distance_m: f64) -> SnapToRoute {
+        return SnapToRoute {
+            route,
+            max_distance_m
+        };
+    }
+
+    /// Closest point of the route to `point` and its distance in meters from the start of
+    /// the route.
+    fn snap(&self, point: &Point) -> Option<(Point, f64)> {
+        if self.route.len() == 1 {
+            return Some((self.route[0], 0.)).filter(|(snapped, _)| distance(point, snapped) <= self.max_distance_m);
+        }
+
+        let mut best: Option<(Point, f64, f64)> = None;
+        let mut start = 0.;
+        for segment in self.route.windows(2) {
+            let segment_length = distance(&segment[0], &segment[1]);
+            let (closest, t) = closest_on_segment(point, &segment[0], &segment[1]);
+            let d = distance(point, &closest);
+            if d <= self.max_distance_m && best.as_ref().is_none_or(|(_, best_distance, _)| d < *best_distance) {
+                best = Some((closest, d, start + t * segment_length));
+            }
+            start += segment_length;
+        }
+
+        return best.map(|(closest, _, measure)| (closest, measure));
+    }
+}
+
+impl MapMatcher for SnapToRoute {
+    type Error = Infallible;
+
+    fn match_trace(&self, trace: &[TimedPoint]) -> Result<MatchedPath, Infallible> {
+        let mut snapped: Vec<Option<Point>> = Vec::with_capacity(trace.len());
+        let mut from = f64::INFINITY;
+        let mut to = f64::NEG_INFINITY;
+        for point in trace {
+            let position = self.snap(&Point::new(point.latitude, point.longitude));
+            if let Some((_, measure)) = position {
+                from = from.min(measure);
+                to = to.max(measure);
+            }
+            snapped.push(position.map(|(point, _)| point));
+        }
+
+        return Ok(MatchedPath {
+            points: slice_between(&self.route, from, to),
+            snapped
+        });
+    }
+}
+
+/// Quality of a map matching result, see [`matching_metrics`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct MatchingMetrics {
+    /// mean distance in meters of the raw points from the matched path
+    pub mean_offset_m: f64,
+    /// 95th percentile of the distances in meters of the raw points from the matched path
+    pub p95_offset_m: f64,
+    /// length of the matched path divided by the length of the raw trace
+    pub length_ratio: f64
+}
+
+/// Scores how well a matched path fits the raw trace it was matched from.
+///
+/// Large offsets hint at points snapped to the wrong road, a length ratio far from 1 at
+/// detours or shortcuts of the matched path (noisy traces are usually a bit longer than the
+/// road, so ratios slightly below 1 are normal).
+///
+/// `raw`: points of the trace as recorded
+///
+/// `matched`: points of the matched path, e.g. [`MatchedPath::points`]
+///
+/// The offsets are `f64::INFINITY` if only the matched path is empty and 0 if the raw trace
+/// is empty. The length ratio is `f64::INFINITY` if only the raw trace has no length and 1
+/// if neither has a length.
+///
+/// ```
+/// use polyline_rust::{Point, matching_metrics};
+///
+/// let raw = vec![Point::new(0.0001, 0.), Point::new(-0.0001, 0.001), Point::new(0.0001, 0.002)];
+/// let matched = vec![Point::new(0., 0.), Point::new(0., 0.002)];
+/// let metrics = matching_metrics(&raw, &matched);
+/// assert!((metrics.mean_offset_m - 11.1).abs() < 0.1);
+/// assert!(metrics.length_ratio < 1.);
+/// ```
+pub fn matching_metrics(raw: &[Point], matched: &[Point]) -> MatchingMetrics {
+    let mut offsets: Vec<f64> = raw.iter().map(|point| distance_to_polyline(point, matched)).collect();
+    offsets.sort_by(|a, b| a.total_cmp(b));
+
+    let (mean_offset_m, p95_offset_m) = if offsets.is_empty() {
+        (0., 0.)
+    } else {
+        // nearest rank, the smallest offset not exceeded by 95 % of the points
+        let rank = (offsets.len() * 95).div_ceil(100);
+        (offsets.iter().sum::<f64>() / offsets.len() as f64, offsets[rank - 1])
+    };
+
+    let (raw_length, matched_length) = (length(raw), length(matched));
+    let length_ratio = if raw_length == 0. {
+        if matched_length == 0. { 1. } else { f64::INFINITY }
+    } else {
+        matched_length / raw_length
+    };
+
+    return MatchingMetrics {
+        mean_offset_m,
+        p95_offset_m,
+        length_ratio
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MapMatcher, MatchedPath, MatchingMetrics, Point, SnapToRoute, TimedPoint, encode, encode_timed, match_encoded, matching_metrics};
+
+    fn trace() -> Vec<TimedPoint> {
+        return vec![
+            TimedPoint::new(0.0001, 0.5, 0., 0),
+            TimedPoint::new(1., 0., 0., 5),
+            TimedPoint::new(0.5, 1.0001, 0., 10)
+        ];
+    }
+
+    #[test]
+    fn snap_to_route() {
+        let matcher = SnapToRoute::new(vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.)], 20.);
+        let matched = matcher.match_trace(&trace()).unwrap();
+        assert_eq!(matched.snapped, vec![Some(Point::new(0., 0.5)), None, Some(Point::new(0.5, 1.))]);
+        assert_eq!(matched.points[..2], [Point::new(0., 0.5), Point::new(0., 1.)]);
+        assert!(matched.points.len() == 3 && (matched.points[2].latitude - 0.5).abs() < 1e-9);
+
+        let matched = matcher.match_trace(&trace()[1..2]).unwrap();
+        assert_eq!(matched, MatchedPath { points: vec![], snapped: vec![None] });
+    }
+
+    /// Matcher of a service that is down.
+    struct Unavailable;
+
+    impl MapMatcher for Unavailable {
+        type Error = &'static str;
+
+        fn match_trace(&self, _: &[TimedPoint]) -> Result<MatchedPath, &'static str> {
+            return Err("service unavailable");
+        }
+    }
+
+    #[test]
+    fn pluggable() {
+        let polyline = encode_timed(trace(), 5, 0);
+        let matcher = SnapToRoute::new(vec![Point::new(0., 0.), Point::new(0., 1.)], 20.);
+        assert_eq!(match_encoded(&matcher, &polyline, 5, 0), Ok(encode(vec![Point::new(0., 0.5)], 5)));
+        assert_eq!(match_encoded(&Unavailable, &polyline, 5, 0), Err("service unavailable"));
+
+        let matchers: Vec<Box<dyn MapMatcher<Error = &'static str>>> = vec![Box::new(Unavailable)];
+        assert!(match_encoded(matchers[0].as_ref(), &polyline, 5, 0).is_err());
+    }
+
+    #[test]
+    fn metrics() {
+        let matched = vec![Point::new(0., 0.), Point::new(0., 0.1)];
+        // 20 points 0 to 19 m off the road
+        let raw: Vec<Point> = (0..20).map(|i| Point::new(i as f64 / 111_195., i as f64 * 0.005)).collect();
+        let metrics = matching_metrics(&raw, &matched);
+        assert!((metrics.mean_offset_m - 9.5).abs() < 0.01, "{:?}", metrics);
+        assert!((metrics.p95_offset_m - 18.).abs() < 0.01, "{:?}", metrics);
+        assert!(metrics.length_ratio > 1.);
+
+        assert_eq!(matching_metrics(&matched, &matched), MatchingMetrics { mean_offset_m: 0., p95_offset_m: 0., length_ratio: 1. });
+        assert_eq!(matching_metrics(&[], &[]), MatchingMetrics { mean_offset_m: 0., p95_offset_m: 0., length_ratio: 1. });
+        assert_eq!(matching_metrics(&matched, &[]).mean_offset_m, f64::INFINITY);
+        assert_eq!(matching_metrics(&matched[..1], &matched).length_ratio, f64::INFINITY);
+    }
+}
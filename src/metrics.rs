@@ -0,0 +1,160 @@
+use crate::Point;
+use crate::geodesy::{distance, distance_to_polyline};
+
+/// Earth models for distances and lengths, see [`length_with`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum DistanceModel {
+    /// Great-circle distance on a sphere (haversine), fast and off by up to 0.5%
+    #[default]
+    Haversine,
+    /// Geodesic distance on the WGS84 ellipsoid (Vincenty), accurate to about half a
+    /// millimeter; for surveying, see the [`geodesic`](crate::geodesic) module
+    #[cfg(feature = "geodesic")]
+    Vincenty
+}
+
+impl DistanceModel {
+    /// Distance between `a` and `b` in meters.
+    ///
+    /// Vincenty's iteration doesn't converge for nearly antipodal points, those fall back to
+    /// the haversine distance.
+    pub fn distance(&self, a: &Point, b: &Point) -> f64 {
+        return match self {
+            DistanceModel::Haversine => distance(a, b),
+            #[cfg(feature = "geodesic")]
+            DistanceModel::Vincenty => crate::geodesic::distance(a, b).unwrap_or_else(|| distance(a, b))
+        };
+    }
+}
+
+/// Length of the polyline in meters.
+pub fn length(points: &[Point]) -> f64 {
+    return length_with(points, DistanceModel::Haversine);
+}
+
+/// Length of the polyline in meters with the given earth model.
+///
+/// `points`: points of the polyline
+///
+/// `model`: how distances between consecutive points are measured
+pub fn length_with(points: &[Point], model: DistanceModel) -> f64 {
+    let mut total = 0.;
+    for segment in points.windows(2) {
+        total += model.distance(&segment[0], &segment[1]);
+    }
+
+    return total;
+}
+
+/// Hausdorff distance between two polylines in meters.
+///
+/// The largest distance from any vertex of one polyline to the closest point on the other one,
+/// checked in both directions. Returns `0` if both are empty and `f64::INFINITY` if only one is.
+pub fn hausdorff(a: &[Point], b: &[Point]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.;
+    }
+
+    return directed_hausdorff(a, b).max(directed_hausdorff(b, a));
+}
+
+/// Discrete Fréchet distance between two polylines in meters.
+///
+/// Also known as the "dog leash" distance: unlike the Hausdorff distance it respects the
+/// direction of travel, so a route driven in reverse is far from the original.
+/// Returns `0` if both are empty and `f64::INFINITY` if only one is.
+pub fn frechet(a: &[Point], b: &[Point]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.;
+    }
+    if a.is_empty() || b.is_empty() {
+        return f64::INFINITY;
+    }
+
+    // only the previous row of the coupling matrix is needed
+    let mut previous: Vec<f64> = vec![0.; b.len()];
+    let mut current: Vec<f64> = vec![0.; b.len()];
+
+    for (i, point_a) in a.iter().enumerate() {
+        for (j, point_b) in b.iter().enumerate() {
+            let d = distance(point_a, point_b);
+            current[j] = match (i, j) {
+                (0, 0) => d,
+                (0, _) => current[j-1].max(d),
+                (_, 0) => previous[0].max(d),
+                _ => previous[j].min(previous[j-1]).min(current[j-1]).max(d)
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    return previous[b.len()-1];
+}
+
+fn directed_hausdorff(from: &[Point], to: &[Point]) -> f64 {
+    let mut max: f64 = 0.;
+    for point in from.iter() {
+        max = max.max(distance_to_polyline(point, to));
+    }
+
+    return max;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+    use crate::metrics::{length, length_with, hausdorff, frechet, DistanceModel};
+
+    #[test]
+    fn length_of_empty_and_single_point() {
+        assert_eq!(length(&[]), 0.);
+        assert_eq!(length(&[Point::new(1., 1.)]), 0.);
+    }
+
+    #[test]
+    fn length_along_equator() {
+        let l = length(&[Point::new(0., 0.), Point::new(0., 1.), Point::new(0., 2.)]);
+        assert!((l - 222_390.16).abs() < 0.1);
+    }
+
+    #[test]
+    fn identical_lines() {
+        let a = vec![Point::new(48.2, 16.37), Point::new(48.21, 16.38)];
+        assert_eq!(hausdorff(&a, &a), 0.);
+        assert_eq!(frechet(&a, &a), 0.);
+    }
+
+    #[test]
+    fn reversed_line() {
+        let a = vec![Point::new(0., 0.), Point::new(0., 1.)];
+        let b = vec![Point::new(0., 1.), Point::new(0., 0.)];
+        assert_eq!(hausdorff(&a, &b), 0.);
+        assert!((frechet(&a, &b) - 111_195.08).abs() < 0.1);
+    }
+
+    #[test]
+    fn empty_line() {
+        assert_eq!(hausdorff(&[], &[]), 0.);
+        assert_eq!(hausdorff(&[Point::new(0., 0.)], &[]), f64::INFINITY);
+        assert_eq!(frechet(&[], &[Point::new(0., 0.)]), f64::INFINITY);
+    }
+
+    #[test]
+    fn distance_models() {
+        let points = [Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.)];
+        assert_eq!(length_with(&points, DistanceModel::Haversine), length(&points));
+        assert_eq!(DistanceModel::default(), DistanceModel::Haversine);
+    }
+
+    #[test]
+    #[cfg(feature = "geodesic")]
+    fn vincenty_length() {
+        // one degree of longitude on the equator and of latitude from there on WGS84
+        let points = [Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.)];
+        let ellipsoidal = length_with(&points, DistanceModel::Vincenty);
+        assert!((ellipsoidal - (111_319.491 + 110_574.389)).abs() < 0.01, "{}", ellipsoidal);
+        // nearly antipodal points fall back to the sphere
+        let antipodal = [Point::new(0., 0.), Point::new(0.5, 179.7)];
+        assert_eq!(length_with(&antipodal, DistanceModel::Vincenty), length(&antipodal));
+    }
+}
@@ -0,0 +1,125 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, Coordinate, DecodeError, encode_to, decode, try_decode};
+
+/// Separator between the polylines of [`encode_multi`].
+///
+/// Polylines only consist of the characters `?` to `~`, so the comma can't be mistaken for
+/// a part of a polyline.
+pub const MULTI_SEPARATOR: char = ',';
+
+/// Encodes several polylines into a single string, e.g. a MultiLineString or a route with
+/// disconnected legs.
+///
+/// Every polyline is encoded on its own, starting from `0, 0`, and the polylines are joined
+/// with [`MULTI_SEPARATOR`]. No polylines and a single empty polyline both give an empty
+/// string, which [`decode_multi`] reads as no polylines.
+///
+/// `lines`: the polylines, e.g. a `Vec<Vec<Point>>`
+///
+/// `precision`: usually 5 or 6, see [`encode`](crate::encode)
+///
+/// ```
+/// use polyline_rust::{Point, encode_multi, decode_multi};
+///
+/// let legs = vec![
+///     vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)],
+///     vec![Point::new(43.252, -126.453)]
+/// ];
+/// let encoded = encode_multi(&legs, 5);
+/// assert_eq!(encoded, "_p~iF~ps|U_ulLnnqC,_t~fGfzxbW");
+/// assert_eq!(decode_multi(&encoded, 5), legs);
+/// ```
+pub fn encode_multi<I>(lines: I, precision: u32) -> String
+    where I: IntoIterator, I::Item: IntoIterator, <I::Item as IntoIterator>::Item: Coordinate {
+    let mut encoded = String::new();
+    for (i, line) in lines.into_iter().enumerate() {
+        if i > 0 {
+            encoded.push(MULTI_SEPARATOR);
+        }
+        // writing into a `String` never fails
+        let _ = encode_to(line, precision, &mut encoded);
+    }
+
+    return encoded;
+}
+
+/// Decodes polylines joined by [`encode_multi`].
+///
+/// Every polyline is decoded like [`decode`], so malformed polylines are cut off or empty
+/// without affecting the others.
+///
+/// `polylines`: polylines separated by [`MULTI_SEPARATOR`]
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+pub fn decode_multi(polylines: &str, precision: u32) -> Vec<Vec<Point>> {
+    if polylines.is_empty() {
+        return Vec::new();
+    }
+
+    return polylines.split(MULTI_SEPARATOR)
+        .map(|polyline| decode(polyline, precision))
+        .collect();
+}
+
+/// Decodes polylines joined by [`encode_multi`] and fails on malformed input, see [`try_decode`].
+///
+/// The offsets of the errors are counted from the start of `polylines`.
+///
+/// `polylines`: polylines separated by [`MULTI_SEPARATOR`]
+///
+/// `precision`: usually 5 or 6, see [`decode`]
+pub fn try_decode_multi(polylines: &str, precision: u32) -> Result<Vec<Vec<Point>>, DecodeError> {
+    let mut lines: Vec<Vec<Point>> = Vec::new();
+    if polylines.is_empty() {
+        return Ok(lines);
+    }
+
+    let mut start = 0;
+    for polyline in polylines.split(MULTI_SEPARATOR) {
+        let points = try_decode(polyline, precision)
+            .map_err(|error| shift(error, start))?;
+        lines.push(points);
+        start += polyline.len() + MULTI_SEPARATOR.len_utf8();
+    }
+
+    return Ok(lines);
+}
+
+/// Moves the offset of an error in a single polyline by the start of the polyline.
+fn shift(error: DecodeError, start: usize) -> DecodeError {
+    return match error {
+        DecodeError::InvalidCharacter { offset, character } => DecodeError::InvalidCharacter { offset: start + offset, character },
+        DecodeError::ChunkOutOfRange { offset } => DecodeError::ChunkOutOfRange { offset: start + offset },
+        DecodeError::DanglingGroup { offset } => DecodeError::DanglingGroup { offset: start + offset },
+        DecodeError::DanglingCoordinate { offset } => DecodeError::DanglingCoordinate { offset: start + offset },
+        error => error
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, DecodeError, encode_multi, decode_multi, try_decode_multi};
+
+    #[test]
+    fn round_trip() {
+        let lines: Vec<Vec<Point>> = vec![vec![Point::new(38.5, -120.2)], vec![], vec![Point::new(-0.00001, 0.00001)]];
+        for precision in [5, 6] {
+            let encoded = encode_multi(&lines, precision);
+            assert_eq!(decode_multi(&encoded, precision), lines);
+            assert_eq!(try_decode_multi(&encoded, precision), Ok(lines.clone()));
+        }
+
+        assert_eq!(encode_multi(Vec::<Vec<Point>>::new(), 5), "");
+        assert_eq!(decode_multi("", 5), Vec::<Vec<Point>>::new());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(decode_multi("_p~iF~ps|U,_p~iF~ps|U_", 5).len(), 2);
+        assert_eq!(try_decode_multi("_p~iF~ps|U,_p~iF~ps|U_", 5), Err(DecodeError::DanglingGroup { offset: 21 }));
+        assert_eq!(try_decode_multi("_p~iF~ps|U,_p~iF!ps|U", 5), Err(DecodeError::InvalidCharacter { offset: 16, character: '!' }));
+    }
+}
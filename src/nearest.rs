@@ -0,0 +1,158 @@
+use crate::{Point, decode};
+use crate::bounds::decode_bounds;
+use crate::geodesy::{distance, closest_on_segment};
+
+/// Finds the polyline closest to `target` in a batch of encoded polylines.
+///
+/// The bounding box of every polyline is computed without decoding it first, polylines whose
+/// bounding box is farther away than the best match so far are skipped. Only the remaining
+/// candidates are decoded, one at a time.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with
+///
+/// `target`: the point to look for, e.g. the current position of a user
+///
+/// Returns the index of the closest polyline, the distance in meters and the closest point on
+/// that polyline, or `None` if all polylines are empty.
+pub fn nearest_polyline<I, S>(polylines: I, precision: u32, target: &Point) -> Option<(usize, f64, Point)>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    let mut best: Option<(usize, f64, Point)> = None;
+
+    for (index, polyline) in polylines.into_iter().enumerate() {
+        let polyline = polyline.as_ref();
+        // malformed polylines have no bounding box, they are decoded up to the error like with `decode`
+        let bounds = match decode_bounds(polyline, precision) {
+            Ok(Some(bounds)) => Some(bounds),
+            Ok(None) => continue,
+            Err(_) => None
+        };
+
+        if let (Some(bounds), Some((_, best_distance, _))) = (bounds, best) {
+            if bounds.distance_to(target) >= best_distance {
+                continue;
+            }
+        }
+
+        let points = decode(polyline, precision);
+        if let Some((snapped, snapped_distance, _)) = nearest_point(&points, target) {
+            let is_closer = match best {
+                Some((_, best_distance, _)) => snapped_distance < best_distance,
+                None => true
+            };
+            if is_closer {
+                best = Some((index, snapped_distance, snapped));
+            }
+        }
+    }
+
+    return best;
+}
+
+/// Snaps `target` to the polyline, e.g. the location of a user to a route.
+///
+/// Every segment is projected onto a local plane around `target`; [`nearest_polyline`]
+/// searches many encoded polylines.
+///
+/// `points`: points of the polyline
+///
+/// `target`: the point to snap
+///
+/// Returns the closest point on the polyline, its distance in meters from `target` and the
+/// index of its segment (the segment from point `i` to point `i + 1`), `None` if the polyline
+/// is empty. A polyline of a single point has the segment 0.
+///
+/// ```
+/// use polyline_rust::{Point, nearest_point};
+///
+/// let route = vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.)];
+/// let (snapped, distance, segment) = nearest_point(&route, &Point::new(0.5, 1.001)).unwrap();
+/// assert_eq!(segment, 1);
+/// assert!((snapped.latitude - 0.5).abs() < 1e-9 && (distance - 111.2).abs() < 0.1);
+/// ```
+pub fn nearest_point(points: &[Point], target: &Point) -> Option<(Point, f64, usize)> {
+    if points.is_empty() {
+        return None;
+    }
+    if points.len() == 1 {
+        let point = Point::new(points[0].latitude, points[0].longitude);
+        return Some((point, distance(target, &points[0]), 0));
+    }
+
+    let mut best: Option<(Point, f64, usize)> = None;
+    for (i, segment) in points.windows(2).enumerate() {
+        let (closest, _) = closest_on_segment(target, &segment[0], &segment[1]);
+        let d = distance(target, &closest);
+        let is_closer = match best {
+            Some((_, best_distance, _)) => d < best_distance,
+            None => true
+        };
+        if is_closer {
+            best = Some((closest, d, i));
+        }
+    }
+
+    return best;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, encode, nearest_point, nearest_polyline};
+
+    #[test]
+    fn closest_route() {
+        let routes = vec![
+            encode(vec![Point::new(0., 0.), Point::new(0., 1.)], 5),
+            String::new(),
+            encode(vec![Point::new(0.01, 0.), Point::new(0.01, 1.)], 5),
+            encode(vec![Point::new(10., 10.), Point::new(10., 11.)], 5),
+        ];
+
+        let (index, distance, snapped) = nearest_polyline(&routes, 5, &Point::new(0.008, 0.5)).unwrap();
+        assert_eq!(index, 2);
+        assert!((distance - 222.39).abs() < 0.01);
+        assert!((snapped.latitude - 0.01).abs() < 1e-9);
+        assert!((snapped.longitude - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn across_the_antimeridian() {
+        let routes = vec![
+            encode(vec![Point::new(0.1, 179.9), Point::new(0.1, 179.95)], 5),
+            encode(vec![Point::new(0., 179.5), Point::new(0., -179.5)], 5),
+        ];
+
+        let (index, distance, _) = nearest_polyline(&routes, 5, &Point::new(0., 180.)).unwrap();
+        assert_eq!(index, 1);
+        assert!(distance < 1.);
+    }
+
+    #[test]
+    fn malformed_route() {
+        // decoded up to the dangling latitude like with `decode`
+        let mut malformed = encode(vec![Point::new(0.01, 0.), Point::new(0.01, 1.)], 5);
+        malformed.push_str("_ulL");
+        let routes = vec![encode(vec![Point::new(0., 0.), Point::new(0., 1.)], 5), malformed];
+
+        let (index, _, _) = nearest_polyline(&routes, 5, &Point::new(0.008, 0.5)).unwrap();
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn no_routes() {
+        assert_eq!(nearest_polyline(vec!["", ""], 5, &Point::new(0., 0.)), None);
+    }
+
+    #[test]
+    fn snap_to_polyline() {
+        let route = vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(0., 2.)];
+        let (snapped, distance, segment) = nearest_point(&route, &Point::new(-0.01, 1.5)).unwrap();
+        assert_eq!(segment, 1);
+        assert!(snapped.latitude.abs() < 1e-9 && (snapped.longitude - 1.5).abs() < 1e-9);
+        assert!((distance - 1_111.95).abs() < 0.01);
+
+        assert_eq!(nearest_point(&route[..1], &Point::new(0., 0.)), Some((Point::new(0., 0.), 0., 0)));
+        assert_eq!(nearest_point(&[], &Point::new(0., 0.)), None);
+    }
+}
@@ -0,0 +1,237 @@
+/// Options for [`encode_with`](crate::encode_with).
+///
+/// A builder: start with [`EncodeOptions::new`] and enable what's needed, all adjustments and
+/// checks are disabled by default. New options are added as new methods, so code using
+/// `EncodeOptions` keeps compiling.
+///
+/// ```
+/// use polyline_rust::{Point, EncodeOptions, encode, encode_with};
+///
+/// let options = EncodeOptions::new(5).normalize_longitude();
+/// assert_eq!(encode_with(vec![Point::new(38.5, 239.8)], &options), Ok(encode(vec![Point::new(38.5, -120.2)], 5)));
+/// ```
+#[derive(PartialEq, Debug, Clone)]
+pub struct EncodeOptions {
+    pub(crate) precision: u32,
+    pub(crate) normalize_longitude: bool,
+    pub(crate) axis_order: AxisOrder,
+    pub(crate) validate: bool,
+    pub(crate) dedup: bool
+}
+
+impl EncodeOptions {
+    /// Creates new `EncodeOptions` with the given precision that encode like [`encode`](crate::encode).
+    pub fn new(precision: u32) -> EncodeOptions {
+        return EncodeOptions {
+            precision,
+            normalize_longitude: false,
+            axis_order: AxisOrder::LatLng,
+            validate: false,
+            dedup: false
+        };
+    }
+
+    /// Wraps longitudes into [-180, 180) before encoding, e.g. 190 into -170.
+    ///
+    /// For data from systems that use longitudes from 0 to 360.
+    pub fn normalize_longitude(mut self) -> EncodeOptions {
+        self.normalize_longitude = true;
+        return self;
+    }
+
+    /// Sets which coordinate of a point is written first.
+    pub fn axis_order(mut self, axis_order: AxisOrder) -> EncodeOptions {
+        self.axis_order = axis_order;
+        return self;
+    }
+
+    /// Rejects NaN, infinite and out of range coordinates like [`try_encode`](crate::try_encode).
+    ///
+    /// Longitudes are checked after [`normalize_longitude`](EncodeOptions::normalize_longitude).
+    pub fn validate(mut self) -> EncodeOptions {
+        self.validate = true;
+        return self;
+    }
+
+    /// Skips points that are the same as their predecessor at the precision, e.g. a GPS
+    /// receiver repeating its position while standing still.
+    pub fn dedup(mut self) -> EncodeOptions {
+        self.dedup = true;
+        return self;
+    }
+}
+
+/// Options for [`decode_with`](crate::decode_with).
+///
+/// A builder like [`EncodeOptions`], all checks are disabled by default.
+///
+/// ```
+/// use polyline_rust::{DecodeOptions, decode_with};
+///
+/// let options = DecodeOptions::new(5).max_jump_m(600_000.);
+/// assert!(decode_with("_p~iF~ps|U_ulLnnqC_mqNvxq`@", &options).is_ok());
+/// ```
+#[derive(PartialEq, Debug, Clone)]
+pub struct DecodeOptions {
+    pub(crate) precision: u32,
+    pub(crate) odd_trailing: OddTrailingPolicy,
+    pub(crate) reject_out_of_range: bool,
+    pub(crate) axis_order: AxisOrder,
+    pub(crate) dedup: bool,
+    #[cfg(feature = "std")]
+    pub(crate) max_jump_m: Option<f64>
+}
+
+impl DecodeOptions {
+    /// Creates new `DecodeOptions` with the given precision and all checks disabled.
+    ///
+    /// A latitude without longitude at the end is an error, see [`DecodeOptions::odd_trailing`].
+    pub fn new(precision: u32) -> DecodeOptions {
+        return DecodeOptions {
+            precision,
+            odd_trailing: OddTrailingPolicy::Error,
+            reject_out_of_range: false,
+            axis_order: AxisOrder::LatLng,
+            dedup: false,
+            #[cfg(feature = "std")]
+            max_jump_m: None
+        };
+    }
+
+    /// Sets how a latitude without longitude at the end of the polyline is handled.
+    pub fn odd_trailing(mut self, policy: OddTrailingPolicy) -> DecodeOptions {
+        self.odd_trailing = policy;
+        return self;
+    }
+
+    /// Sets which coordinate of a point comes first in the polyline.
+    pub fn axis_order(mut self, axis_order: AxisOrder) -> DecodeOptions {
+        self.axis_order = axis_order;
+        return self;
+    }
+
+    /// Removes points that are the same as their predecessor.
+    ///
+    /// The checks, e.g. [`max_jump_m`](DecodeOptions::max_jump_m), count the points after
+    /// removing the duplicates.
+    pub fn dedup(mut self) -> DecodeOptions {
+        self.dedup = true;
+        return self;
+    }
+
+    /// Rejects polylines with latitudes outside of [-90, 90] or longitudes outside of
+    /// [-180, 180], e.g. because the producer used longitudes from 0 to 360.
+    pub fn reject_out_of_range(mut self) -> DecodeOptions {
+        self.reject_out_of_range = true;
+        return self;
+    }
+
+    /// Rejects polylines with consecutive points farther apart than `meters`.
+    ///
+    /// Huge jumps usually mean the string got corrupted or was decoded with the wrong precision.
+    #[cfg(feature = "std")]
+    pub fn max_jump_m(mut self, meters: f64) -> DecodeOptions {
+        self.max_jump_m = Some(meters);
+        return self;
+    }
+}
+
+/// Handling of a polyline ending with a latitude without longitude, i.e. an odd number of values.
+///
+/// This usually means the string got truncated somewhere upstream.
+///
+/// ```
+/// use polyline_rust::{Point, DecodeOptions, OddTrailingPolicy, decode_with};
+///
+/// let options = DecodeOptions::new(5).odd_trailing(OddTrailingPolicy::PairWithZero);
+/// assert_eq!(decode_with("_p~iF~ps|U_ulL", &options), Ok(vec![
+///     Point::new(38.5, -120.2),
+///     Point::new(40.7, -120.2)
+/// ]));
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum OddTrailingPolicy {
+    /// Ignores the latitude, like [`decode`](crate::decode).
+    DropSilently,
+    /// Fails with [`DecodeError::DanglingCoordinate`](crate::DecodeError::DanglingCoordinate),
+    /// like [`try_decode`](crate::try_decode).
+    Error,
+    /// Keeps the latitude as last point with a longitude delta of zero, i.e. the longitude of
+    /// the previous point.
+    PairWithZero
+}
+
+/// Order of the coordinates of every point in the polyline.
+///
+/// The polyline algorithm writes the latitude first. Some producers, e.g. GeoJSON centric
+/// pipelines, write the longitude first instead.
+///
+/// ```
+/// use polyline_rust::{Point, AxisOrder, EncodeOptions, DecodeOptions, encode_with, decode_with};
+///
+/// let polyline = encode_with(vec![Point::new(38.5, -120.2)], &EncodeOptions::new(5).axis_order(AxisOrder::LngLat)).unwrap();
+/// assert_eq!(polyline, "~ps|U_p~iF");
+/// assert_eq!(decode_with(&polyline, &DecodeOptions::new(5).axis_order(AxisOrder::LngLat)), Ok(vec![Point::new(38.5, -120.2)]));
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum AxisOrder {
+    /// Latitude first, like [`encode`](crate::encode) and [`decode`](crate::decode).
+    LatLng,
+    /// Longitude first.
+    LngLat
+}
+
+/// Wraps a longitude into [-180, 180).
+pub(crate) fn wrap_longitude(longitude: f64) -> f64 {
+    let mut wrapped = (longitude + 180.) % 360.;
+    if wrapped < 0. {
+        wrapped += 360.;
+    }
+    // adding 360 to a tiny negative remainder rounds to 360
+    if wrapped >= 360. {
+        wrapped -= 360.;
+    }
+
+    return wrapped - 180.;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, ValidationError, EncodeOptions, DecodeOptions, AxisOrder, encode, encode_with, decode_with};
+    use crate::options::wrap_longitude;
+
+    #[test]
+    fn encode_defaults_like_encode() {
+        let track = vec![Point::new(-79.4486385, -179.9832104), Point::new(-79.4486385, -179.9832104), Point::new(0.000004, 12.3)];
+        for precision in [5, 6] {
+            assert_eq!(encode_with(&track, &EncodeOptions::new(precision)), Ok(encode(&track, precision)));
+        }
+    }
+
+    #[test]
+    fn encode_options() {
+        let options = EncodeOptions::new(5).normalize_longitude().validate();
+        assert_eq!(encode_with(vec![Point::new(38.5, 239.8)], &options), Ok(String::from("_p~iF~ps|U")));
+        assert_eq!(encode_with(vec![Point::new(0., 0.), Point::new(-91., 0.)], &options), Err(ValidationError::OutOfRange { index: 1, latitude: -91., longitude: 0. }));
+
+        let options = EncodeOptions::new(5).dedup().axis_order(AxisOrder::LngLat);
+        let track = vec![Point::new(38.5, -120.2), Point::new(38.500004, -120.2), Point::new(38.5, -120.2)];
+        assert_eq!(encode_with(&track, &options), encode_with(&track[..1], &options));
+    }
+
+    #[test]
+    fn decode_dedup() {
+        let polyline = encode(vec![Point::new(38.5, -120.2), Point::new(38.5, -120.2), Point::new(40.7, -120.95)], 5);
+        assert_eq!(decode_with(&polyline, &DecodeOptions::new(5).dedup()), Ok(vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)]));
+    }
+
+    #[test]
+    fn wrapping() {
+        for (longitude, wrapped) in [(190., -170.), (-190., 170.), (180., -180.), (-180., -180.), (359.5, -0.5), (720., 0.), (-120.2, -120.2)] {
+            assert_eq!(wrap_longitude(longitude), wrapped);
+        }
+        assert!(wrap_longitude(-1e-20) < 180.);
+        assert!(wrap_longitude(f64::NAN).is_nan());
+    }
+}
@@ -0,0 +1,107 @@
+//! Multi-threaded batch decoding with [rayon](https://docs.rs/rayon).
+//!
+//! Both functions decode on rayon's global thread pool and give the same results as
+//! [`decode_batch`](crate::decode_batch); they differ in the order of the results:
+//!
+//! * [`par_decode_batch`] returns the results in input order, whatever the scheduling of the
+//!   threads. It needs the whole batch as slice, so rayon can split it into equal halves and
+//!   put the results of each half directly into its place of the output.
+//! * [`par_decode_batch_unordered`] returns `(index, result)` pairs in no particular order.
+//!   It takes any iterator, e.g. lines read from a file or received from a channel, so the
+//!   batch never has to be collected in memory first, and the per-thread results are merged
+//!   without reordering. Pipelines that write results by index, into a database or a
+//!   pre-sized `Vec`, don't need the order anyway.
+//!
+//! The order of the unordered variant depends on the scheduling and differs between runs.
+//!
+//! ```
+//! use polyline_rust::parallel::{par_decode_batch, par_decode_batch_unordered};
+//!
+//! let polylines = vec!["_p~iF~ps|U", "_ulLnnqC", "_mqNvxq`@"];
+//! let ordered = par_decode_batch(&polylines, 5);
+//!
+//! let mut unordered = par_decode_batch_unordered(polylines, 5);
+//! unordered.sort_by_key(|(index, _)| *index);
+//! let unordered: Vec<_> = unordered.into_iter().map(|(_, result)| result).collect();
+//! assert_eq!(unordered, ordered);
+//! ```
+
+use rayon::iter::{IntoParallelRefIterator, ParallelBridge, ParallelIterator};
+
+use crate::{Point, DecodeError, try_decode};
+
+/// Decodes many polylines in parallel, the results are in input order.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with, see [`decode`](crate::decode)
+///
+/// Returns one result per polyline, malformed strings give an error in their place.
+pub fn par_decode_batch<S>(polylines: &[S], precision: u32) -> Vec<Result<Vec<Point>, DecodeError>>
+    where S: AsRef<str> + Sync {
+    return polylines.par_iter()
+        .map(|polyline| try_decode(polyline.as_ref(), precision))
+        .collect();
+}
+
+/// Decodes many polylines in parallel, the results are in no particular order.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with, see [`decode`](crate::decode)
+///
+/// Returns the position of every polyline in the input with its result.
+pub fn par_decode_batch_unordered<I, S>(polylines: I, precision: u32) -> Vec<(usize, Result<Vec<Point>, DecodeError>)>
+    where I: IntoIterator<Item = S>, I::IntoIter: Send, S: AsRef<str> + Send {
+    return polylines.into_iter()
+        .enumerate()
+        .par_bridge()
+        .map(|(index, polyline)| (index, try_decode(polyline.as_ref(), precision)))
+        .fold(Vec::new, |mut results, result| {
+            results.push(result);
+            return results;
+        })
+        .reduce(Vec::new, |mut a, mut b| {
+            // appending the shorter one copies less, at the expense of the order
+            if a.len() < b.len() {
+                std::mem::swap(&mut a, &mut b);
+            }
+            a.append(&mut b);
+            return a;
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, DecodeError, decode_batch, encode};
+    use crate::parallel::{par_decode_batch, par_decode_batch_unordered};
+
+    fn polylines() -> Vec<String> {
+        let mut polylines: Vec<String> = (0..1_000)
+            .map(|i| encode(vec![Point::new(i as f64 * 0.01, 1.), Point::new(2., -(i as f64) * 0.01)], 5))
+            .collect();
+        polylines[500] = String::from("_p~iF");
+        return polylines;
+    }
+
+    #[test]
+    fn ordered() {
+        let polylines = polylines();
+        let decoded = par_decode_batch(&polylines, 5);
+        assert_eq!(decoded, decode_batch(&polylines, 5));
+        assert_eq!(decoded[500], Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+
+    #[test]
+    fn unordered() {
+        let polylines = polylines();
+        let mut decoded = par_decode_batch_unordered(polylines.iter(), 5);
+        assert_eq!(decoded.len(), polylines.len());
+
+        decoded.sort_by_key(|(index, _)| *index);
+        for (i, (index, result)) in decoded.into_iter().enumerate() {
+            assert_eq!(index, i);
+            assert_eq!(result, decode_batch(&polylines[i..=i], 5).remove(0));
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, Coordinate, encode, decode};
+
+/// `10^precision` for every supported precision.
+const SCALES: [f64; 10] = [1., 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9];
+
+/// Number of decimal digits of the coordinates in a polyline, checked to be at most
+/// [`Precision::MAX`].
+///
+/// [`Precision::of`] checks a literal precision at compile time, [`Precision::new`] one that
+/// is only known at runtime:
+///
+/// ```
+/// use polyline_rust::Precision;
+///
+/// const GOOGLE: Precision = Precision::of::<5>();
+/// assert_eq!(GOOGLE, Precision::FIVE);
+/// assert_eq!(Precision::new(42), None);
+/// ```
+///
+/// ```compile_fail
+/// let invalid = polyline_rust::Precision::of::<42>();
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct Precision(u32);
+
+impl Precision {
+    /// Highest supported precision.
+    pub const MAX: u32 = 9;
+
+    /// Google's original precision, accurate to about one meter.
+    pub const FIVE: Precision = Precision(5);
+
+    /// Accurate to about ten centimeters.
+    pub const SIX: Precision = Precision(6);
+
+    /// Creates a new `Precision`, `None` if `digits` is higher than [`Precision::MAX`].
+    pub const fn new(digits: u32) -> Option<Precision> {
+        if digits > Precision::MAX {
+            return None;
+        }
+
+        return Some(Precision(digits));
+    }
+
+    /// Creates a new `Precision`, a precision higher than [`Precision::MAX`] doesn't compile.
+    pub const fn of<const DIGITS: u32>() -> Precision {
+        #[allow(clippy::let_unit_value)]
+        let () = Check::<DIGITS>::VALID;
+        return Precision(DIGITS);
+    }
+
+    /// The number of decimal digits.
+    pub const fn digits(self) -> u32 {
+        return self.0;
+    }
+
+    /// `10^digits`, the factor between coordinates and the integers in the polyline.
+    pub const fn scale(self) -> f64 {
+        return SCALES[self.0 as usize];
+    }
+}
+
+impl From<Precision> for u32 {
+    fn from(precision: Precision) -> u32 {
+        return precision.0;
+    }
+}
+
+/// Fails to compile for precisions above [`Precision::MAX`], see [`Precision::of`].
+struct Check<const DIGITS: u32>;
+
+impl<const DIGITS: u32> Check<DIGITS> {
+    const VALID: () = assert!(DIGITS <= Precision::MAX, "the precision is at most 9");
+}
+
+/// `10^precision`, looked up for the supported precisions.
+///
+/// Unsupported precisions don't panic: up to 22 the result is exact, above it is rounded and
+/// from 309 on it is infinite. `f64::powi` isn't available without `std`, so the power is
+/// multiplied out.
+pub(crate) fn scale(precision: u32) -> f64 {
+    if let Some(scale) = SCALES.get(precision as usize) {
+        return *scale;
+    }
+
+    let mut scale = SCALES[Precision::MAX as usize];
+    let mut digits = Precision::MAX;
+    while digits < precision && scale.is_finite() {
+        scale *= 10.;
+        digits += 1;
+    }
+
+    return scale;
+}
+
+/// Encodes coordinates with a precision checked at compile time, see [`encode`].
+///
+/// ```
+/// use polyline_rust::{Point, encode_const};
+///
+/// assert_eq!(encode_const::<5, _>(vec![Point::new(38.5, -120.2)]), "_p~iF~ps|U");
+/// ```
+pub fn encode_const<const PRECISION: u32, I>(points: I) -> String
+    where I: IntoIterator, I::Item: Coordinate {
+    return encode(points, Precision::of::<PRECISION>().digits());
+}
+
+/// Decodes coordinates with a precision checked at compile time, see [`decode`].
+///
+/// ```
+/// use polyline_rust::{Point, decode_const};
+///
+/// assert_eq!(decode_const::<5>("_p~iF~ps|U"), vec![Point::new(38.5, -120.2)]);
+/// ```
+pub fn decode_const<const PRECISION: u32>(polyline: &str) -> Vec<Point> {
+    return decode(polyline, Precision::of::<PRECISION>().digits());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Precision;
+    use crate::precision::scale;
+
+    #[test]
+    fn scales() {
+        for digits in 0..=Precision::MAX {
+            assert_eq!(Precision::new(digits).unwrap().scale(), 10_u64.pow(digits) as f64);
+            assert_eq!(scale(digits), 10_u64.pow(digits) as f64);
+        }
+        assert_eq!(scale(12), 1e12);
+        assert_eq!(scale(22), 1e22);
+        assert!(scale(308).is_finite());
+        assert_eq!(scale(309), f64::INFINITY);
+        assert_eq!(scale(u32::MAX), f64::INFINITY);
+        assert_eq!(Precision::new(Precision::MAX + 1), None);
+    }
+}
@@ -0,0 +1,210 @@
+//! Protobuf messages for polylines, generated with `prost`.
+//!
+//! gRPC APIs can choose between two representations of the same geometry:
+//!
+//! ```protobuf
+//! message Polyline {
+//!   uint32 precision = 1;
+//!   string encoded = 2;
+//! }
+//!
+//! message LineString {
+//!   uint32 precision = 1;
+//!   repeated sint32 latitude_deltas = 2 [packed = true];
+//!   repeated sint32 longitude_deltas = 3 [packed = true];
+//! }
+//! ```
+//!
+//! `LineString` stores the same fixed-point deltas as the encoded polyline as zigzag varints.
+//! Coordinates and deltas have to fit into 32 bits, which limits the precision to 6. Higher
+//! precisions only work for points close to each other and to the null meridian, conversions
+//! fail with [`ValidationError::Overflow`] for points that don't fit.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::{Point, EncodedPolyline, ValidationError, round_to_integer, try_decode};
+use crate::precision::scale;
+
+/// An encoded polyline string and its precision.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Polyline {
+    #[prost(uint32, tag = "1")]
+    pub precision: u32,
+    #[prost(string, tag = "2")]
+    pub encoded: String
+}
+
+/// Points as fixed-point deltas to the previous point (multiplied by 10^precision).
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LineString {
+    #[prost(uint32, tag = "1")]
+    pub precision: u32,
+    #[prost(sint32, repeated, tag = "2")]
+    pub latitude_deltas: Vec<i32>,
+    #[prost(sint32, repeated, tag = "3")]
+    pub longitude_deltas: Vec<i32>
+}
+
+impl LineString {
+    /// Creates a new `LineString` from points.
+    ///
+    /// Fails with [`ValidationError::Overflow`] if a coordinate or delta doesn't fit into 32
+    /// bits, see the [module documentation](self).
+    ///
+    /// `precision`: usually 5 or 6
+    pub fn from_points(points: &[Point], precision: u32) -> Result<LineString, ValidationError> {
+        let factor = scale(precision);
+
+        let mut line = LineString {
+            precision,
+            latitude_deltas: Vec::with_capacity(points.len()),
+            longitude_deltas: Vec::with_capacity(points.len())
+        };
+
+        let mut latitude: i32 = 0;
+        let mut longitude: i32 = 0;
+        for (index, point) in points.iter().enumerate() {
+            if !point.latitude.is_finite() || !point.longitude.is_finite() {
+                return Err(ValidationError::NotFinite { index });
+            }
+
+            let point_latitude = to_i32(round_to_integer(point.latitude * factor));
+            let point_longitude = to_i32(round_to_integer(point.longitude * factor));
+            let (d_latitude, d_longitude) = match (point_latitude, point_longitude) {
+                (Some(point_latitude), Some(point_longitude)) => (
+                    point_latitude.checked_sub(latitude),
+                    point_longitude.checked_sub(longitude)
+                ),
+                _ => (None, None)
+            };
+
+            match (d_latitude, d_longitude) {
+                (Some(d_latitude), Some(d_longitude)) => {
+                    line.latitude_deltas.push(d_latitude);
+                    line.longitude_deltas.push(d_longitude);
+                    latitude += d_latitude;
+                    longitude += d_longitude;
+                },
+                _ => return Err(ValidationError::Overflow { index })
+            }
+        }
+
+        return Ok(line);
+    }
+
+    /// Converts the deltas back to points. Surplus deltas of the longer list are ignored.
+    pub fn to_points(&self) -> Vec<Point> {
+        let factor = scale(self.precision);
+
+        let mut latitude: i32 = 0;
+        let mut longitude: i32 = 0;
+        return self.latitude_deltas.iter().zip(self.longitude_deltas.iter())
+            .map(|(d_latitude, d_longitude)| {
+                // only messages from other sources overflow, `from_points` rejects those
+                latitude = latitude.wrapping_add(*d_latitude);
+                longitude = longitude.wrapping_add(*d_longitude);
+                return Point::new(latitude as f64 / factor, longitude as f64 / factor);
+            })
+            .collect();
+    }
+}
+
+impl From<EncodedPolyline> for Polyline {
+    fn from(polyline: EncodedPolyline) -> Polyline {
+        return Polyline {
+            precision: polyline.precision(),
+            encoded: polyline.into_string()
+        };
+    }
+}
+
+impl From<Polyline> for EncodedPolyline {
+    fn from(polyline: Polyline) -> EncodedPolyline {
+        return EncodedPolyline::new(polyline.encoded, polyline.precision);
+    }
+}
+
+/// Fails on malformed polylines and points that don't fit, see [`LineString::from_points`].
+impl TryFrom<&Polyline> for LineString {
+    type Error = ValidationError;
+
+    fn try_from(polyline: &Polyline) -> Result<LineString, ValidationError> {
+        let points = try_decode(&polyline.encoded, polyline.precision).map_err(ValidationError::Malformed)?;
+        return LineString::from_points(&points, polyline.precision);
+    }
+}
+
+impl From<&LineString> for Polyline {
+    fn from(line: &LineString) -> Polyline {
+        return Polyline {
+            precision: line.precision,
+            encoded: crate::encode(line.to_points(), line.precision)
+        };
+    }
+}
+
+/// `None` if `value`, an integer, doesn't fit into an `i32`.
+fn to_i32(value: f64) -> Option<i32> {
+    if value < i32::MIN as f64 || value > i32::MAX as f64 {
+        return None;
+    }
+
+    return Some(value as i32);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use prost::Message;
+
+    use crate::{Point, EncodedPolyline, DecodeError, ValidationError};
+    use crate::proto::{Polyline, LineString};
+
+    #[test]
+    fn polyline_roundtrip() {
+        let polyline = Polyline::from(EncodedPolyline::new("_p~iF~ps|U_ulLnnqC".to_string(), 5));
+        let decoded = Polyline::decode(polyline.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(EncodedPolyline::from(decoded).as_str(), "_p~iF~ps|U_ulLnnqC");
+    }
+
+    #[test]
+    fn line_string_roundtrip() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+        let line = LineString::from_points(&points, 5).unwrap();
+        assert_eq!(line.latitude_deltas, vec![3_850_000, 220_000]);
+
+        let decoded = LineString::decode(line.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded.to_points(), points);
+    }
+
+    #[test]
+    fn conversion_between_representations() {
+        let polyline = Polyline { precision: 5, encoded: "_p~iF~ps|U_ulLnnqC".to_string() };
+        assert_eq!(Polyline::from(&LineString::try_from(&polyline).unwrap()), polyline);
+
+        let dangling = Polyline { precision: 5, encoded: "_p~iF~ps|U_ulL".to_string() };
+        assert_eq!(LineString::try_from(&dangling), Err(ValidationError::Malformed(DecodeError::DanglingCoordinate { offset: 10 })));
+    }
+
+    #[test]
+    fn overflow() {
+        // 21.5° is more than 2^31 at precision 8
+        assert_eq!(LineString::from_points(&[Point::new(1., 21.5)], 8), Err(ValidationError::Overflow { index: 0 }));
+        // every coordinate fits at precision 7, but not every delta
+        let points = vec![Point::new(0., 179.9), Point::new(0., -179.9)];
+        assert!(LineString::from_points(&points[..1], 7).is_ok());
+        assert_eq!(LineString::from_points(&points, 7), Err(ValidationError::Overflow { index: 1 }));
+        assert_eq!(LineString::from_points(&points, 6).map(|line| line.to_points()), Ok(points));
+
+        assert_eq!(LineString::from_points(&[Point::new(f64::NAN, 0.)], 5), Err(ValidationError::NotFinite { index: 0 }));
+    }
+
+    #[test]
+    fn high_precision_with_small_coordinates() {
+        let line = LineString { precision: 10, latitude_deltas: vec![1], longitude_deltas: vec![-1] };
+        assert_eq!(line.to_points(), vec![Point::new(1e-10, -1e-10)]);
+    }
+}
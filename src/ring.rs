@@ -0,0 +1,101 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, Error, ValidationError, encode, round, try_decode};
+
+/// Whether the polyline is a closed ring, i.e. it has at least two points and the last one
+/// is the first one.
+///
+/// `points`: points of the polyline
+pub fn is_closed(points: &[Point]) -> bool {
+    return points.len() > 1 && points.first() == points.last();
+}
+
+/// Encodes the boundary of a polygon, e.g. a path of a Google Maps polygon; the ring is closed
+/// by repeating the first point at the end unless the last point is the first one already.
+///
+/// Points are compared at `precision`, so a last point that only differs from the first one
+/// below the precision isn't followed by another copy of the first point.
+///
+/// `points`: points of the ring, closed or not
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+///
+/// ```
+/// use polyline_rust::{Point, encode_ring, decode_ring, is_closed};
+///
+/// let triangle = vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 0.)];
+/// let ring = decode_ring(&encode_ring(&triangle, 5), 5).unwrap();
+/// assert_eq!(ring.len(), 4);
+/// assert!(is_closed(&ring));
+/// ```
+pub fn encode_ring(points: &[Point], precision: u32) -> String {
+    let same_position = |a: &Point, b: &Point| {
+        return round(a.latitude, precision) == round(b.latitude, precision)
+            && round(a.longitude, precision) == round(b.longitude, precision);
+    };
+
+    return match (points.first(), points.last()) {
+        (Some(first), Some(last)) if points.len() == 1 || !same_position(first, last) => {
+            encode(points.iter().chain(core::iter::once(first)), precision)
+        },
+        _ => encode(points, precision)
+    };
+}
+
+/// Decodes the boundary of a polygon and checks that the ring is closed, see [`encode_ring`].
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`](crate::decode)
+///
+/// Returns [`DecodeError`](crate::DecodeError)s of malformed polylines like
+/// [`try_decode`] and [`ValidationError::NotClosed`] if the last point isn't the first one.
+/// An empty polyline is an empty ring.
+pub fn decode_ring(polyline: &str, precision: u32) -> Result<Vec<Point>, Error> {
+    let points = try_decode(polyline, precision)?;
+    if !points.is_empty() && !is_closed(&points) {
+        return Err(Error::Validation(ValidationError::NotClosed));
+    }
+
+    return Ok(points);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, Error, DecodeError, ValidationError, encode, encode_ring, decode_ring, is_closed};
+
+    fn square() -> Vec<Point> {
+        return vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(1., 1.), Point::new(1., 0.)];
+    }
+
+    #[test]
+    fn closes_once() {
+        let mut closed = square();
+        closed.push(Point::new(0., 0.));
+        assert_eq!(encode_ring(&square(), 5), encode(&closed, 5));
+        assert_eq!(encode_ring(&closed, 5), encode(&closed, 5));
+
+        // closed at the precision already
+        let mut almost = square();
+        almost.push(Point::new(0.000001, 0.));
+        assert_eq!(encode_ring(&almost, 5), encode(&closed, 5));
+        assert_eq!(encode_ring(&almost, 6), encode(almost.iter().chain(closed.iter().take(1)), 6));
+
+        assert_eq!(encode_ring(&[], 5), "");
+        assert_eq!(encode_ring(&closed[..1], 5), encode([closed[0], closed[0]], 5));
+    }
+
+    #[test]
+    fn closed_rings_only() {
+        let mut closed = square();
+        closed.push(Point::new(0., 0.));
+        assert!(is_closed(&closed) && !is_closed(&square()) && !is_closed(&closed[..1]));
+
+        assert_eq!(decode_ring(&encode(&closed, 5), 5).ok(), Some(closed));
+        assert_eq!(decode_ring("", 5).ok(), Some(vec![]));
+        assert!(matches!(decode_ring(&encode(square(), 5), 5), Err(Error::Validation(ValidationError::NotClosed))));
+        assert!(matches!(decode_ring("_p~iF", 5), Err(Error::Decode(DecodeError::DanglingCoordinate { offset: 0 }))));
+    }
+}
@@ -0,0 +1,235 @@
+//! S2 cells of polylines, e.g. as row keys for routes in stores sorted by S2 cell id.
+//!
+//! More info: [https://s2geometry.io](https://s2geometry.io)
+//!
+//! Cell ids are the 64 bit ids of the S2 library (quadratic projection, Hilbert curve order),
+//! so they can be mixed with ids computed by other S2 implementations.
+//!
+//! ```
+//! use polyline_rust::{Point, decode};
+//! use polyline_rust::s2::{s2_covering, cell_level};
+//!
+//! let route = decode("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5);
+//! let cells = s2_covering(&route, 4, 10, 8);
+//! assert!(!cells.is_empty() && cells.len() <= 8);
+//! assert!(cells.iter().all(|cell| (4..=10).contains(&cell_level(*cell))));
+//! ```
+
+use std::collections::BTreeSet;
+
+use crate::{Point, EARTH_RADIUS_M};
+use crate::geodesy::{distance, interpolate};
+
+/// Level of the smallest cells (about 1cm²).
+pub const MAX_LEVEL: u8 = 30;
+
+const POS_BITS: u32 = 2 * MAX_LEVEL as u32 + 1;
+const MAX_SIZE: u32 = 1 << MAX_LEVEL;
+
+const SWAP_MASK: usize = 0b01;
+const INVERT_MASK: usize = 0b10;
+
+/// Position of the child cells along the Hilbert curve by orientation and `(i << 1) | j`.
+const IJ_TO_POS: [[u64; 4]; 4] = [
+    [0, 1, 3, 2],
+    [0, 3, 1, 2],
+    [2, 3, 1, 0],
+    [2, 1, 3, 0]
+];
+
+/// Change of orientation of the child cell at each position.
+const POS_TO_ORIENTATION: [usize; 4] = [SWAP_MASK, 0, 0, INVERT_MASK | SWAP_MASK];
+
+/// Minimum width of cells of level 0 in radians; cells of level `k` are 2^k times smaller.
+const MIN_WIDTH: f64 = 0.942_809_041_582_063_4;
+
+/// Id of the cell at `level` containing the point.
+///
+/// `level`: 0 (one of the six faces of the cube) to [`MAX_LEVEL`]
+pub fn cell_id(point: &Point, level: u8) -> u64 {
+    return parent(leaf_cell_id(point), level.min(MAX_LEVEL));
+}
+
+/// Level of a cell id.
+pub fn cell_level(id: u64) -> u8 {
+    return MAX_LEVEL - (id.trailing_zeros() / 2) as u8;
+}
+
+/// Cells at a level between `min_level` and `max_level` covering the polyline, sorted by id.
+///
+/// The polyline is sampled along its segments (in plain latitude/longitude space) at a
+/// fraction of the cell size, so a cell only touched at one of its corners may be missed.
+/// Cells of `max_level` are used if there are at most `max_cells` of them, otherwise all
+/// cells are replaced by their parents until they are few enough. `min_level` takes
+/// precedence over `max_cells`: the result can contain more cells for long routes.
+///
+/// `points`: points of the polyline
+///
+/// `min_level`, `max_level`: range of cell levels, at most [`MAX_LEVEL`]
+///
+/// `max_cells`: desired maximum number of cells, `usize::MAX` for no limit
+///
+/// Returns an empty `Vec` for an empty polyline.
+pub fn s2_covering(points: &[Point], min_level: u8, max_level: u8, max_cells: usize) -> Vec<u64> {
+    let max_level = max_level.min(MAX_LEVEL);
+    let min_level = min_level.min(max_level);
+    let max_cells = max_cells.max(1);
+
+    // start with a level whose cells are large enough to keep the number of samples bounded
+    let length: f64 = points.windows(2).map(|segment| distance(&segment[0], &segment[1])).sum();
+    let mut level = max_level;
+    while level > min_level && length / cell_width_m(level) > 4. * max_cells as f64 {
+        level -= 1;
+    }
+
+    let step = cell_width_m(level) / 4.;
+    let mut cells: BTreeSet<u64> = BTreeSet::new();
+    for point in points.iter() {
+        cells.insert(cell_id(point, level));
+    }
+    for segment in points.windows(2) {
+        let samples = (distance(&segment[0], &segment[1]) / step).ceil() as usize;
+        for k in 1..samples {
+            let sample = interpolate(&segment[0], &segment[1], k as f64 / samples as f64);
+            cells.insert(cell_id(&sample, level));
+        }
+    }
+
+    while cells.len() > max_cells && level > min_level {
+        level -= 1;
+        cells = cells.iter().map(|cell| parent(*cell, level)).collect();
+    }
+
+    return cells.into_iter().collect();
+}
+
+/// Minimum width of cells of `level` in meters.
+fn cell_width_m(level: u8) -> f64 {
+    return MIN_WIDTH * EARTH_RADIUS_M / (1_u64 << level) as f64;
+}
+
+fn parent(id: u64, level: u8) -> u64 {
+    let lsb: u64 = 1 << (2 * (MAX_LEVEL - level));
+    return (id & lsb.wrapping_neg()) | lsb;
+}
+
+fn leaf_cell_id(point: &Point) -> u64 {
+    let (face, u, v) = face_uv(point);
+    let i = st_to_ij(uv_to_st(u));
+    let j = st_to_ij(uv_to_st(v));
+
+    let mut orientation = face & SWAP_MASK;
+    let mut position: u64 = 0;
+    for k in (0..MAX_LEVEL).rev() {
+        let ij = (((i >> k) & 1) << 1 | ((j >> k) & 1)) as usize;
+        let child = IJ_TO_POS[orientation][ij];
+        position = (position << 2) | child;
+        orientation ^= POS_TO_ORIENTATION[child as usize];
+    }
+
+    return ((face as u64) << POS_BITS) | (position << 1) | 1;
+}
+
+/// Projects the point onto a face of the cube.
+fn face_uv(point: &Point) -> (usize, f64, f64) {
+    let latitude = point.latitude.to_radians();
+    let longitude = point.longitude.to_radians();
+    let x = latitude.cos() * longitude.cos();
+    let y = latitude.cos() * longitude.sin();
+    let z = latitude.sin();
+
+    let mut face = if x.abs() >= y.abs() && x.abs() >= z.abs() {
+        0
+    } else if y.abs() >= z.abs() {
+        1
+    } else {
+        2
+    };
+    if [x, y, z][face] < 0. {
+        face += 3;
+    }
+
+    let (u, v) = match face {
+        0 => (y / x, z / x),
+        1 => (-x / y, z / y),
+        2 => (-x / z, -y / z),
+        3 => (z / x, y / x),
+        4 => (z / y, -x / y),
+        _ => (-y / z, -x / z)
+    };
+
+    return (face, u, v);
+}
+
+/// Quadratic projection, which makes cells of the same level roughly equal in size.
+fn uv_to_st(u: f64) -> f64 {
+    if u >= 0. {
+        return 0.5 * (1. + 3. * u).sqrt();
+    }
+    return 1. - 0.5 * (1. - 3. * u).sqrt();
+}
+
+fn st_to_ij(s: f64) -> u32 {
+    return ((s * MAX_SIZE as f64).floor() as i64).clamp(0, MAX_SIZE as i64 - 1) as u32;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+    use crate::s2::{cell_id, cell_level, s2_covering, parent};
+
+    #[test]
+    fn faces() {
+        assert_eq!(cell_id(&Point::new(0., 0.), 0), 0x1000_0000_0000_0000);
+        assert_eq!(cell_id(&Point::new(0., 90.), 0), 0x3000_0000_0000_0000);
+        assert_eq!(cell_id(&Point::new(90., 0.), 0), 0x5000_0000_0000_0000);
+        assert_eq!(cell_id(&Point::new(0., 180.), 0), 0x7000_0000_0000_0000);
+        assert_eq!(cell_id(&Point::new(0., -90.), 0), 0x9000_0000_0000_0000);
+        assert_eq!(cell_id(&Point::new(-90., 0.), 0), 0xb000_0000_0000_0000);
+    }
+
+    #[test]
+    fn same_as_s2_library() {
+        // New York City and San Francisco
+        assert_eq!(cell_id(&Point::new(40.7128, -74.0060), 12), 0x89c2_5a30_0000_0000);
+        assert_eq!(cell_id(&Point::new(37.7749, -122.4194), 12), 0x8085_8090_0000_0000);
+    }
+
+    #[test]
+    fn levels() {
+        let point = Point::new(48.208771, 16.372572);
+        let leaf = cell_id(&point, 30);
+        assert_eq!(leaf & 1, 1);
+        for level in 0..=30 {
+            let cell = cell_id(&point, level);
+            assert_eq!(cell_level(cell), level);
+            assert_eq!(parent(leaf, level), cell);
+        }
+    }
+
+    #[test]
+    fn covering() {
+        let route = vec![Point::new(48.2, 16.3), Point::new(48.2, 16.4), Point::new(48.3, 16.4)];
+
+        let fine = s2_covering(&route, 10, 16, 1000);
+        assert!(fine.len() > 10 && fine.len() <= 1000);
+        assert!(fine.iter().all(|cell| cell_level(*cell) == 16));
+        assert!(fine.windows(2).all(|pair| pair[0] < pair[1]));
+        for point in route.iter() {
+            assert!(fine.contains(&cell_id(point, 16)));
+        }
+
+        let coarse = s2_covering(&route, 2, 16, 4);
+        assert!(coarse.len() <= 4);
+        let level = cell_level(coarse[0]);
+        assert!(route.iter().all(|point| coarse.contains(&cell_id(point, level))));
+
+        assert_eq!(s2_covering(&[], 0, 30, 8), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn unlimited_cells() {
+        let route = vec![Point::new(48.2, 16.3), Point::new(48.2, 16.301)];
+        assert_eq!(s2_covering(&route, 10, 16, usize::MAX), s2_covering(&route, 10, 16, 1000));
+    }
+}
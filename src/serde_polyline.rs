@@ -0,0 +1,108 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::{Serializer, Deserializer, Deserialize};
+use serde::de::Error;
+
+use crate::{Point, encode, try_decode};
+
+/// Serializes a `Vec<Point>` field as encoded polyline string with precision 5.
+///
+/// ```
+/// use polyline_rust::Point;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Route {
+///     #[serde(with = "polyline_rust::serde_polyline5")]
+///     points: Vec<Point>
+/// }
+///
+/// let route = Route { points: vec![Point::new(38.5, -120.2)] };
+/// assert_eq!(serde_json::to_string(&route).unwrap(), r#"{"points":"_p~iF~ps|U"}"#);
+/// ```
+pub mod serde_polyline5 {
+    use alloc::vec::Vec;
+
+    use serde::{Serializer, Deserializer};
+
+    use crate::Point;
+
+    /// Serializes the points as encoded polyline string.
+    pub fn serialize<S: Serializer>(points: &[Point], serializer: S) -> Result<S::Ok, S::Error> {
+        return super::serialize(points, 5, serializer);
+    }
+
+    /// Deserializes points from an encoded polyline string, malformed strings are rejected.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Point>, D::Error> {
+        return super::deserialize(5, deserializer);
+    }
+}
+
+/// Serializes a `Vec<Point>` field as encoded polyline string with precision 6, see
+/// [`serde_polyline5`](crate::serde_polyline5).
+pub mod serde_polyline6 {
+    use alloc::vec::Vec;
+
+    use serde::{Serializer, Deserializer};
+
+    use crate::Point;
+
+    /// Serializes the points as encoded polyline string.
+    pub fn serialize<S: Serializer>(points: &[Point], serializer: S) -> Result<S::Ok, S::Error> {
+        return super::serialize(points, 6, serializer);
+    }
+
+    /// Deserializes points from an encoded polyline string, malformed strings are rejected.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Point>, D::Error> {
+        return super::deserialize(6, deserializer);
+    }
+}
+
+fn serialize<S: Serializer>(points: &[Point], precision: u32, serializer: S) -> Result<S::Ok, S::Error> {
+    return serializer.serialize_str(&encode(points, precision));
+}
+
+fn deserialize<'de, D: Deserializer<'de>>(precision: u32, deserializer: D) -> Result<Vec<Point>, D::Error> {
+    let polyline = String::deserialize(deserializer)?;
+    return try_decode(&polyline, precision).map_err(D::Error::custom);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Serialize, Deserialize};
+
+    use crate::Point;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Route {
+        #[serde(with = "crate::serde_polyline5")]
+        points: Vec<Point>,
+        #[serde(with = "crate::serde_polyline6")]
+        precise: Vec<Point>
+    }
+
+    #[test]
+    fn point() {
+        let json = serde_json::to_string(&Point::new(38.5, -120.2)).unwrap();
+        assert_eq!(json, r#"{"latitude":38.5,"longitude":-120.2}"#);
+        assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), Point::new(38.5, -120.2));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let route = Route {
+            points: vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)],
+            precise: vec![Point::new(1.000001, 2.)]
+        };
+        let json = serde_json::to_string(&route).unwrap();
+        assert_eq!(json, r#"{"points":"_p~iF~ps|U_ulLnnqC","precise":"ac`|@_gayB"}"#);
+        assert_eq!(serde_json::from_str::<Route>(&json).unwrap(), route);
+    }
+
+    #[test]
+    fn malformed() {
+        let error = serde_json::from_str::<Route>(r#"{"points":"_p~iF","precise":""}"#).unwrap_err();
+        assert!(error.to_string().contains("latitude without longitude"));
+    }
+}
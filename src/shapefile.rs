@@ -0,0 +1,280 @@
+//! Shapefile export of decoded polylines.
+//!
+//! More info: [ESRI Shapefile Technical Description](https://www.esri.com/content/dam/esrisites/sitecore-archive/Files/Pdfs/library/whitepapers/pdfs/shapefile.pdf)
+//!
+//! A shapefile consists of several files with the same name and different extensions; this
+//! module writes the mandatory ones:
+//!
+//! * `.shp`: the geometries, one `PolyLine` per polyline; empty polylines become `Null` shapes
+//! * `.shx`: the index of the records in the `.shp` file
+//! * `.dbf`: the attribute table (dBase III) with a single character column `ID`
+//!
+//! Coordinates are WGS84 longitude/latitude, write [`PRJ_WGS84`] to the `.prj` file so GIS
+//! software picks up the coordinate system.
+
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{Point, try_decode};
+
+/// Content of the `.prj` file for WGS84 coordinates.
+pub const PRJ_WGS84: &str = "GEOGCS[\"GCS_WGS_1984\",DATUM[\"D_WGS_1984\",SPHEROID[\"WGS_1984\",6378137.0,298.257223563]],PRIMEM[\"Greenwich\",0.0],UNIT[\"Degree\",0.0174532925199433]]";
+
+const FILE_CODE: i32 = 9994;
+const VERSION: i32 = 1000;
+const HEADER_LENGTH: usize = 100;
+
+const SHAPE_TYPE_NULL: i32 = 0;
+const SHAPE_TYPE_POLY_LINE: i32 = 3;
+
+/// dBase character fields are at most 254 bytes long.
+const MAX_ID_LENGTH: usize = 254;
+
+/// Writes polylines as shapefile.
+///
+/// `features`: pairs of feature id and polyline string in "Encoded Polyline Algorithm Format";
+/// ids longer than 254 bytes are truncated
+///
+/// `precision`: precision the polylines were encoded with
+///
+/// `shp`, `shx`, `dbf`: destinations of the three files, e.g. `BufWriter<File>`s
+///
+/// All polylines are decoded before writing, because the headers contain the bounding box and
+/// the length of the files. A malformed polyline fails with [`io::ErrorKind::InvalidData`]
+/// naming the feature id, nothing is written in that case.
+pub fn write_shapefile<W, I, K, S>(features: I, precision: u32, shp: W, shx: W, dbf: W) -> io::Result<()>
+    where W: Write, I: IntoIterator<Item = (K, S)>, K: AsRef<str>, S: AsRef<str> {
+    return write_shapefile_with_progress(features, precision, shp, shx, dbf, |_, _| {});
+}
+
+/// Like [`write_shapefile`], and calls `progress` after every decoded feature.
+///
+/// `progress`: called with the number of decoded features and the total number of features
+pub fn write_shapefile_with_progress<W, I, K, S, F>(features: I, precision: u32, mut shp: W, mut shx: W, mut dbf: W, mut progress: F) -> io::Result<()>
+    where W: Write, I: IntoIterator<Item = (K, S)>, K: AsRef<str>, S: AsRef<str>, F: FnMut(usize, usize) {
+    let features: Vec<(K, S)> = features.into_iter().collect();
+    let mut ids: Vec<String> = Vec::with_capacity(features.len());
+    let mut lines: Vec<Vec<Point>> = Vec::with_capacity(features.len());
+    for (id, polyline) in features.iter() {
+        ids.push(truncate(id.as_ref(), MAX_ID_LENGTH).to_string());
+        lines.push(try_decode(polyline.as_ref(), precision).map_err(|error| {
+            return io::Error::new(io::ErrorKind::InvalidData, format!("feature {:?}: {}", id.as_ref(), error));
+        })?);
+        progress(lines.len(), features.len());
+    }
+
+    let mut envelope = [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY];
+    for point in lines.iter().flatten() {
+        envelope[0] = envelope[0].min(point.longitude);
+        envelope[1] = envelope[1].min(point.latitude);
+        envelope[2] = envelope[2].max(point.longitude);
+        envelope[3] = envelope[3].max(point.latitude);
+    }
+    if envelope[0] > envelope[2] {
+        envelope = [0.; 4];
+    }
+
+    // all lengths and offsets in the .shp and .shx files are counted in 16 bit words
+    let content_lengths: Vec<usize> = lines.iter().map(|line| content_length(line)).collect();
+    let shp_length = HEADER_LENGTH + content_lengths.iter().map(|length| 8 + length).sum::<usize>();
+    let shx_length = HEADER_LENGTH + 8 * lines.len();
+
+    write_header(&mut shp, shp_length, &envelope)?;
+    write_header(&mut shx, shx_length, &envelope)?;
+
+    let mut offset = HEADER_LENGTH;
+    for (i, (line, length)) in lines.iter().zip(content_lengths.iter()).enumerate() {
+        shx.write_all(&((offset / 2) as i32).to_be_bytes())?;
+        shx.write_all(&((length / 2) as i32).to_be_bytes())?;
+
+        shp.write_all(&(i as i32 + 1).to_be_bytes())?;
+        shp.write_all(&((length / 2) as i32).to_be_bytes())?;
+        write_poly_line(&mut shp, line)?;
+
+        offset += 8 + length;
+    }
+
+    write_dbf(&mut dbf, &ids)?;
+
+    shp.flush()?;
+    shx.flush()?;
+    return dbf.flush();
+}
+
+/// Length in bytes of the record content of a line, without the record header.
+fn content_length(line: &[Point]) -> usize {
+    if line.is_empty() {
+        return 4;
+    }
+    // shape type, box, number of parts and points, a single part index and the points
+    return 4 + 32 + 4 + 4 + 4 + 16 * line.len();
+}
+
+fn write_header<W: Write>(writer: &mut W, file_length: usize, envelope: &[f64; 4]) -> io::Result<()> {
+    writer.write_all(&FILE_CODE.to_be_bytes())?;
+    writer.write_all(&[0; 20])?;
+    writer.write_all(&((file_length / 2) as i32).to_be_bytes())?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&SHAPE_TYPE_POLY_LINE.to_le_bytes())?;
+    for value in envelope.iter() {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    // no z and m values
+    return writer.write_all(&[0; 32]);
+}
+
+fn write_poly_line<W: Write>(writer: &mut W, line: &[Point]) -> io::Result<()> {
+    if line.is_empty() {
+        return writer.write_all(&SHAPE_TYPE_NULL.to_le_bytes());
+    }
+
+    writer.write_all(&SHAPE_TYPE_POLY_LINE.to_le_bytes())?;
+
+    let min_lon = line.iter().map(|point| point.longitude).fold(f64::INFINITY, f64::min);
+    let min_lat = line.iter().map(|point| point.latitude).fold(f64::INFINITY, f64::min);
+    let max_lon = line.iter().map(|point| point.longitude).fold(f64::NEG_INFINITY, f64::max);
+    let max_lat = line.iter().map(|point| point.latitude).fold(f64::NEG_INFINITY, f64::max);
+    for value in [min_lon, min_lat, max_lon, max_lat].iter() {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+
+    writer.write_all(&1_i32.to_le_bytes())?;
+    writer.write_all(&(line.len() as i32).to_le_bytes())?;
+    writer.write_all(&0_i32.to_le_bytes())?;
+    for point in line.iter() {
+        writer.write_all(&point.longitude.to_le_bytes())?;
+        writer.write_all(&point.latitude.to_le_bytes())?;
+    }
+
+    return Ok(());
+}
+
+/// Writes a dBase III table with a single character column `ID`.
+fn write_dbf<W: Write>(writer: &mut W, ids: &[String]) -> io::Result<()> {
+    let width = ids.iter().map(|id| id.len()).max().unwrap_or(0).max(1);
+    let (year, month, day) = today();
+
+    writer.write_all(&[0x03, (year - 1900) as u8, month, day])?;
+    writer.write_all(&(ids.len() as u32).to_le_bytes())?;
+    // header: 32 bytes, one 32 byte field descriptor and the terminator
+    writer.write_all(&(32_u16 + 32 + 1).to_le_bytes())?;
+    // record: deletion flag and the id
+    writer.write_all(&(1 + width as u16).to_le_bytes())?;
+    writer.write_all(&[0; 20])?;
+
+    let mut descriptor = [0_u8; 32];
+    descriptor[..2].copy_from_slice(b"ID");
+    descriptor[11] = b'C';
+    descriptor[16] = width as u8;
+    writer.write_all(&descriptor)?;
+    writer.write_all(&[0x0d])?;
+
+    for id in ids.iter() {
+        writer.write_all(b" ")?;
+        writer.write_all(id.as_bytes())?;
+        writer.write_all(&vec![b' '; width - id.len()])?;
+    }
+
+    return writer.write_all(&[0x1a]);
+}
+
+/// Cuts `text` to at most `length` bytes without splitting a character.
+fn truncate(text: &str, length: usize) -> &str {
+    let mut end = text.len().min(length);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    return &text[..end];
+}
+
+/// Current date (UTC) as year, month and day for the `.dbf` header.
+fn today() -> (i64, u8, u8) {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+    return civil_from_days((seconds / 86_400) as i64);
+}
+
+/// Converts days since 1970-01-01 to a date in the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return (year, month as u8, day as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapefile::{write_shapefile, write_shapefile_with_progress, civil_from_days, truncate};
+
+    fn i32_be(bytes: &[u8], position: usize) -> i32 {
+        let mut buffer = [0; 4];
+        buffer.copy_from_slice(&bytes[position..position + 4]);
+        return i32::from_be_bytes(buffer);
+    }
+
+    fn f64_le(bytes: &[u8], position: usize) -> f64 {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(&bytes[position..position + 8]);
+        return f64::from_le_bytes(buffer);
+    }
+
+    #[test]
+    fn shp_shx_and_dbf() {
+        let (mut shp, mut shx, mut dbf): (Vec<u8>, Vec<u8>, Vec<u8>) = (vec![], vec![], vec![]);
+        write_shapefile(vec![("a", "_p~iF~ps|U_ulLnnqC"), ("bc", "")], 5, &mut shp, &mut shx, &mut dbf).unwrap();
+
+        // header, polyline record with two points and a null record
+        assert_eq!(shp.len(), 100 + 8 + 80 + 8 + 4);
+        assert_eq!(i32_be(&shp, 0), 9994);
+        assert_eq!(i32_be(&shp, 24) as usize * 2, shp.len());
+        assert_eq!(f64_le(&shp, 36), -120.95);
+        assert_eq!(f64_le(&shp, 60), 40.7);
+        assert_eq!(f64_le(&shp, 100 + 8 + 48), -120.2);
+
+        assert_eq!(shx.len(), 100 + 2 * 8);
+        assert_eq!(i32_be(&shx, 100), 50);
+        assert_eq!(i32_be(&shx, 108), 50 + 4 + 40);
+        assert_eq!(i32_be(&shx, 112), 2);
+
+        assert_eq!(dbf[4], 2);
+        assert_eq!(dbf[32 + 16], 2);
+        assert_eq!(dbf[65..], b" a  bc\x1a"[..]);
+    }
+
+    #[test]
+    fn progress() {
+        let (mut shp, mut shx, mut dbf): (Vec<u8>, Vec<u8>, Vec<u8>) = (vec![], vec![], vec![]);
+        let mut reported = vec![];
+        write_shapefile_with_progress(vec![("a", "??"), ("b", "??")], 5, &mut shp, &mut shx, &mut dbf, |done, total| reported.push((done, total))).unwrap();
+        assert_eq!(reported, vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn malformed_polyline() {
+        let (mut shp, mut shx, mut dbf): (Vec<u8>, Vec<u8>, Vec<u8>) = (vec![], vec![], vec![]);
+        let error = write_shapefile(vec![("a", "??"), ("b", "_p~iF")], 5, &mut shp, &mut shx, &mut dbf).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "feature \"b\": latitude without longitude at offset 0");
+        assert!(shp.is_empty() && shx.is_empty() && dbf.is_empty());
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn truncate_at_char_boundary() {
+        assert_eq!(truncate("äb", 1), "");
+        assert_eq!(truncate("äb", 2), "ä");
+        assert_eq!(truncate("ab", 10), "ab");
+    }
+}
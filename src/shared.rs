@@ -0,0 +1,125 @@
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crate::{Point, Bounds, Cancelled, DecodeError, encode, try_decode, length};
+use crate::bounds::bounds;
+use crate::simplify::douglas_peucker;
+
+/// Immutable, reference-counted polyline.
+///
+/// Cloning only increments a reference count, so decoded geometry can be shared between threads
+/// and async tasks without copying the points. Dereferences to `[Point]`.
+///
+/// ```
+/// use polyline_rust::SharedPolyline;
+///
+/// let route = SharedPolyline::decode("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5).unwrap();
+/// let shared = route.clone();
+/// std::thread::spawn(move || assert_eq!(shared.len(), 3)).join().unwrap();
+/// assert_eq!(route.encode(5), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+/// ```
+#[derive(PartialEq, Debug, Clone)]
+pub struct SharedPolyline(Arc<[Point]>);
+
+impl SharedPolyline {
+    /// Decodes a polyline, malformed strings are rejected.
+    ///
+    /// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+    ///
+    /// `precision`: usually 5 or 6, see [`decode`](crate::decode)
+    pub fn decode(polyline: &str, precision: u32) -> Result<SharedPolyline, DecodeError> {
+        return Ok(SharedPolyline::from(try_decode(polyline, precision)?));
+    }
+
+    /// Encodes the points, see [`encode`].
+    pub fn encode(&self, precision: u32) -> String {
+        return encode(self.points(), precision);
+    }
+
+    /// The points of the polyline.
+    pub fn points(&self) -> &[Point] {
+        return &self.0;
+    }
+
+    /// Length of the polyline in meters, see [`length`].
+    pub fn length(&self) -> f64 {
+        return length(&self.0);
+    }
+
+    /// Bounding box of the points, `None` if there are none.
+    pub fn bounds(&self) -> Option<Bounds> {
+        return bounds(&self.0);
+    }
+
+    /// Simplifies the polyline with Ramer–Douglas–Peucker into a new `SharedPolyline`, see
+    /// [`simplify`](crate::simplify).
+    ///
+    /// `tolerance_deg`: points closer than this to the simplified line are removed
+    pub fn simplify(&self, tolerance_deg: f64) -> SharedPolyline {
+        // can't be cancelled without flag
+        return SharedPolyline::from(douglas_peucker(&self.0, tolerance_deg, None).unwrap_or_default());
+    }
+
+    /// Like [`simplify`](SharedPolyline::simplify), but stops as soon as `cancel` is set.
+    ///
+    /// `tolerance_deg`: points closer than this to the simplified line are removed
+    ///
+    /// `cancel`: cancellation flag, set by another thread
+    pub fn simplify_cancellable(&self, tolerance_deg: f64, cancel: &AtomicBool) -> Result<SharedPolyline, Cancelled> {
+        return Ok(SharedPolyline::from(douglas_peucker(&self.0, tolerance_deg, Some(cancel))?));
+    }
+
+    /// Returns `true` if both share the same points, i.e. one is a clone of the other.
+    pub fn ptr_eq(&self, other: &SharedPolyline) -> bool {
+        return Arc::ptr_eq(&self.0, &other.0);
+    }
+}
+
+impl From<Vec<Point>> for SharedPolyline {
+    fn from(points: Vec<Point>) -> SharedPolyline {
+        return SharedPolyline(Arc::from(points));
+    }
+}
+
+impl Deref for SharedPolyline {
+    type Target = [Point];
+
+    fn deref(&self) -> &[Point] {
+        return &self.0;
+    }
+}
+
+impl AsRef<[Point]> for SharedPolyline {
+    fn as_ref(&self) -> &[Point] {
+        return &self.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Bounds, SharedPolyline, DecodeError};
+
+    #[test]
+    fn clones_share_points() {
+        let polyline = SharedPolyline::decode("_p~iF~ps|U_ulLnnqC", 5).unwrap();
+        let clone = polyline.clone();
+        assert!(polyline.ptr_eq(&clone));
+        assert_eq!(clone[1], Point::new(40.7, -120.95));
+        assert!(!polyline.ptr_eq(&SharedPolyline::from(vec![])));
+    }
+
+    #[test]
+    fn methods() {
+        let polyline = SharedPolyline::from(vec![Point::new(0., 0.), Point::new(0.001, 1.), Point::new(0., 2.)]);
+        assert_eq!(polyline.bounds(), Some(Bounds::new(0., 0., 0.001, 2.)));
+        assert!((polyline.length() - 222_390.).abs() < 10.);
+        assert_eq!(polyline.simplify(0.01).points(), &[Point::new(0., 0.), Point::new(0., 2.)]);
+        assert_eq!(SharedPolyline::from(vec![]).bounds(), None);
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(SharedPolyline::decode("_p~iF", 5), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+}
@@ -0,0 +1,413 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::AtomicBool;
+
+use crate::{Point, Cancelled, DecodeError, distance, encode, try_decode};
+use crate::cancel::check;
+
+/// Simplifies a line with the Ramer–Douglas–Peucker algorithm, e.g. to shrink dense GPS traces
+/// before encoding them for map display.
+///
+/// The first and the last point are always kept; any other point is removed if it's closer
+/// than `tolerance_deg` to the simplified line. Distances are measured in plain
+/// latitude/longitude space, so the tolerance is in degrees: 0.0001° is roughly 11 m in
+/// latitude, and less in longitude away from the equator.
+///
+/// `points`: points of the line
+///
+/// `tolerance_deg`: maximum distance of removed points from the simplified line
+///
+/// ```
+/// use polyline_rust::{Point, simplify};
+///
+/// let trace = vec![Point::new(0., 0.), Point::new(0.00001, 0.001), Point::new(0., 0.002)];
+/// assert_eq!(simplify(&trace, 0.0001), vec![Point::new(0., 0.), Point::new(0., 0.002)]);
+/// ```
+pub fn simplify(points: &[Point], tolerance_deg: f64) -> Vec<Point> {
+    // can't be cancelled without flag
+    return douglas_peucker(points, tolerance_deg, None).unwrap_or_default();
+}
+
+/// Algorithms of [`simplify_with`].
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum SimplifyAlgorithm {
+    /// Ramer–Douglas–Peucker, keeps points farther than the tolerance from the simplified
+    /// line, see [`simplify`]
+    #[default]
+    DouglasPeucker,
+    /// Visvalingam–Whyatt, repeatedly removes the point forming the smallest triangle with its
+    /// neighbours until all triangles have an area of at least the squared tolerance; keeps
+    /// the overall shape better and gives smoother lines for cartographic display
+    VisvalingamWhyatt
+}
+
+/// Simplifies a line with the given algorithm.
+///
+/// `points`: points of the line
+///
+/// `tolerance_deg`: tolerance in degrees, see [`SimplifyAlgorithm`] for its meaning
+///
+/// `algorithm`: the simplification algorithm
+///
+/// ```
+/// use polyline_rust::{Point, SimplifyAlgorithm, simplify_with};
+///
+/// let trace = vec![Point::new(0., 0.), Point::new(0.00001, 0.001), Point::new(0., 0.002)];
+/// let simplified = simplify_with(&trace, 0.001, SimplifyAlgorithm::VisvalingamWhyatt);
+/// assert_eq!(simplified, vec![Point::new(0., 0.), Point::new(0., 0.002)]);
+/// ```
+pub fn simplify_with(points: &[Point], tolerance_deg: f64, algorithm: SimplifyAlgorithm) -> Vec<Point> {
+    // can't be cancelled without flag
+    return match algorithm {
+        SimplifyAlgorithm::DouglasPeucker => douglas_peucker(points, tolerance_deg, None),
+        SimplifyAlgorithm::VisvalingamWhyatt => visvalingam_whyatt(points, tolerance_deg, None)
+    }.unwrap_or_default();
+}
+
+/// Like [`simplify_with`], and calls `progress` while simplifying, e.g. to render a progress
+/// bar while a long trace is simplified for several zoom levels.
+///
+/// `progress`: called with the number of points known to be kept or removed and the total
+/// number of points, the last call reports all points
+///
+/// ```
+/// use polyline_rust::{Point, SimplifyAlgorithm, simplify_with_progress};
+///
+/// let trace = vec![Point::new(0., 0.), Point::new(0.00001, 0.001), Point::new(0., 0.002)];
+/// let mut reported = Vec::new();
+/// simplify_with_progress(&trace, 0.0001, SimplifyAlgorithm::DouglasPeucker, |done, total| reported.push((done, total)));
+/// assert_eq!(reported.last(), Some(&(3, 3)));
+/// ```
+pub fn simplify_with_progress<F>(points: &[Point], tolerance_deg: f64, algorithm: SimplifyAlgorithm, progress: F) -> Vec<Point>
+    where F: FnMut(usize, usize) {
+    // can't be cancelled without flag
+    return match algorithm {
+        SimplifyAlgorithm::DouglasPeucker => douglas_peucker_with_progress(points, tolerance_deg, None, progress),
+        SimplifyAlgorithm::VisvalingamWhyatt => visvalingam_whyatt_with_progress(points, tolerance_deg, None, progress)
+    }.unwrap_or_default();
+}
+
+/// Like [`simplify`], but stops as soon as `cancel` is set.
+///
+/// `cancel`: cancellation flag, set by another thread
+pub fn simplify_cancellable(points: &[Point], tolerance_deg: f64, cancel: &AtomicBool) -> Result<Vec<Point>, Cancelled> {
+    return douglas_peucker(points, tolerance_deg, Some(cancel));
+}
+
+/// Drops points closer than `min_distance_m` to the previous kept point (radial distance).
+///
+/// Much cheaper than [`simplify`], it looks at every point once. It removes the jitter of
+/// standing or slow movement in 1 Hz GPS traces, which makes it a common pre-pass before
+/// Douglas–Peucker. The first and the last point are always kept.
+///
+/// `points`: points of the line
+///
+/// `min_distance_m`: minimum distance in meters between consecutive points of the result
+///
+/// ```
+/// use polyline_rust::{Point, thin};
+///
+/// // standing at a traffic light, the fix moves by a few decimeters
+/// let trace = vec![Point::new(52.5, 13.4), Point::new(52.500002, 13.4), Point::new(52.500001, 13.400003), Point::new(52.5001, 13.4)];
+/// assert_eq!(thin(&trace, 2.), vec![Point::new(52.5, 13.4), Point::new(52.5001, 13.4)]);
+/// ```
+pub fn thin(points: &[Point], min_distance_m: f64) -> Vec<Point> {
+    let mut thinned: Vec<Point> = Vec::new();
+    for (i, point) in points.iter().enumerate() {
+        let is_far = thinned.last().is_none_or(|previous| distance(previous, point) >= min_distance_m);
+        if is_far || i + 1 == points.len() {
+            thinned.push(*point);
+        }
+    }
+
+    return thinned;
+}
+
+/// Decodes, simplifies and re-encodes a polyline, see [`simplify`].
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format", malformed strings are
+/// rejected
+///
+/// `precision`: precision of the polyline, also used for the result
+///
+/// `tolerance_deg`: maximum distance of removed points from the simplified line
+///
+/// ```
+/// use polyline_rust::{Point, encode, simplify_encoded};
+///
+/// let simplified = simplify_encoded("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5, 10.);
+/// assert_eq!(simplified, Ok(encode(vec![Point::new(38.5, -120.2), Point::new(43.252, -126.453)], 5)));
+/// ```
+pub fn simplify_encoded(polyline: &str, precision: u32, tolerance_deg: f64) -> Result<String, DecodeError> {
+    return Ok(encode(simplify(&try_decode(polyline, precision)?, tolerance_deg), precision));
+}
+
+/// Ramer–Douglas–Peucker simplification in plain latitude/longitude space.
+///
+/// Keeps the first and the last point and every point farther than `tolerance_deg` from the
+/// simplified line between its kept neighbours. `cancel` is checked for every split range.
+pub(crate) fn douglas_peucker(points: &[Point], tolerance_deg: f64, cancel: Option<&AtomicBool>) -> Result<Vec<Point>, Cancelled> {
+    return douglas_peucker_with_progress(points, tolerance_deg, cancel, |_, _| {});
+}
+
+/// Like [`douglas_peucker`], and calls `progress` after every split range with the number of
+/// points known to be kept or removed.
+fn douglas_peucker_with_progress<F>(points: &[Point], tolerance_deg: f64, cancel: Option<&AtomicBool>, mut progress: F) -> Result<Vec<Point>, Cancelled>
+    where F: FnMut(usize, usize) {
+    if points.len() < 3 {
+        progress(points.len(), points.len());
+        return Ok(points.to_vec());
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    // explicit stack instead of recursion, long GPS traces would overflow the call stack
+    let mut ranges: Vec<(usize, usize)> = vec![(0, points.len() - 1)];
+    let mut decided = 2;
+    while let Some((first, last)) = ranges.pop() {
+        check(cancel)?;
+        let mut farthest = first;
+        let mut max_distance = 0.;
+        for i in first + 1..last {
+            let distance = segment_distance(&points[i], &points[first], &points[last]);
+            if distance > max_distance {
+                farthest = i;
+                max_distance = distance;
+            }
+        }
+
+        if max_distance > tolerance_deg {
+            keep[farthest] = true;
+            ranges.push((first, farthest));
+            ranges.push((farthest, last));
+            decided += 1;
+        } else {
+            // all points between are removed
+            decided += last - first - 1;
+        }
+        progress(decided, points.len());
+    }
+
+    return Ok(points.iter()
+        .zip(keep.iter())
+        .filter(|(_, keep)| **keep)
+        .map(|(point, _)| *point)
+        .collect());
+}
+
+/// Visvalingam–Whyatt simplification in plain latitude/longitude space.
+///
+/// Keeps the first and the last point; removes the point with the smallest effective area
+/// until every remaining point's area is at least `tolerance_deg²`. `cancel` is checked for
+/// every removed point.
+pub(crate) fn visvalingam_whyatt(points: &[Point], tolerance_deg: f64, cancel: Option<&AtomicBool>) -> Result<Vec<Point>, Cancelled> {
+    return visvalingam_whyatt_with_progress(points, tolerance_deg, cancel, |_, _| {});
+}
+
+/// Like [`visvalingam_whyatt`], and calls `progress` after every removed point with the number
+/// of removed points, and once with all points at the end.
+fn visvalingam_whyatt_with_progress<F>(points: &[Point], tolerance_deg: f64, cancel: Option<&AtomicBool>, mut progress: F) -> Result<Vec<Point>, Cancelled>
+    where F: FnMut(usize, usize) {
+    if points.len() < 3 {
+        progress(points.len(), points.len());
+        return Ok(points.to_vec());
+    }
+
+    let last = points.len() - 1;
+    let threshold = tolerance_deg * tolerance_deg;
+    // neighbours of the points that are still kept, as doubly linked list
+    let mut previous: Vec<usize> = (0..points.len()).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..points.len()).map(|i| (i + 1).min(last)).collect();
+    let mut areas: Vec<f64> = vec![f64::INFINITY; points.len()];
+    let mut keep = vec![true; points.len()];
+
+    let mut removed = 0;
+    let mut heap: BinaryHeap<Candidate> = BinaryHeap::with_capacity(points.len());
+    for i in 1..last {
+        areas[i] = triangle_area(&points[i - 1], &points[i], &points[i + 1]);
+        heap.push(Candidate { area: areas[i], index: i });
+    }
+
+    while let Some(Candidate { area, index }) = heap.pop() {
+        // entries are never updated in place, outdated ones are skipped instead
+        if !keep[index] || area != areas[index] {
+            continue;
+        }
+        if area >= threshold {
+            break;
+        }
+        check(cancel)?;
+
+        keep[index] = false;
+        removed += 1;
+        progress(removed, points.len());
+        let (before, after) = (previous[index], next[index]);
+        next[before] = after;
+        previous[after] = before;
+
+        for neighbour in [before, after] {
+            if neighbour == 0 || neighbour == last {
+                continue;
+            }
+            // a neighbour never gets a smaller area than the removed point, so removal order
+            // follows the visible importance of the points
+            let area = triangle_area(&points[previous[neighbour]], &points[neighbour], &points[next[neighbour]]).max(area);
+            areas[neighbour] = area;
+            heap.push(Candidate { area, index: neighbour });
+        }
+    }
+    // the remaining points are kept
+    progress(points.len(), points.len());
+
+    return Ok(points.iter()
+        .zip(keep.iter())
+        .filter(|(_, keep)| **keep)
+        .map(|(point, _)| *point)
+        .collect());
+}
+
+/// Point of the Visvalingam–Whyatt heap, ordered so the smallest area is popped first.
+struct Candidate {
+    area: f64,
+    index: usize
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        // reversed for a min-heap, ties removed front to back
+        return other.area.total_cmp(&self.area).then(other.index.cmp(&self.index));
+    }
+}
+
+/// Planar area in square degrees of the triangle `a`-`b`-`c`.
+fn triangle_area(a: &Point, b: &Point, c: &Point) -> f64 {
+    return ((b.latitude - a.latitude) * (c.longitude - a.longitude) - (c.latitude - a.latitude) * (b.longitude - a.longitude)).abs() / 2.;
+}
+
+/// Planar distance in degrees from `point` to the segment `a`-`b`.
+fn segment_distance(point: &Point, a: &Point, b: &Point) -> f64 {
+    let d_lat = b.latitude - a.latitude;
+    let d_lon = b.longitude - a.longitude;
+    let length_squared = d_lat * d_lat + d_lon * d_lon;
+
+    let t = if length_squared == 0. {
+        0.
+    } else {
+        (((point.latitude - a.latitude) * d_lat + (point.longitude - a.longitude) * d_lon) / length_squared).clamp(0., 1.)
+    };
+    return (point.latitude - (a.latitude + t * d_lat)).hypot(point.longitude - (a.longitude + t * d_lon));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use crate::{Point, Cancelled, DecodeError, simplify, simplify_encoded, thin};
+    use crate::{SimplifyAlgorithm, simplify_with, simplify_with_progress};
+    use crate::simplify::{douglas_peucker, visvalingam_whyatt};
+
+    #[test]
+    fn removes_points_within_tolerance() {
+        let points = vec![
+            Point::new(0., 0.),
+            Point::new(0.05, 1.),
+            Point::new(0., 2.),
+            Point::new(1., 3.),
+            Point::new(0., 4.)
+        ];
+        let simplified = douglas_peucker(&points, 0.1, None).unwrap();
+        assert_eq!(simplified, vec![Point::new(0., 0.), Point::new(0., 2.), Point::new(1., 3.), Point::new(0., 4.)]);
+    }
+
+    #[test]
+    fn short_lines() {
+        assert_eq!(douglas_peucker(&[], 1., None), Ok(vec![]));
+        assert_eq!(douglas_peucker(&[Point::new(1., 2.), Point::new(1., 2.)], 1., None), Ok(vec![Point::new(1., 2.), Point::new(1., 2.)]));
+    }
+
+    #[test]
+    fn cancelled() {
+        let points: Vec<Point> = (0..100).map(|i| Point::new((i % 7) as f64, i as f64)).collect();
+        assert_eq!(douglas_peucker(&points, 0.1, Some(&AtomicBool::new(true))), Err(Cancelled));
+        assert_eq!(douglas_peucker(&points[..2], 0.1, Some(&AtomicBool::new(true))).map(|points| points.len()), Ok(2));
+    }
+
+    #[test]
+    fn public_api() {
+        let points = vec![Point::new(0., 0.), Point::new(0.05, 1.), Point::new(0., 2.)];
+        assert_eq!(simplify(&points, 0.1), vec![Point::new(0., 0.), Point::new(0., 2.)]);
+        assert_eq!(simplify(&points, 0.01), points);
+        assert_eq!(simplify_encoded("_p~iF", 5, 1.), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+        assert_eq!(simplify_encoded("", 5, 1.), Ok(String::new()));
+    }
+
+    #[test]
+    fn progress() {
+        let points: Vec<Point> = (0..100).map(|i| Point::new((i % 7) as f64 * 0.01, i as f64)).collect();
+        for algorithm in [SimplifyAlgorithm::DouglasPeucker, SimplifyAlgorithm::VisvalingamWhyatt] {
+            let mut reported = vec![];
+            let simplified = simplify_with_progress(&points, 0.02, algorithm, |done, total| reported.push((done, total)));
+            assert_eq!(simplified, simplify_with(&points, 0.02, algorithm));
+            assert!(reported.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+            assert!(reported.iter().all(|(_, total)| *total == 100));
+            assert_eq!(reported.last(), Some(&(100, 100)));
+        }
+
+        let mut reported = vec![];
+        simplify_with_progress(&points[..1], 0.02, SimplifyAlgorithm::DouglasPeucker, |done, total| reported.push((done, total)));
+        assert_eq!(reported, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn visvalingam_whyatt_removes_small_triangles() {
+        let points = vec![
+            Point::new(0., 0.),
+            Point::new(0.05, 1.),
+            Point::new(0., 2.),
+            Point::new(1., 3.),
+            Point::new(0., 4.)
+        ];
+        // the triangle of the second point has an area of 0.05, the one of the fourth of 1
+        assert_eq!(visvalingam_whyatt(&points, 0.5, None), Ok(vec![Point::new(0., 0.), Point::new(0., 2.), Point::new(1., 3.), Point::new(0., 4.)]));
+        assert_eq!(simplify_with(&points, 0.3, SimplifyAlgorithm::VisvalingamWhyatt), simplify_with(&points, 0.1, SimplifyAlgorithm::DouglasPeucker));
+        assert_eq!(visvalingam_whyatt(&points, 0.1, None), Ok(points.clone()));
+        assert_eq!(visvalingam_whyatt(&points, 10., None), Ok(vec![Point::new(0., 0.), Point::new(0., 4.)]));
+        assert_eq!(visvalingam_whyatt(&points, 10., Some(&AtomicBool::new(true))), Err(Cancelled));
+    }
+
+    #[test]
+    fn visvalingam_whyatt_effective_area() {
+        // all three inner points have an area of 0.005; once one of them is gone the middle
+        // point forms a larger triangle and survives a tolerance of 0.1
+        let points = vec![Point::new(0., 0.), Point::new(0., 1.), Point::new(0.01, 1.5), Point::new(0., 2.), Point::new(0., 3.)];
+        assert_eq!(visvalingam_whyatt(&points, 0.1, None), Ok(vec![Point::new(0., 0.), Point::new(0.01, 1.5), Point::new(0., 3.)]));
+        assert_eq!(visvalingam_whyatt(&points, 0.2, None), Ok(vec![Point::new(0., 0.), Point::new(0., 3.)]));
+        assert_eq!(SimplifyAlgorithm::default(), SimplifyAlgorithm::DouglasPeucker);
+    }
+
+    #[test]
+    fn thin_keeps_ends() {
+        // 0.00001° of latitude is about 1.1 m
+        let points: Vec<Point> = [0., 1., 2., 3., 10., 11., 12.].iter().map(|i| Point::new(i * 0.00001, 0.)).collect();
+        assert_eq!(thin(&points, 3.), vec![points[0], points[3], points[4], points[6]]);
+        assert_eq!(thin(&points[..4], 3.5), vec![points[0], points[3]]);
+        assert_eq!(thin(&points[..2], 3.), points[..2].to_vec());
+        assert_eq!(thin(&points, 0.), points);
+        assert_eq!(thin(&[], 3.), vec![]);
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, Coordinate, round, write_element, MAX_GROUP_LENGTH};
+use crate::precision::scale;
+
+/// Polylines up to this many bytes are decoded by [`decode`].
+pub(crate) const SMALL_INPUT_LENGTH: usize = 100;
+
+/// Iterators with at most this many points are encoded by [`encode`].
+pub(crate) const SMALL_INPUT_POINTS: usize = 16;
+
+/// Longest encoding of a point, two groups of [`MAX_GROUP_LENGTH`] chunks.
+const MAX_POINT_LENGTH: usize = 2 * MAX_GROUP_LENGTH;
+
+/// Decodes short polylines without intermediate buffers on the heap.
+///
+/// The group values are collected on the stack, so the returned `Vec` is the only allocation.
+/// The points are bit-identical to the ones of the general decoder. Returns `None` for
+/// anything the general decoder has to deal with: malformed input, groups with more than 60
+/// bits and an odd number of values.
+pub(crate) fn decode(polyline: &str, precision: u32) -> Option<Vec<Point>> {
+    let bytes = polyline.as_bytes();
+    if bytes.len() > SMALL_INPUT_LENGTH {
+        return None;
+    }
+
+    let mut values = [0_i64; SMALL_INPUT_LENGTH];
+    let mut count = 0;
+    let mut value: u64 = 0;
+    let mut shift = 0;
+    for byte in bytes.iter() {
+        if !(b'?'..=b'~').contains(byte) || shift >= 60 {
+            return None;
+        }
+
+        let chunk = (*byte - 63) as u64;
+        value |= (chunk & 0x1f) << shift;
+        shift += 5;
+        if chunk & 0x20 == 0 {
+            let mut signed = value as i64;
+            if signed & 1 == 1 {
+                signed = !signed;
+            }
+            values[count] = signed >> 1;
+            count += 1;
+            value = 0;
+            shift = 0;
+        }
+    }
+    if shift != 0 || count % 2 == 1 {
+        return None;
+    }
+
+    // same float operations as `pair_coordinates`, so the results don't differ in the last bit
+    let scale = scale(precision);
+    let mut points: Vec<Point> = Vec::with_capacity(count / 2);
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    for pair in values[..count].chunks(2) {
+        latitude = round(latitude + round(pair[0] as f64 / scale, precision), precision);
+        longitude = round(longitude + round(pair[1] as f64 / scale, precision), precision);
+        points.push(Point::new(latitude, longitude));
+    }
+
+    return Some(points);
+}
+
+/// Encodes points into a stack buffer and allocates the `String` once at the end.
+///
+/// Meant for iterators with at most [`SMALL_INPUT_POINTS`] points, longer ones still work
+/// but spill the buffer into the `String` whenever it is full.
+pub(crate) fn encode<I>(points: I, precision: u32) -> String
+    where I: Iterator, I::Item: Coordinate {
+    let mut buffer = [0_u8; SMALL_INPUT_POINTS * MAX_POINT_LENGTH];
+    let mut length = 0;
+    let mut spilled = String::new();
+
+    let scale = crate::precision::scale(precision);
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    for point in points {
+        if buffer.len() - length < MAX_POINT_LENGTH {
+            spilled.push_str(as_str(&buffer[..length]));
+            length = 0;
+        }
+
+        length += write_element(point.lat() - latitude, scale, &mut buffer[length..]);
+        length += write_element(point.lng() - longitude, scale, &mut buffer[length..]);
+        latitude = point.lat();
+        longitude = point.lng();
+    }
+
+    if spilled.is_empty() {
+        return String::from(as_str(&buffer[..length]));
+    }
+    spilled.push_str(as_str(&buffer[..length]));
+    return spilled;
+}
+
+pub(crate) fn as_str(bytes: &[u8]) -> &str {
+    // only characters from `?` to `~` are written
+    return core::str::from_utf8(bytes).unwrap_or("");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::Point;
+    use crate::{encode_to, decode_coordinates, pair_coordinates};
+    use crate::small::{decode, encode};
+
+    fn general_decode(polyline: &str, precision: u32) -> Vec<Point> {
+        return pair_coordinates(&decode_coordinates(polyline, precision).0, precision);
+    }
+
+    fn general_encode(points: &[Point], precision: u32) -> String {
+        let mut encoded = String::new();
+        encode_to(points, precision, &mut encoded).unwrap();
+        return encoded;
+    }
+
+    #[test]
+    fn same_as_general_path() {
+        let polylines = ["", "??", "_p~iF~ps|U_ulLnnqC_mqNvxq`@", "mgjjAcfh~OgSySep@gS", "~d|cN`~oia@"];
+        for polyline in polylines.iter() {
+            for precision in 0..=9 {
+                let points = decode(polyline, precision).unwrap();
+                assert_eq!(points, general_decode(polyline, precision));
+                assert_eq!(encode(points.iter(), precision), general_encode(&points, precision));
+            }
+        }
+    }
+
+    #[test]
+    fn falls_back() {
+        assert_eq!(decode("_p~iF", 5), None);
+        assert_eq!(decode("_p~iF~ps|U_", 5), None);
+        assert_eq!(decode("_p~iF ", 5), None);
+        assert_eq!(decode(&"_p~iF~ps|U".repeat(11), 5), None);
+    }
+
+    #[test]
+    fn spills_long_input() {
+        let points: Vec<Point> = (0..100).map(|i| Point::new(i as f64 * 0.5, -(i as f64) * 0.25)).collect();
+        assert_eq!(encode(points.iter(), 5), general_encode(&points, 5));
+    }
+}
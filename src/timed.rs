@@ -0,0 +1,189 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crate::{DecodeError, push_element, push_integer, last_group_offset, round, scan_groups};
+use crate::precision::scale;
+
+/// Single coordinate of a GPS trace with elevation and time.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// usually meters above sea level, 0 if unknown
+    pub elevation: f64,
+    /// measure of the point, seconds since the Unix epoch
+    pub timestamp: i64
+}
+
+impl TimedPoint {
+    /// Creates a new `TimedPoint`.
+    pub fn new(latitude: f64, longitude: f64, elevation: f64, timestamp: i64) -> TimedPoint {
+        return TimedPoint {
+            latitude,
+            longitude,
+            elevation,
+            timestamp
+        };
+    }
+}
+
+/// Encodes a GPS trace with time; every point is a latitude, longitude, elevation and timestamp delta.
+///
+/// The timestamp is the fourth stream, after the streams of [`encode3`](crate::encode3). It's
+/// delta-encoded as integer, so timestamps are recovered exactly over the whole `i64` range;
+/// traces with a fix every second need a single byte per point for it.
+///
+/// `points`: points of the trace
+///
+/// `precision`: precision of latitude and longitude, usually 5 or 6, see [`encode`](crate::encode)
+///
+/// `elevation_precision`: number of decimals of the elevation, e.g. 2 for centimeters
+///
+/// ```
+/// use polyline_rust::{TimedPoint, encode_timed, decode_timed};
+///
+/// let points = vec![
+///     TimedPoint::new(47.3, 8.5, 408., 1_714_546_800),
+///     TimedPoint::new(47.3001, 8.5002, 408.5, 1_714_546_801)
+/// ];
+/// let polyline = encode_timed(&points, 5, 1);
+/// assert_eq!(decode_timed(&polyline, 5, 1), points);
+/// ```
+pub fn encode_timed<I>(points: I, precision: u32, elevation_precision: u32) -> String
+    where I: IntoIterator, I::Item: Borrow<TimedPoint> {
+    let mut encoded = String::new();
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    let mut elevation: f64 = 0.;
+    let mut timestamp: i64 = 0;
+
+    for point in points {
+        let point = point.borrow();
+        push_element(&mut encoded, point.latitude - latitude, precision);
+        push_element(&mut encoded, point.longitude - longitude, precision);
+        push_element(&mut encoded, point.elevation - elevation, elevation_precision);
+        push_integer(&mut encoded, point.timestamp.wrapping_sub(timestamp));
+
+        latitude = point.latitude;
+        longitude = point.longitude;
+        elevation = point.elevation;
+        timestamp = point.timestamp;
+    }
+
+    return encoded;
+}
+
+/// Decodes a GPS trace with time, see [`encode_timed`].
+///
+/// Like [`decode`](crate::decode) it stops at the first invalid character and silently
+/// drops an incomplete point at the end.
+///
+/// `polyline`: polyline string with elevation and time
+///
+/// `precision`: precision of latitude and longitude
+///
+/// `elevation_precision`: number of decimals of the elevation
+pub fn decode_timed(polyline: &str, precision: u32, elevation_precision: u32) -> Vec<TimedPoint> {
+    let (values, _) = decode_integers(polyline);
+    return tuple_values(&values, precision, elevation_precision);
+}
+
+/// Decodes a GPS trace with time and fails on malformed input, see [`try_decode`](crate::try_decode).
+///
+/// `polyline`: polyline string with elevation and time
+///
+/// `precision`: precision of latitude and longitude
+///
+/// `elevation_precision`: number of decimals of the elevation
+pub fn try_decode_timed(polyline: &str, precision: u32, elevation_precision: u32) -> Result<Vec<TimedPoint>, DecodeError> {
+    let (values, error) = decode_integers(polyline);
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    if values.len() % 4 != 0 {
+        // offset of the first group of the incomplete point
+        let mut offset = polyline.len();
+        for _ in 0..values.len() % 4 {
+            offset = last_group_offset(&polyline[..offset]);
+        }
+        return Err(DecodeError::DanglingCoordinate { offset });
+    }
+
+    return Ok(tuple_values(&values, precision, elevation_precision));
+}
+
+/// Unscaled values of all groups up to the first problem.
+fn decode_integers(polyline: &str) -> (Vec<i64>, Option<DecodeError>) {
+    let mut values: Vec<i64> = Vec::new();
+    let error = scan_groups(polyline, |value| {
+        // logical shift, `push_integer` uses all 64 bits
+        values.push((value >> 1) as i64 ^ -((value & 1) as i64));
+    }).err();
+
+    return (values, error);
+}
+
+/// Turns unscaled deltas into points with the same rounding as `pair_coordinates`.
+fn tuple_values(values: &[i64], precision: u32, elevation_precision: u32) -> Vec<TimedPoint> {
+    let elevation_scale = scale(elevation_precision);
+    let scale = scale(precision);
+
+    let mut points: Vec<TimedPoint> = Vec::with_capacity(values.len() / 4);
+    let mut latitude: f64 = 0.;
+    let mut longitude: f64 = 0.;
+    let mut elevation: f64 = 0.;
+    let mut timestamp: i64 = 0;
+    for tuple in values.chunks_exact(4) {
+        latitude = round(latitude + round(tuple[0] as f64 / scale, precision), precision);
+        longitude = round(longitude + round(tuple[1] as f64 / scale, precision), precision);
+        elevation = round(elevation + round(tuple[2] as f64 / elevation_scale, elevation_precision), elevation_precision);
+        timestamp = timestamp.wrapping_add(tuple[3]);
+        points.push(TimedPoint::new(latitude, longitude, elevation, timestamp));
+    }
+
+    return points;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point3, TimedPoint, DecodeError, encode3, encode_timed, decode_timed, try_decode_timed};
+
+    #[test]
+    fn roundtrip() {
+        let points = vec![
+            TimedPoint::new(49.94, 11.57, 461., 1_714_546_800),
+            TimedPoint::new(49.941, 11.571, 459.5, 1_714_546_805),
+            TimedPoint::new(49.9405, 11.57211, -3.07, 1_714_546_799)
+        ];
+        let polyline = encode_timed(&points, 5, 2);
+        assert!(polyline.starts_with(&encode3(vec![Point3::new(49.94, 11.57, 461.)], 5, 2)));
+        assert_eq!(try_decode_timed(&polyline, 5, 2), Ok(points));
+    }
+
+    #[test]
+    fn lossless_timestamps() {
+        // beyond the 53 bits an `f64` holds exactly
+        let points = vec![
+            TimedPoint::new(0., 0., 0., i64::MAX),
+            TimedPoint::new(0., 0., 0., i64::MIN),
+            TimedPoint::new(0., 0., 0., -1),
+            TimedPoint::new(0., 0., 0., 9_007_199_254_740_993)
+        ];
+        assert_eq!(try_decode_timed(&encode_timed(&points, 5, 0), 5, 0), Ok(points));
+        // one second later is one more byte
+        let trace = vec![TimedPoint::new(1., 2., 3., 100), TimedPoint::new(1., 2., 3., 101)];
+        assert_eq!(encode_timed(&trace, 5, 0).len(), encode_timed(&trace[..1], 5, 0).len() + 4);
+    }
+
+    #[test]
+    fn incomplete_point() {
+        let polyline = encode_timed(&[TimedPoint::new(1., 2., 3., 10), TimedPoint::new(4., 5., 6., 20)], 5, 0);
+        let truncated = &polyline[..polyline.len() - 1];
+        assert_eq!(decode_timed(truncated, 5, 0), vec![TimedPoint::new(1., 2., 3., 10)]);
+        assert_eq!(try_decode_timed(truncated, 5, 0), Err(DecodeError::DanglingCoordinate { offset: 10 }));
+        assert_eq!(try_decode_timed("_p~iF~ps|U", 5, 0), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::Point;
+use crate::geodesy::normalize_longitude;
+
+/// Moves all points by `d_latitude` and `d_longitude` degrees.
+///
+/// Longitudes are wrapped into [-180, 180), so a line can be moved across the antimeridian.
+/// Latitudes are clamped to [-90, 90].
+pub fn translate(points: &[Point], d_latitude: f64, d_longitude: f64) -> Vec<Point> {
+    return points.iter()
+        .map(|point| normalize(point.latitude + d_latitude, point.longitude + d_longitude))
+        .collect();
+}
+
+/// Rotates all points counterclockwise by `angle_deg` degrees around `center`.
+///
+/// The rotation happens on a plane tangent to `center` (longitudes are scaled by the cosine of
+/// the center's latitude), so shapes are preserved for lines spanning up to a few hundred
+/// kilometers. Lines crossing the antimeridian are handled.
+pub fn rotate_around(points: &[Point], center: &Point, angle_deg: f64) -> Vec<Point> {
+    let (sin, cos) = angle_deg.to_radians().sin_cos();
+
+    return map_local(points, center, |x, y| (x * cos - y * sin, x * sin + y * cos));
+}
+
+/// Scales the distance of all points to `center` by `factor`.
+///
+/// Like [`rotate_around`] this works on a plane tangent to `center`.
+pub fn scale_about(points: &[Point], center: &Point, factor: f64) -> Vec<Point> {
+    return map_local(points, center, |x, y| (x * factor, y * factor));
+}
+
+/// Applies `f` to the position of every point relative to `center` in degrees of latitude.
+fn map_local<F>(points: &[Point], center: &Point, f: F) -> Vec<Point>
+    where F: Fn(f64, f64) -> (f64, f64) {
+    let scale = center.latitude.to_radians().cos();
+
+    return points.iter().map(|point| {
+        let x = normalize_longitude(point.longitude - center.longitude) * scale;
+        let y = point.latitude - center.latitude;
+
+        let (x, y) = f(x, y);
+        return normalize(center.latitude + y, center.longitude + x / scale);
+    }).collect();
+}
+
+/// Creates a point with the longitude wrapped into [-180, 180) and the latitude clamped to
+/// [-90, 90].
+fn normalize(latitude: f64, longitude: f64) -> Point {
+    return Point::new(latitude.clamp(-90., 90.), normalize_longitude(longitude));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, translate, rotate_around, scale_about};
+
+    fn assert_close(actual: Vec<Point>, expected: Vec<Point>) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a.latitude - e.latitude).abs() < 1e-9, "{:?} != {:?}", a, e);
+            assert!((a.longitude - e.longitude).abs() < 1e-9, "{:?} != {:?}", a, e);
+        }
+    }
+
+    #[test]
+    fn translate_across_antimeridian() {
+        assert_close(translate(&[Point::new(10., 179.5), Point::new(89.5, 0.)], 1., 1.), vec![
+            Point::new(11., -179.5),
+            Point::new(90., 1.)
+        ]);
+    }
+
+    #[test]
+    fn rotate_quarter() {
+        let center = Point::new(0., 0.);
+        assert_close(rotate_around(&[Point::new(0., 1.), Point::new(1., 0.)], &center, 90.), vec![
+            Point::new(1., 0.),
+            Point::new(0., -1.)
+        ]);
+    }
+
+    #[test]
+    fn rotate_around_antimeridian() {
+        let center = Point::new(0., 180.);
+        assert_close(rotate_around(&[Point::new(0., 179.)], &center, 180.), vec![
+            Point::new(0., -179.)
+        ]);
+    }
+
+    #[test]
+    fn scale() {
+        let center = Point::new(60., 10.);
+        assert_close(scale_about(&[Point::new(61., 12.), Point::new(60., 10.)], &center, 2.), vec![
+            Point::new(62., 14.),
+            Point::new(60., 10.)
+        ]);
+    }
+}
@@ -0,0 +1,74 @@
+//! The next version of the codec API: fallible, slice-taking and driven by options.
+//!
+//! The functions at the crate root stay as they are, so code can move over one call site at
+//! a time. Only the shorthands with a fixed precision (`encode5`, `decode6`, ...) are
+//! deprecated, the precision belongs into the options or a [`Codec`](crate::Codec).
+//!
+//! | crate root                        | `v2`                                            |
+//! |-----------------------------------|-------------------------------------------------|
+//! | `encode(points, 5)`               | `v2::encode(&points, 5)?`                       |
+//! | `try_encode(points, 5)`           | `v2::encode(&points, 5)?`                       |
+//! | `decode(polyline, 5)`             | `v2::decode(polyline, &DecodeOptions::new(5))?` |
+//! | `try_decode(polyline, 5)`         | `v2::decode(polyline, &DecodeOptions::new(5))?` |
+//! | `decode_with(polyline, &options)` | `v2::decode(polyline, &options)?`               |
+//!
+//! The module is deliberately narrower than a complete second API: it only holds the two
+//! entry points and re-exports the option types. [`try_encode`], [`try_decode`](crate::try_decode),
+//! [`encode_with`](crate::encode_with) and [`decode_with`] already return `Result` and stay
+//! at the crate root, `v2` forwards to them. `encode` and `decode` at the crate root aren't
+//! deprecated either: the warnings would hit every existing call site at once, which is the
+//! flag day this module is meant to avoid.
+//!
+//! ```
+//! use polyline_rust::{Point, DecodeOptions, v2};
+//!
+//! let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+//! let polyline = v2::encode(&points, 5).unwrap();
+//! assert_eq!(v2::decode(&polyline, &DecodeOptions::new(5)), Ok(points));
+//!
+//! assert!(v2::encode(&[Point::new(f64::NAN, 0.)], 5).is_err());
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, Coordinate, DecodeError, ValidationError, decode_with, try_encode};
+
+pub use crate::{EncodeOptions, DecodeOptions, OddTrailingPolicy, AxisOrder};
+
+/// Encodes coordinates to the "Encoded Polyline Algorithm Format", see [`encode`](crate::encode).
+///
+/// `points`: points of the polyline
+///
+/// `precision`: usually 5 or 6, see [`encode`](crate::encode)
+///
+/// Rejects coordinates that aren't on earth instead of encoding garbage, see [`try_encode`].
+pub fn encode<C>(points: &[C], precision: u32) -> Result<String, ValidationError>
+    where C: Coordinate {
+    return try_encode(points, precision);
+}
+
+/// Decodes coordinates from the "Encoded Polyline Algorithm Format" and runs the checks
+/// enabled in `options`, see [`decode_with`].
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `options`: precision and checks, see [`DecodeOptions`]
+pub fn decode(polyline: &str, options: &DecodeOptions) -> Result<Vec<Point>, DecodeError> {
+    return decode_with(polyline, options);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, DecodeError, ValidationError, v2};
+    use crate::v2::DecodeOptions;
+
+    #[test]
+    fn fallible() {
+        assert_eq!(v2::encode(&[Point::new(38.5, -120.2), Point::new(0., f64::INFINITY)], 5), Err(ValidationError::NotFinite { index: 1 }));
+        assert_eq!(v2::encode(&[Point::new(38.5, 181.)], 5), Err(ValidationError::OutOfRange { index: 0, latitude: 38.5, longitude: 181. }));
+        assert_eq!(v2::encode::<Point>(&[], 5), Ok(String::new()));
+        assert_eq!(v2::decode("_p~iF", &DecodeOptions::new(5)), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+}
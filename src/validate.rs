@@ -0,0 +1,220 @@
+use std::sync::atomic::AtomicBool;
+
+use crate::{Point, Cancelled, ValidationError, decode, scan_groups};
+use crate::cancel::check;
+use crate::geodesy::distance;
+
+/// Polylines longer than this many bytes are reported as suspiciously long by
+/// [`validate_batch`]. That's roughly 20,000 points or more.
+pub const SUSPICIOUS_LENGTH: usize = 200_000;
+
+/// Maximum number of offsets stored in [`IssueCount::samples`].
+const MAX_SAMPLES: usize = 10;
+
+/// Number of polylines with a certain issue, see [`ValidationReport`].
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct IssueCount {
+    /// number of affected polylines
+    pub count: usize,
+    /// positions in the input of the first few affected polylines
+    pub samples: Vec<usize>
+}
+
+impl IssueCount {
+    fn add(&mut self, offset: usize) {
+        self.count += 1;
+        if self.samples.len() < MAX_SAMPLES {
+            self.samples.push(offset);
+        }
+    }
+}
+
+/// Summary of a batch of polylines, see [`validate_batch`].
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct ValidationReport {
+    /// number of checked polylines
+    pub total: usize,
+    /// number of polylines that are neither empty, malformed nor out of range
+    pub valid: usize,
+    /// empty strings
+    pub empty: IssueCount,
+    /// strings that are not in "Encoded Polyline Algorithm Format"
+    pub malformed: IssueCount,
+    /// polylines with latitudes outside of [-90, 90] or longitudes outside of [-180, 180]
+    pub out_of_range: IssueCount,
+    /// polylines longer than [`SUSPICIOUS_LENGTH`] bytes; these are also counted as valid
+    /// if there is nothing else wrong with them
+    pub too_long: IssueCount
+}
+
+/// Checks a batch of encoded polylines and summarizes the issues found.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with
+pub fn validate_batch<I, S>(polylines: I, precision: u32) -> ValidationReport
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    // can't be cancelled without flag
+    return validate(polylines, precision, None).unwrap_or_default();
+}
+
+/// Like [`validate_batch`], but stops as soon as `cancel` is set, e.g. because the client of
+/// a web request disconnected.
+///
+/// The flag is checked before every polyline.
+///
+/// `polylines`: polyline strings in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: precision the polylines were encoded with
+///
+/// `cancel`: cancellation flag, set by another thread
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use polyline_rust::{Cancelled, validate_batch_cancellable};
+///
+/// let cancel = AtomicBool::new(false);
+/// assert_eq!(validate_batch_cancellable(vec!["??"], 5, &cancel).map(|report| report.valid), Ok(1));
+/// cancel.store(true, Ordering::Relaxed);
+/// assert_eq!(validate_batch_cancellable(vec!["??"], 5, &cancel), Err(Cancelled));
+/// ```
+pub fn validate_batch_cancellable<I, S>(polylines: I, precision: u32, cancel: &AtomicBool) -> Result<ValidationReport, Cancelled>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    return validate(polylines, precision, Some(cancel));
+}
+
+fn validate<I, S>(polylines: I, precision: u32, cancel: Option<&AtomicBool>) -> Result<ValidationReport, Cancelled>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    let mut report = ValidationReport::default();
+
+    for (offset, polyline) in polylines.into_iter().enumerate() {
+        check(cancel)?;
+        let polyline = polyline.as_ref();
+        report.total += 1;
+
+        if polyline.len() > SUSPICIOUS_LENGTH {
+            report.too_long.add(offset);
+        }
+
+        if polyline.is_empty() {
+            report.empty.add(offset);
+        } else if !is_well_formed(polyline) {
+            report.malformed.add(offset);
+        } else if !is_in_range(polyline, precision) {
+            report.out_of_range.add(offset);
+        } else {
+            report.valid += 1;
+        }
+    }
+
+    return Ok(report);
+}
+
+/// Checks that all coordinates are finite and in range.
+///
+/// `points`: the points to check
+///
+/// Returns the first invalid point as error.
+pub fn validate_points(points: &[Point]) -> Result<(), ValidationError> {
+    for (index, point) in points.iter().enumerate() {
+        if !point.latitude.is_finite() || !point.longitude.is_finite() {
+            return Err(ValidationError::NotFinite { index });
+        }
+        if !(-90. ..=90.).contains(&point.latitude) || !(-180. ..=180.).contains(&point.longitude) {
+            return Err(ValidationError::OutOfRange { index, latitude: point.latitude, longitude: point.longitude });
+        }
+    }
+
+    return Ok(());
+}
+
+/// Returns the indices of all points farther than `max_jump_m` meters away from their
+/// predecessor.
+///
+/// Huge jumps between consecutive points usually mean the polyline got corrupted or was
+/// decoded with the wrong precision.
+pub fn find_jumps(points: &[Point], max_jump_m: f64) -> Vec<usize> {
+    let mut jumps: Vec<usize> = Vec::new();
+    for (i, segment) in points.windows(2).enumerate() {
+        if distance(&segment[0], &segment[1]) > max_jump_m {
+            jumps.push(i + 1);
+        }
+    }
+
+    return jumps;
+}
+
+/// Checks that all characters are in the polyline range, every group is terminated
+/// and fits into 64 bits, and that there is an even number of coordinates.
+pub(crate) fn is_well_formed(polyline: &str) -> bool {
+    return scan_groups(polyline, |_| {}).is_ok_and(|coordinates| coordinates % 2 == 0);
+}
+
+fn is_in_range(polyline: &str, precision: u32) -> bool {
+    return decode(polyline, precision).iter().all(|point| {
+        point.latitude.abs() <= 90. && point.longitude.abs() <= 180.
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, ValidationError, try_decode, validate_batch, validate_points, find_jumps, SUSPICIOUS_LENGTH};
+    use crate::validate::is_well_formed;
+
+    #[test]
+    fn valid_batch() {
+        let report = validate_batch(vec!["_p~iF~ps|U_ulLnnqC_mqNvxq`@", "??"], 5);
+        assert_eq!(report.total, 2);
+        assert_eq!(report.valid, 2);
+        assert_eq!(report.malformed.count, 0);
+    }
+
+    #[test]
+    fn issues_with_offsets() {
+        let long = "??".repeat(SUSPICIOUS_LENGTH);
+        let report = validate_batch(vec![
+            "",
+            "_p~iF~ps|U_ulLnnqC_mqNvxq`",
+            "??",
+            "a",
+            "_p~iF ~ps|U",
+            "_gjaR?",
+            long.as_str()
+        ], 5);
+
+        assert_eq!(report.total, 7);
+        assert_eq!(report.valid, 2);
+        assert_eq!(report.empty.samples, vec![0]);
+        assert_eq!(report.malformed.count, 3);
+        assert_eq!(report.malformed.samples, vec![1, 3, 4]);
+        assert_eq!(report.out_of_range.samples, vec![5]);
+        assert_eq!(report.too_long.samples, vec![6]);
+    }
+
+    #[test]
+    fn well_formed_like_try_decode() {
+        for polyline in ["", "??", "_p~iF~ps|U_ulLnnqC", "_p~iF~ps|U_ulL", "_p~iF~ps|U_", "_p~iF ~ps|U", "_p~iFä~ps|U",
+                         "~~~~~~~~~~~~N?", "~~~~~~~~~~~~O?", "~~~~~~~~~~~~~?"] {
+            assert_eq!(is_well_formed(polyline), try_decode(polyline, 5).is_ok(), "{}", polyline);
+        }
+    }
+
+    #[test]
+    fn jumps() {
+        let points = vec![
+            Point::new(48.2, 16.3),
+            Point::new(48.2, 16.301),
+            Point::new(4.82, 1.63),
+            Point::new(4.82, 1.631)
+        ];
+        assert_eq!(find_jumps(&points, 1000.), vec![2]);
+        assert_eq!(find_jumps(&points, 10_000_000.), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn points() {
+        assert_eq!(validate_points(&[Point::new(90., -180.), Point::new(-90., 180.)]), Ok(()));
+        assert_eq!(validate_points(&[Point::new(0., 0.), Point::new(1234.5, 0.)]), Err(ValidationError::OutOfRange { index: 1, latitude: 1234.5, longitude: 0. }));
+        assert_eq!(validate_points(&[Point::new(f64::NAN, 0.)]), Err(ValidationError::NotFinite { index: 0 }));
+    }
+}
@@ -0,0 +1,100 @@
+//! Bindings for JavaScript with `wasm-bindgen`.
+//!
+//! The browser runs the same codec as a Rust backend, so both decode a polyline into
+//! bit-identical coordinates. Points are passed as a `Float64Array` of interleaved latitudes and
+//! longitudes, `[lat0, lng0, lat1, lng1, ...]`, instead of an object per point.
+//!
+//! In JavaScript:
+//!
+//! ```text
+//! const polyline = encode(new Float64Array([38.5, -120.2, 40.7, -120.95]), 5);
+//! const coordinates = decode(polyline, 5); // Float64Array [38.5, -120.2, 40.7, -120.95]
+//! ```
+//!
+//! The functions are exported from the WebAssembly module of the `cdylib` crate that depends on
+//! this crate with the `wasm` feature, e.g. one built with `wasm-pack` that contains
+//! `pub use polyline_rust::wasm::*;`.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use wasm_bindgen::prelude::{wasm_bindgen, JsError};
+
+use crate::{Point, DecodeError, try_decode};
+
+/// Encodes interleaved coordinates to the "Encoded Polyline Algorithm Format", see
+/// [`encode`](crate::encode).
+///
+/// `coordinates`: latitude and longitude of every point, one after the other
+///
+/// `precision`: usually 5 or 6, see [`encode`](crate::encode)
+///
+/// Throws an `Error` for an odd number of coordinates.
+#[wasm_bindgen]
+pub fn encode(coordinates: &[f64], precision: u32) -> Result<String, JsError> {
+    return match encode_flat(coordinates, precision) {
+        Some(polyline) => Ok(polyline),
+        None => Err(JsError::new("odd number of coordinates, expected a latitude and a longitude per point"))
+    };
+}
+
+/// Decodes a polyline into interleaved coordinates, see [`try_decode`].
+///
+/// `polyline`: polyline string in "Encoded Polyline Algorithm Format"
+///
+/// `precision`: usually 5 or 6, see [`decode`](crate::decode)
+///
+/// Throws an `Error` with the [`DecodeError`] as message for malformed polylines.
+#[wasm_bindgen]
+pub fn decode(polyline: &str, precision: u32) -> Result<Vec<f64>, JsError> {
+    return decode_flat(polyline, precision).map_err(|error| JsError::new(&error.to_string()));
+}
+
+/// [`encode`] without JavaScript types, `None` for an odd number of coordinates.
+fn encode_flat(coordinates: &[f64], precision: u32) -> Option<String> {
+    if coordinates.len() % 2 == 1 {
+        return None;
+    }
+
+    return Some(crate::encode(coordinates.chunks_exact(2).map(|pair| Point::new(pair[0], pair[1])), precision));
+}
+
+/// [`decode`] without JavaScript types.
+pub(crate) fn decode_flat(polyline: &str, precision: u32) -> Result<Vec<f64>, DecodeError> {
+    let points = try_decode(polyline, precision)?;
+
+    let mut coordinates: Vec<f64> = Vec::with_capacity(points.len() * 2);
+    for point in points.iter() {
+        coordinates.push(point.latitude);
+        coordinates.push(point.longitude);
+    }
+
+    return Ok(coordinates);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, DecodeError, encode, decode};
+    use crate::wasm::{encode_flat, decode_flat};
+
+    #[test]
+    fn same_as_codec() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95), Point::new(43.252, -126.453)];
+        let coordinates: Vec<f64> = points.iter().flat_map(|point| [point.latitude, point.longitude]).collect();
+        for precision in [5, 6] {
+            let polyline = encode_flat(&coordinates, precision).unwrap();
+            assert_eq!(polyline, encode(&points, precision));
+
+            let decoded = decode_flat(&polyline, precision).unwrap();
+            let expected: Vec<f64> = decode(&polyline, precision).iter().flat_map(|point| [point.latitude, point.longitude]).collect();
+            assert_eq!(decoded.iter().map(|value| value.to_bits()).collect::<Vec<u64>>(), expected.iter().map(|value| value.to_bits()).collect::<Vec<u64>>());
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(encode_flat(&[38.5, -120.2, 40.7], 5), None);
+        assert_eq!(encode_flat(&[], 5), Some(String::new()));
+        assert_eq!(decode_flat("_p~iF~ps|U_ulL", 5), Err(DecodeError::DanglingCoordinate { offset: 10 }));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{Point, DecodeError, FormatError, encode, try_decode};
+
+const LITTLE_ENDIAN: u8 = 1;
+const BIG_ENDIAN: u8 = 0;
+const GEOMETRY_TYPE_LINE_STRING: u32 = 2;
+/// EWKB flags as written by PostGIS' `ST_AsEWKB`.
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// Appends the points as little endian WKB `LineString` (x = longitude, y = latitude).
+pub(crate) fn write_line_string(buffer: &mut Vec<u8>, points: &[Point]) {
+    buffer.reserve(9 + 16 * points.len());
+    buffer.push(LITTLE_ENDIAN);
+    buffer.extend_from_slice(&GEOMETRY_TYPE_LINE_STRING.to_le_bytes());
+    buffer.extend_from_slice(&(points.len() as u32).to_le_bytes());
+    for point in points.iter() {
+        buffer.extend_from_slice(&point.longitude.to_le_bytes());
+        buffer.extend_from_slice(&point.latitude.to_le_bytes());
+    }
+}
+
+/// Converts points into a little endian WKB `LineString`, as returned by PostGIS' `ST_AsBinary`.
+///
+/// WKB coordinates are `x = longitude, y = latitude`, the order is swapped accordingly.
+pub fn to_wkb(points: &[Point]) -> Vec<u8> {
+    let mut wkb: Vec<u8> = Vec::new();
+    write_line_string(&mut wkb, points);
+
+    return wkb;
+}
+
+/// Reads the points of a WKB `LineString`.
+///
+/// Both byte orders are supported. Z and M values of ISO WKB (types 1002, 2002, 3002) and
+/// EWKB (`ST_AsEWKB`, including an SRID) are ignored.
+///
+/// `wkb`: the `LineString`; trailing bytes are rejected
+pub fn from_wkb(wkb: &[u8]) -> Result<Vec<Point>, FormatError> {
+    let mut reader = Reader { bytes: wkb, position: 0, little_endian: true };
+
+    reader.little_endian = match reader.bytes.first() {
+        Some(&LITTLE_ENDIAN) => true,
+        Some(&BIG_ENDIAN) => false,
+        _ => return Err(FormatError::Syntax { offset: 0 })
+    };
+    reader.position = 1;
+
+    let geometry_type = reader.u32()?;
+    let mut dimensions = 2;
+    if geometry_type & EWKB_Z != 0 {
+        dimensions += 1;
+    }
+    if geometry_type & EWKB_M != 0 {
+        dimensions += 1;
+    }
+    if geometry_type & EWKB_SRID != 0 {
+        reader.u32()?;
+    }
+    let iso_type = geometry_type & !(EWKB_Z | EWKB_M | EWKB_SRID);
+    dimensions += match iso_type {
+        2 => 0,
+        1002 | 2002 => 1,
+        3002 => 2,
+        _ => return Err(FormatError::NotALineString)
+    };
+    if dimensions > 4 {
+        return Err(FormatError::Syntax { offset: 1 });
+    }
+
+    let count = reader.u32()? as usize;
+    // don't trust the count for the allocation, it could claim billions of points
+    let available = (reader.bytes.len() - reader.position) / (8 * dimensions);
+    let mut points: Vec<Point> = Vec::with_capacity(count.min(available));
+    for index in 0..count {
+        let longitude = reader.f64()?;
+        let latitude = reader.f64()?;
+        for _ in 2..dimensions {
+            reader.f64()?;
+        }
+
+        if !(-90. ..=90.).contains(&latitude) || !(-180. ..=180.).contains(&longitude) {
+            return Err(FormatError::InvalidCoordinate { index });
+        }
+        points.push(Point::new(latitude, longitude));
+    }
+
+    if reader.position < reader.bytes.len() {
+        return Err(FormatError::Syntax { offset: reader.position });
+    }
+
+    return Ok(points);
+}
+
+/// Encodes a WKB `LineString` to the "Encoded Polyline Algorithm Format".
+///
+/// `wkb`: the `LineString`, see [`from_wkb`]
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+pub fn encode_wkb(wkb: &[u8], precision: u32) -> Result<String, FormatError> {
+    return Ok(encode(from_wkb(wkb)?, precision));
+}
+
+/// Decodes a polyline into a WKB `LineString`, see [`to_wkb`].
+///
+/// `polyline`: encoded polyline string, malformed strings are rejected
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+///
+/// ```
+/// use polyline_rust::{decode_to_wkb, encode_wkb};
+///
+/// let wkb = decode_to_wkb("_p~iF~ps|U_ulLnnqC", 5).unwrap();
+/// assert_eq!(wkb.len(), 9 + 2 * 16);
+/// assert_eq!(encode_wkb(&wkb, 5).unwrap(), "_p~iF~ps|U_ulLnnqC");
+/// ```
+pub fn decode_to_wkb(polyline: &str, precision: u32) -> Result<Vec<u8>, DecodeError> {
+    return Ok(to_wkb(&try_decode(polyline, precision)?));
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    little_endian: bool
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], FormatError> {
+        let mut value = [0; N];
+        let bytes = self.bytes.get(self.position..self.position + N)
+            .ok_or(FormatError::Syntax { offset: self.position })?;
+        value.copy_from_slice(bytes);
+        self.position += N;
+
+        return Ok(value);
+    }
+
+    fn u32(&mut self) -> Result<u32, FormatError> {
+        let bytes = self.take::<4>()?;
+        return Ok(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) });
+    }
+
+    fn f64(&mut self) -> Result<f64, FormatError> {
+        let bytes = self.take::<8>()?;
+        return Ok(if self.little_endian { f64::from_le_bytes(bytes) } else { f64::from_be_bytes(bytes) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, FormatError, to_wkb, from_wkb};
+    use crate::wkb::write_line_string;
+
+    #[test]
+    fn line_string() {
+        let mut buffer: Vec<u8> = Vec::new();
+        write_line_string(&mut buffer, &[Point::new(1., 2.)]);
+        assert_eq!(buffer[..9], [1, 2, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(buffer[9..17], 2_f64.to_le_bytes());
+        assert_eq!(buffer[17..], 1_f64.to_le_bytes());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95)];
+        assert_eq!(from_wkb(&to_wkb(&points)), Ok(points));
+        assert_eq!(from_wkb(&to_wkb(&[])), Ok(vec![]));
+    }
+
+    #[test]
+    fn big_endian_ewkb_with_srid_and_z() {
+        let mut wkb: Vec<u8> = vec![0];
+        wkb.extend_from_slice(&(0x8000_0000_u32 | 0x2000_0000 | 2).to_be_bytes());
+        wkb.extend_from_slice(&4326_u32.to_be_bytes());
+        wkb.extend_from_slice(&1_u32.to_be_bytes());
+        for value in [16.37, 48.2, 171.5].iter() {
+            wkb.extend_from_slice(&f64::to_be_bytes(*value));
+        }
+        assert_eq!(from_wkb(&wkb), Ok(vec![Point::new(48.2, 16.37)]));
+    }
+
+    #[test]
+    fn iso_zm() {
+        let mut wkb: Vec<u8> = vec![1];
+        wkb.extend_from_slice(&3002_u32.to_le_bytes());
+        wkb.extend_from_slice(&1_u32.to_le_bytes());
+        for value in [1., 2., 3., 4.].iter() {
+            wkb.extend_from_slice(&f64::to_le_bytes(*value));
+        }
+        assert_eq!(from_wkb(&wkb), Ok(vec![Point::new(2., 1.)]));
+    }
+
+    #[test]
+    fn errors() {
+        let wkb = to_wkb(&[Point::new(1., 2.)]);
+        assert_eq!(from_wkb(&[]), Err(FormatError::Syntax { offset: 0 }));
+        assert_eq!(from_wkb(&wkb[..20]), Err(FormatError::Syntax { offset: 17 }));
+        assert_eq!(from_wkb(&[&wkb[..], &[0]].concat()), Err(FormatError::Syntax { offset: 25 }));
+        assert_eq!(from_wkb(&[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]), Err(FormatError::NotALineString));
+        assert_eq!(from_wkb(&to_wkb(&[Point::new(100., 2.)])), Err(FormatError::InvalidCoordinate { index: 0 }));
+
+        let mut huge_count = wkb.clone();
+        huge_count[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(from_wkb(&huge_count), Err(FormatError::Syntax { offset: 25 }));
+    }
+}
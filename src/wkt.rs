@@ -0,0 +1,250 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Point, DecodeError, FormatError, encode, try_decode};
+use crate::format::write_fixed;
+
+/// Converts points into a WKT `LINESTRING`, `LINESTRING EMPTY` if there are no points.
+///
+/// WKT coordinates are `longitude latitude`, the order is swapped accordingly.
+///
+/// `points`: points of the polyline
+///
+/// `precision`: number of decimals of the coordinates, see [`to_csv`](crate::to_csv)
+///
+/// ```
+/// use polyline_rust::{Point, to_wkt};
+///
+/// let wkt = to_wkt(&[Point::new(38.5, -120.2), Point::new(40.7, -120.95)], 5);
+/// assert_eq!(wkt, "LINESTRING(-120.20000 38.50000,-120.95000 40.70000)");
+/// ```
+pub fn to_wkt(points: &[Point], precision: u32) -> String {
+    if points.is_empty() {
+        return String::from("LINESTRING EMPTY");
+    }
+
+    let mut wkt = String::from("LINESTRING(");
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            wkt.push(',');
+        }
+        // writing into a `String` never fails
+        let _ = write_fixed(&mut wkt, point.longitude, precision);
+        wkt.push(' ');
+        let _ = write_fixed(&mut wkt, point.latitude, precision);
+    }
+    wkt.push(')');
+
+    return wkt;
+}
+
+/// Reads the points of a WKT `LINESTRING`.
+///
+/// Keywords are case-insensitive, an EWKT `SRID=...;` prefix is skipped and `Z`, `M` and `ZM`
+/// values are ignored, so the output of PostGIS' `ST_AsText` and `ST_AsEWKT` can be used as is.
+///
+/// `wkt`: the `LINESTRING`
+pub fn from_wkt(wkt: &str) -> Result<Vec<Point>, FormatError> {
+    let mut parser = Parser { bytes: wkt.as_bytes(), position: 0 };
+
+    parser.whitespace();
+    if parser.keyword("SRID") {
+        parser.expect(b'=')?;
+        parser.number()?;
+        parser.expect(b';')?;
+        parser.whitespace();
+    }
+
+    let start = parser.position;
+    let word = parser.word();
+    if word.is_empty() {
+        return Err(FormatError::Syntax { offset: start });
+    }
+    if !word.eq_ignore_ascii_case("LINESTRING") {
+        return Err(FormatError::NotALineString);
+    }
+
+    parser.whitespace();
+    let dimensions_start = parser.position;
+    let dimensions = parser.word();
+    if !["", "Z", "M", "ZM"].iter().any(|allowed| dimensions.eq_ignore_ascii_case(allowed)) {
+        if dimensions.eq_ignore_ascii_case("EMPTY") {
+            return parser.end().map(|_| Vec::new());
+        }
+        return Err(FormatError::Syntax { offset: dimensions_start });
+    }
+    parser.whitespace();
+    if parser.keyword("EMPTY") {
+        return parser.end().map(|_| Vec::new());
+    }
+
+    parser.expect(b'(')?;
+    let mut points: Vec<Point> = Vec::new();
+    loop {
+        let index = points.len();
+        let mut values: Vec<f64> = Vec::with_capacity(4);
+        parser.whitespace();
+        while let Some(b'0'..=b'9') | Some(b'-') | Some(b'+') | Some(b'.') = parser.bytes.get(parser.position) {
+            values.push(parser.number()?);
+            parser.whitespace();
+        }
+
+        if values.len() < 2 || values.len() > 4 {
+            return Err(FormatError::InvalidCoordinate { index });
+        }
+        let (longitude, latitude) = (values[0], values[1]);
+        if !(-90. ..=90.).contains(&latitude) || !(-180. ..=180.).contains(&longitude) {
+            return Err(FormatError::InvalidCoordinate { index });
+        }
+        points.push(Point::new(latitude, longitude));
+
+        match parser.bytes.get(parser.position) {
+            Some(b',') => parser.position += 1,
+            Some(b')') => {
+                parser.position += 1;
+                break;
+            },
+            _ => return Err(FormatError::Syntax { offset: parser.position })
+        }
+    }
+    parser.end()?;
+
+    return Ok(points);
+}
+
+/// Encodes a WKT `LINESTRING` to the "Encoded Polyline Algorithm Format".
+///
+/// `wkt`: the `LINESTRING`, see [`from_wkt`]
+///
+/// `precision`: usually 5 or 6, see [`encode`]
+///
+/// ```
+/// use polyline_rust::encode_wkt;
+///
+/// let polyline = encode_wkt("LINESTRING(-120.2 38.5, -120.95 40.7)", 5).unwrap();
+/// assert_eq!(polyline, "_p~iF~ps|U_ulLnnqC");
+/// ```
+pub fn encode_wkt(wkt: &str, precision: u32) -> Result<String, FormatError> {
+    return Ok(encode(from_wkt(wkt)?, precision));
+}
+
+/// Decodes a polyline into a WKT `LINESTRING`, see [`to_wkt`].
+///
+/// `polyline`: encoded polyline string, malformed strings are rejected
+///
+/// `precision`: usually 5 or 6, see [`encode`]; also the number of decimals in the output
+///
+/// ```
+/// use polyline_rust::decode_to_wkt;
+///
+/// let wkt = decode_to_wkt("_p~iF~ps|U_ulLnnqC", 5).unwrap();
+/// assert_eq!(wkt, "LINESTRING(-120.20000 38.50000,-120.95000 40.70000)");
+/// ```
+pub fn decode_to_wkt(polyline: &str, precision: u32) -> Result<String, DecodeError> {
+    return Ok(to_wkt(&try_decode(polyline, precision)?, precision));
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+
+impl<'a> Parser<'a> {
+    /// Reads a run of ASCII letters.
+    fn word(&mut self) -> &'a str {
+        let start = self.position;
+        while self.bytes.get(self.position).is_some_and(u8::is_ascii_alphabetic) {
+            self.position += 1;
+        }
+        // only ASCII letters were consumed
+        return core::str::from_utf8(&self.bytes[start..self.position]).unwrap_or("");
+    }
+
+    /// Consumes `keyword` if the input continues with it, ignoring case.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let end = self.position + keyword.len();
+        match self.bytes.get(self.position..end) {
+            Some(bytes) if bytes.eq_ignore_ascii_case(keyword.as_bytes()) => {
+                self.position = end;
+                return true;
+            },
+            _ => return false
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, FormatError> {
+        let start = self.position;
+        while let Some(b'0'..=b'9') | Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E') = self.bytes.get(self.position) {
+            self.position += 1;
+        }
+        // only ASCII characters were consumed
+        let text = core::str::from_utf8(&self.bytes[start..self.position]).unwrap_or("");
+        return text.parse::<f64>().map_err(|_| FormatError::Syntax { offset: start });
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), FormatError> {
+        if self.bytes.get(self.position) != Some(&byte) {
+            return Err(FormatError::Syntax { offset: self.position });
+        }
+        self.position += 1;
+
+        return Ok(());
+    }
+
+    /// Fails unless only whitespace is left.
+    fn end(&mut self) -> Result<(), FormatError> {
+        self.whitespace();
+        if self.position < self.bytes.len() {
+            return Err(FormatError::Syntax { offset: self.position });
+        }
+
+        return Ok(());
+    }
+
+    fn whitespace(&mut self) {
+        while self.bytes.get(self.position).is_some_and(u8::is_ascii_whitespace) {
+            self.position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::prelude::v1::*;
+    use crate::{Point, FormatError, DecodeError, to_wkt, from_wkt, encode_wkt, decode_to_wkt};
+
+    #[test]
+    fn roundtrip() {
+        let points = vec![Point::new(38.5, -120.2), Point::new(40.7, -120.95), Point::new(43.252, -126.453)];
+        assert_eq!(from_wkt(&to_wkt(&points, 5)), Ok(points));
+        assert_eq!(to_wkt(&[], 5), "LINESTRING EMPTY");
+        assert_eq!(from_wkt("LINESTRING EMPTY"), Ok(vec![]));
+    }
+
+    #[test]
+    fn postgis_output() {
+        let expected = vec![Point::new(48.2, 16.37), Point::new(48.21, 16.38)];
+        assert_eq!(from_wkt("SRID=4326;LINESTRING(16.37 48.2,16.38 48.21)"), Ok(expected));
+        assert_eq!(from_wkt(" linestring z ( 16.37 48.2 171.5 , 16.38 48.21 180 ) ").map(|points| points.len()), Ok(2));
+        assert_eq!(from_wkt("LINESTRING ZM (1e1 -2.5 3 4)"), Ok(vec![Point::new(-2.5, 10.)]));
+        assert_eq!(from_wkt("LineString Z EMPTY"), Ok(vec![]));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(from_wkt(""), Err(FormatError::Syntax { offset: 0 }));
+        assert_eq!(from_wkt("POINT(1 2)"), Err(FormatError::NotALineString));
+        assert_eq!(from_wkt("LINESTRING(1 2,3 4"), Err(FormatError::Syntax { offset: 18 }));
+        assert_eq!(from_wkt("LINESTRING(1 2,3)"), Err(FormatError::InvalidCoordinate { index: 1 }));
+        assert_eq!(from_wkt("LINESTRING(1 2,181 4)"), Err(FormatError::InvalidCoordinate { index: 1 }));
+        assert_eq!(from_wkt("LINESTRING(1 2) x"), Err(FormatError::Syntax { offset: 16 }));
+        assert_eq!(from_wkt("LINESTRING(1 -)"), Err(FormatError::Syntax { offset: 13 }));
+    }
+
+    #[test]
+    fn polylines() {
+        assert_eq!(encode_wkt("LINESTRING(-120.2 38.5,-120.95 40.7)", 5), Ok(String::from("_p~iF~ps|U_ulLnnqC")));
+        assert_eq!(decode_to_wkt("", 5), Ok(String::from("LINESTRING EMPTY")));
+        assert_eq!(decode_to_wkt("_p~iF", 5), Err(DecodeError::DanglingCoordinate { offset: 0 }));
+    }
+}
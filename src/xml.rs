@@ -0,0 +1,132 @@
+use alloc::vec::Vec;
+
+use crate::FormatError;
+
+/// Finds the next start or end tag at or after `position`.
+///
+/// Comments, CDATA sections, processing instructions and doctypes are skipped. This is a
+/// minimal scanner for the XML based formats, it neither checks nesting nor decodes entities.
+pub(crate) fn next_tag(xml: &str, mut position: usize) -> Result<Option<Tag<'_>>, FormatError> {
+    'tags: while let Some(start) = xml[position..].find('<').map(|start| position + start) {
+        let rest = &xml[start..];
+        for (open, close) in [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>"), ("<!", ">")].iter() {
+            if rest.starts_with(open) {
+                position = rest.find(close).map(|end| start + end + close.len())
+                    .ok_or(FormatError::Syntax { offset: start })?;
+                continue 'tags;
+            }
+        }
+
+        return Tag::parse(xml, start).map(Some);
+    }
+
+    return Ok(None);
+}
+
+/// Start or end tag of an element.
+pub(crate) struct Tag<'a> {
+    /// local name without namespace prefix
+    pub(crate) name: &'a str,
+    // only the GPX reader needs attributes so far
+    #[cfg_attr(not(feature = "gpx"), allow(dead_code))]
+    pub(crate) attributes: Vec<(&'a str, &'a str)>,
+    pub(crate) closing: bool,
+    pub(crate) self_closing: bool,
+    /// byte offset of the `<`
+    pub(crate) start: usize,
+    /// byte offset after the tag
+    pub(crate) end: usize
+}
+
+impl<'a> Tag<'a> {
+    /// Parses the tag starting with the `<` at `start`.
+    fn parse(xml: &'a str, start: usize) -> Result<Tag<'a>, FormatError> {
+        let bytes = xml.as_bytes();
+        let mut position = start + 1;
+        let closing = bytes.get(position) == Some(&b'/');
+        if closing {
+            position += 1;
+        }
+
+        let name_start = position;
+        while position < bytes.len() && !bytes[position].is_ascii_whitespace() && bytes[position] != b'>' && bytes[position] != b'/' {
+            position += 1;
+        }
+        let qualified_name = &xml[name_start..position];
+        if qualified_name.is_empty() {
+            return Err(FormatError::Syntax { offset: start });
+        }
+        let name = qualified_name.rsplit(':').next().unwrap_or(qualified_name);
+
+        let mut attributes: Vec<(&str, &str)> = Vec::new();
+        loop {
+            while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+                position += 1;
+            }
+            match bytes.get(position) {
+                Some(b'>') => return Ok(Tag { name, attributes, closing, self_closing: false, start, end: position + 1 }),
+                Some(b'/') if bytes.get(position + 1) == Some(&b'>') => {
+                    return Ok(Tag { name, attributes, closing, self_closing: true, start, end: position + 2 });
+                },
+                None => return Err(FormatError::Syntax { offset: start }),
+                _ => {}
+            }
+
+            let attribute_start = position;
+            let equals = xml[position..].find(['=', '>'])
+                .map(|equals| position + equals)
+                .filter(|equals| bytes[*equals] == b'=')
+                .ok_or(FormatError::Syntax { offset: attribute_start })?;
+            let attribute = xml[attribute_start..equals].trim();
+            position = equals + 1;
+            while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+                position += 1;
+            }
+
+            let quote = match bytes.get(position) {
+                Some(quote @ b'"') | Some(quote @ b'\'') => *quote as char,
+                _ => return Err(FormatError::Syntax { offset: position })
+            };
+            let value_end = xml[position + 1..].find(quote).map(|end| position + 1 + end)
+                .ok_or(FormatError::Syntax { offset: position })?;
+            attributes.push((attribute, &xml[position + 1..value_end]));
+            position = value_end + 1;
+        }
+    }
+
+    /// Value of the attribute `name` as number.
+    #[cfg_attr(not(feature = "gpx"), allow(dead_code))]
+    pub(crate) fn number(&self, name: &str) -> Option<f64> {
+        let (_, value) = self.attributes.iter().find(|(attribute, _)| *attribute == name)?;
+        return value.trim().parse::<f64>().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FormatError;
+    use crate::xml::next_tag;
+
+    #[test]
+    fn tags() {
+        let xml = "<?xml version='1.0'?><!-- <a> --><kml:a x = \"1\" y='2'>text<![CDATA[<b>]]></kml:a><c/>";
+        let a = next_tag(xml, 0).unwrap().unwrap();
+        assert_eq!((a.name, a.closing, a.self_closing), ("a", false, false));
+        assert_eq!(a.attributes, vec![("x", "1"), ("y", "2")]);
+        assert_eq!(a.number("y"), Some(2.));
+
+        let end = next_tag(xml, a.end).unwrap().unwrap();
+        assert_eq!((end.name, end.closing, &xml[a.end..end.start]), ("a", true, "text<![CDATA[<b>]]>"));
+        let c = next_tag(xml, end.end).unwrap().unwrap();
+        assert_eq!((c.name, c.self_closing), ("c", true));
+        assert!(next_tag(xml, c.end).unwrap().is_none());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(next_tag("<a x=1>", 0).err(), Some(FormatError::Syntax { offset: 5 }));
+        assert_eq!(next_tag("<a", 0).err(), Some(FormatError::Syntax { offset: 0 }));
+        assert_eq!(next_tag("<!-- ", 0).err(), Some(FormatError::Syntax { offset: 0 }));
+        assert_eq!(next_tag("<a b><c d='1'>", 0).err(), Some(FormatError::Syntax { offset: 3 }));
+    }
+}